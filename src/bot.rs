@@ -0,0 +1,686 @@
+/*!
+Bot de chat com os cardápios (`bandex bot`)
+
+Este módulo responde os comandos enviados ao bot no Telegram ou no Matrix com os cardápios dos
+restaurantes das configurações:
+- `/hoje` e `/amanha`: Cardápios do dia, que podem ser filtrados pela refeição e pelo
+  restaurante, como em `/amanha jantar` ou `/hoje central`
+- `/<restaurante>`: Cardápios de um restaurante, procurado pelo nome ou pelo ID, como em
+  `/central` ou `/fisica amanha almoco`
+- `/assinar [HH:MM]`: Assina o envio diário dos cardápios do dia na conversa, por padrão às 10:00
+- `/cancelar`: Cancela o envio diário
+- `/ajuda`: Mostra os comandos
+
+Os comandos também podem começar com `!`, já que os clientes do Matrix tratam os comandos com
+`/`. As assinaturas ficam em um banco de dados SQLite local, em
+`$XDG_DATA_HOME/bandex/bot.sqlite3` ou no arquivo da variável de ambiente `BANDEX_BOT_FILE`.
+*/
+
+use std::{
+    env,
+    path::{Path, PathBuf},
+};
+
+use chrono::{Datelike, Days, NaiveDate, NaiveDateTime, NaiveTime};
+use rusqlite::{params, Connection};
+
+use crate::{
+    bot_api::BotPlatform,
+    config::{self, Config},
+    display::WEEKDAY_NAMES,
+    text,
+    types::{Menu, MenuType, MenusCache, RestaurantID},
+};
+
+/// Variável de ambiente para o arquivo das assinaturas do bot.
+const ENV_VAR_BOT_FILE: &str = "BANDEX_BOT_FILE";
+
+/// Variável de ambiente com o diretório de dados do usuário (especificação XDG).
+const ENV_VAR_XDG_DATA_HOME: &str = "XDG_DATA_HOME";
+
+/// Arquivo das assinaturas dentro do diretório de dados do usuário.
+const XDG_BOT_FILE: &str = "bandex/bot.sqlite3";
+
+/// Formato das datas guardadas no banco (ISO 8601).
+const DATE_FORMAT: &str = "%Y-%m-%d";
+
+/// Formato dos horários das assinaturas.
+const TIME_FORMAT: &str = "%H:%M";
+
+/// Horário padrão do envio diário.
+const DEFAULT_PUSH_TIME: &str = "10:00";
+
+/// Cria a tabela das assinaturas, se ela ainda não existir.
+const CREATE_TABLE: &str = "
+CREATE TABLE IF NOT EXISTS subscriptions (
+    platform TEXT NOT NULL,
+    chat_id TEXT NOT NULL,
+    time TEXT NOT NULL,
+    last_sent TEXT,
+    PRIMARY KEY (platform, chat_id)
+)";
+
+/// Ajuda do bot, enviada por `/ajuda`.
+const HELP: &str = "Comandos do Bandex:
+/hoje [almoco|jantar] [restaurante]: cardápios de hoje
+/amanha [almoco|jantar] [restaurante]: cardápios de amanhã
+/<restaurante> [hoje|amanha] [almoco|jantar]: cardápios de um restaurante, como /central
+/assinar [HH:MM]: recebe os cardápios do dia todos os dias, por padrão às 10:00
+/cancelar: cancela o envio diário
+/ajuda: mostra esta mensagem";
+
+/// Arquivo das assinaturas, definido pela variável de ambiente `BANDEX_BOT_FILE` ou no
+/// diretório de dados do usuário.
+pub fn bot_file() -> Option<PathBuf> {
+    if let Some(path) = env::var_os(ENV_VAR_BOT_FILE).filter(|path| !path.is_empty()) {
+        return Some(PathBuf::from(path));
+    }
+
+    config::read_env_dir(ENV_VAR_XDG_DATA_HOME)
+        .or_else(|| env::var_os("HOME").map(|home| PathBuf::from(home).join(".local/share")))
+        .map(|dir| dir.join(XDG_BOT_FILE))
+}
+
+/// Dia consultado, relativo à data atual.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum BotDay {
+    Today,
+    Tomorrow,
+}
+
+/// Comando enviado ao bot.
+#[derive(Debug, Clone, PartialEq)]
+pub enum BotCommand {
+    /// Cardápios de um dia, opcionalmente de uma refeição e de um restaurante.
+    Menus {
+        day: BotDay,
+        menu_type: Option<MenuType>,
+        restaurant: Option<String>,
+    },
+
+    /// Assina o envio diário dos cardápios no horário.
+    Subscribe(NaiveTime),
+
+    /// Cancela o envio diário.
+    Unsubscribe,
+
+    Help,
+}
+
+impl BotCommand {
+    /// Lê o comando da mensagem `message`. Retorna `None` se a mensagem não for um comando e
+    /// um erro, com a mensagem de resposta, se o comando for inválido.
+    pub fn parse(message: &str) -> Option<Result<BotCommand, String>> {
+        let message = message.trim();
+        let command = message
+            .strip_prefix('/')
+            .or_else(|| message.strip_prefix('!'))?;
+
+        let mut words = command.split_whitespace();
+        // Nos grupos do Telegram, os comandos podem vir com o nome do bot ("/hoje@bandex_bot")
+        let name = words
+            .next()
+            .unwrap_or_default()
+            .split('@')
+            .next()
+            .unwrap_or_default();
+        let name = text::normalize(name);
+        let args: Vec<&str> = words.collect();
+
+        Some(match name.as_str() {
+            "ajuda" | "help" | "start" => Ok(BotCommand::Help),
+            "cancelar" => Ok(BotCommand::Unsubscribe),
+            "assinar" => match args[..] {
+                [] => Ok(BotCommand::Subscribe(
+                    NaiveTime::parse_from_str(DEFAULT_PUSH_TIME, TIME_FORMAT)
+                        .expect("horário padrão válido"),
+                )),
+                [time] => NaiveTime::parse_from_str(time, TIME_FORMAT)
+                    .map(BotCommand::Subscribe)
+                    .map_err(|_| format!("Horário inválido: {time} (use HH:MM, como 10:30)")),
+                _ => Err("Use /assinar [HH:MM], como /assinar 10:30".to_string()),
+            },
+            "hoje" => Self::parse_menus(BotDay::Today, None, &args),
+            "amanha" => Self::parse_menus(BotDay::Tomorrow, None, &args),
+            "" => Err("Comando vazio. Envie /ajuda para ver os comandos".to_string()),
+            restaurant => Self::parse_menus(BotDay::Today, Some(restaurant.to_string()), &args),
+        })
+    }
+
+    /// Lê os argumentos de um comando de cardápios: o dia, a refeição e o restaurante.
+    fn parse_menus(
+        mut day: BotDay,
+        mut restaurant: Option<String>,
+        args: &[&str],
+    ) -> Result<BotCommand, String> {
+        let mut menu_type = None;
+        for arg in args {
+            match text::normalize(arg).as_str() {
+                "hoje" => day = BotDay::Today,
+                "amanha" => day = BotDay::Tomorrow,
+                "almoco" => menu_type = Some(MenuType::Lunch),
+                "jantar" | "janta" => menu_type = Some(MenuType::Dinner),
+                word if restaurant.is_none() && !word.is_empty() => {
+                    restaurant = Some(word.to_string())
+                }
+                _ => {
+                    return Err(format!(
+                        "Não entendi \"{arg}\". Envie /ajuda para ver os comandos"
+                    ))
+                }
+            }
+        }
+
+        Ok(BotCommand::Menus {
+            day,
+            menu_type,
+            restaurant,
+        })
+    }
+}
+
+/// Assinaturas do envio diário dos cardápios, guardadas em um banco SQLite.
+pub struct Subscriptions {
+    connection: Connection,
+    platform: BotPlatform,
+}
+
+impl Subscriptions {
+    /// Abre (ou cria) as assinaturas da plataforma `platform` no arquivo `path`, criando também
+    /// os diretórios do arquivo.
+    pub fn open(path: &Path, platform: BotPlatform) -> rusqlite::Result<Subscriptions> {
+        if let Some(dir) = path.parent() {
+            // Se não for possível criar o diretório, o erro aparece ao abrir o banco
+            let _ = std::fs::create_dir_all(dir);
+        }
+        Self::from_connection(Connection::open(path)?, platform)
+    }
+
+    /// Cria assinaturas apenas na memória, para os testes.
+    #[cfg(test)]
+    pub fn open_in_memory(platform: BotPlatform) -> rusqlite::Result<Subscriptions> {
+        Self::from_connection(Connection::open_in_memory()?, platform)
+    }
+
+    fn from_connection(
+        connection: Connection,
+        platform: BotPlatform,
+    ) -> rusqlite::Result<Subscriptions> {
+        connection.execute(CREATE_TABLE, [])?;
+        Ok(Subscriptions {
+            connection,
+            platform,
+        })
+    }
+
+    /// Assina o envio diário na conversa `chat_id` no horário `time`, substituindo o horário
+    /// anterior. Os cardápios de hoje são enviados se o novo horário já tiver passado.
+    pub fn subscribe(&self, chat_id: &str, time: NaiveTime) -> rusqlite::Result<()> {
+        self.connection.execute(
+            "INSERT INTO subscriptions (platform, chat_id, time, last_sent)
+             VALUES (?1, ?2, ?3, NULL)
+             ON CONFLICT (platform, chat_id) DO UPDATE SET time = excluded.time",
+            params![
+                self.platform.to_string(),
+                chat_id,
+                time.format(TIME_FORMAT).to_string()
+            ],
+        )?;
+        Ok(())
+    }
+
+    /// Cancela a assinatura da conversa `chat_id`, retornando se ela existia.
+    pub fn unsubscribe(&self, chat_id: &str) -> rusqlite::Result<bool> {
+        let removed = self.connection.execute(
+            "DELETE FROM subscriptions WHERE platform = ?1 AND chat_id = ?2",
+            params![self.platform.to_string(), chat_id],
+        )?;
+        Ok(removed > 0)
+    }
+
+    /// Conversas em que o envio diário já passou do horário em `now` e ainda não foi feito.
+    pub fn due(&self, now: NaiveDateTime) -> rusqlite::Result<Vec<String>> {
+        let mut statement = self.connection.prepare(
+            "SELECT chat_id FROM subscriptions
+             WHERE platform = ?1 AND time <= ?2 AND (last_sent IS NULL OR last_sent < ?3)
+             ORDER BY time, chat_id",
+        )?;
+        let chats = statement
+            .query_map(
+                params![
+                    self.platform.to_string(),
+                    now.time().format(TIME_FORMAT).to_string(),
+                    now.date().format(DATE_FORMAT).to_string()
+                ],
+                |row| row.get(0),
+            )?
+            .collect();
+        chats
+    }
+
+    /// Marca o envio diário da conversa `chat_id` como feito na data `date`.
+    pub fn mark_sent(&self, chat_id: &str, date: NaiveDate) -> rusqlite::Result<()> {
+        self.connection.execute(
+            "UPDATE subscriptions SET last_sent = ?3 WHERE platform = ?1 AND chat_id = ?2",
+            params![
+                self.platform.to_string(),
+                chat_id,
+                date.format(DATE_FORMAT).to_string()
+            ],
+        )?;
+        Ok(())
+    }
+}
+
+/// Cardápio de um restaurante em uma resposta do bot.
+struct RestaurantMenu {
+    name: String,
+    menu: Menu,
+}
+
+/// Bot com os cardápios dos restaurantes das configurações.
+pub struct Bot<'a> {
+    menus_cache: MenusCache,
+    config: &'a Config,
+    subscriptions: Subscriptions,
+}
+
+impl<'a> Bot<'a> {
+    pub fn new(menus_cache: MenusCache, config: &'a Config, subscriptions: Subscriptions) -> Self {
+        Bot {
+            menus_cache,
+            config,
+            subscriptions,
+        }
+    }
+
+    /// Resposta para a mensagem `message` enviada na conversa `chat_id` em `now`, ou `None`
+    /// se a mensagem não for um comando.
+    pub async fn reply(
+        &mut self,
+        chat_id: &str,
+        message: &str,
+        now: NaiveDateTime,
+    ) -> Option<String> {
+        let command = match BotCommand::parse(message)? {
+            Ok(command) => command,
+            Err(error) => return Some(error),
+        };
+
+        Some(match command {
+            BotCommand::Help => HELP.to_string(),
+            BotCommand::Subscribe(time) => match self.subscriptions.subscribe(chat_id, time) {
+                Ok(()) => format!(
+                    "Pronto! Os cardápios do dia serão enviados aqui todos os dias às {}",
+                    time.format(TIME_FORMAT)
+                ),
+                Err(error) => format!("Não foi possível guardar a assinatura: {error}"),
+            },
+            BotCommand::Unsubscribe => match self.subscriptions.unsubscribe(chat_id) {
+                Ok(true) => "Envio diário cancelado".to_string(),
+                Ok(false) => "Não há envio diário nesta conversa".to_string(),
+                Err(error) => format!("Não foi possível cancelar a assinatura: {error}"),
+            },
+            BotCommand::Menus {
+                day,
+                menu_type,
+                restaurant,
+            } => {
+                let date = match day {
+                    BotDay::Today => now.date(),
+                    BotDay::Tomorrow => now.date() + Days::new(1),
+                };
+                self.menus_reply(date, menu_type, restaurant.as_deref())
+                    .await
+            }
+        })
+    }
+
+    /// Envios diários que devem ser feitos em `now`, como pares de conversa e mensagem. Os
+    /// envios são marcados como feitos, e os dias sem nenhum restaurante aberto são pulados.
+    pub async fn due_pushes(
+        &mut self,
+        now: NaiveDateTime,
+    ) -> Result<Vec<(String, String)>, String> {
+        let chats = self
+            .subscriptions
+            .due(now)
+            .map_err(|error| format!("Não foi possível consultar as assinaturas: {error}"))?;
+        if chats.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let menus = self.find_menus(now.date(), None, None).await;
+        let open = menus.iter().any(|menu| !is_closed(&menu.menu));
+        let message = render_menus(now.date(), &menus);
+
+        let mut pushes = Vec::new();
+        for chat_id in chats {
+            self.subscriptions
+                .mark_sent(&chat_id, now.date())
+                .map_err(|error| format!("Não foi possível atualizar as assinaturas: {error}"))?;
+            if open {
+                pushes.push((chat_id, message.clone()));
+            }
+        }
+        Ok(pushes)
+    }
+
+    /// Resposta com os cardápios da data `date`, da refeição e do restaurante escolhidos.
+    async fn menus_reply(
+        &mut self,
+        date: NaiveDate,
+        menu_type: Option<MenuType>,
+        restaurant: Option<&str>,
+    ) -> String {
+        let restaurant_id = match restaurant {
+            Some(term) => match self.find_restaurant(term).await {
+                Some(id) => Some(id),
+                None => return format!("Restaurante não encontrado: {term}"),
+            },
+            None => None,
+        };
+
+        let menus = self.find_menus(date, menu_type, restaurant_id).await;
+        if menus.is_empty() {
+            return format!(
+                "Nenhum cardápio encontrado para {} ({})",
+                WEEKDAY_NAMES[date.weekday().num_days_from_monday() as usize],
+                date.format("%d/%m/%Y")
+            );
+        }
+        render_menus(date, &menus)
+    }
+
+    /// Procura o restaurante das configurações pelo ID ou pelas palavras do nome.
+    async fn find_restaurant(&mut self, term: &str) -> Option<RestaurantID> {
+        let term = text::normalize(term);
+        let words: Vec<&str> = term.split(' ').collect();
+
+        for restaurant in &self.config.restaurants {
+            if term == restaurant.id.to_string() {
+                return Some(restaurant.id);
+            }
+            let Some((name, _)) = self.menus_cache.get_name_and_menus(restaurant.id).await else {
+                continue;
+            };
+            let name = text::normalize(&name);
+            let name_words: Vec<&str> = name.split(' ').collect();
+            if words.iter().all(|word| name_words.contains(word)) {
+                return Some(restaurant.id);
+            }
+        }
+        None
+    }
+
+    /// Cardápios da data `date`, ordenados pela refeição e pela ordem das configurações.
+    async fn find_menus(
+        &mut self,
+        date: NaiveDate,
+        menu_type: Option<MenuType>,
+        restaurant_id: Option<RestaurantID>,
+    ) -> Vec<RestaurantMenu> {
+        let menu_types = menu_type.map_or(vec![MenuType::Lunch, MenuType::Dinner], |menu_type| {
+            vec![menu_type]
+        });
+
+        let mut menus = Vec::new();
+        for menu_type in &menu_types {
+            for restaurant in &self.config.restaurants {
+                if restaurant_id.is_some_and(|id| id != restaurant.id) {
+                    continue;
+                }
+                let Some((name, menu)) = self
+                    .menus_cache
+                    .get_name_and_menu(restaurant.id, menu_type, date.weekday())
+                    .await
+                else {
+                    continue;
+                };
+                // Os cardápios carregados são da semana atual, então o cardápio do mesmo dia
+                // da semana pode ser de outra data
+                if menu.date.is_some_and(|menu_date| menu_date != date) {
+                    continue;
+                }
+                menus.push(RestaurantMenu { name, menu });
+            }
+        }
+        menus
+    }
+}
+
+/// Se o restaurante está fechado na refeição.
+fn is_closed(menu: &Menu) -> bool {
+    menu.content.trim() == "Fechado"
+}
+
+/// Mensagem com os cardápios `menus` da data `date`, agrupados pela refeição.
+fn render_menus(date: NaiveDate, menus: &[RestaurantMenu]) -> String {
+    let weekday_name = WEEKDAY_NAMES[date.weekday().num_days_from_monday() as usize];
+    let mut message = String::new();
+    let mut current_type = None;
+
+    for RestaurantMenu { name, menu } in menus {
+        if current_type != Some(&menu.menu_type) {
+            if current_type.is_some() {
+                message.push('\n');
+            }
+            message.push_str(&format!(
+                "🍽 {} de {weekday_name} ({})\n",
+                menu.menu_type,
+                date.format("%d/%m/%Y")
+            ));
+            current_type = Some(&menu.menu_type);
+        }
+
+        message.push_str(&format!("\n{name}\n"));
+        if is_closed(menu) {
+            message.push_str("Fechado\n");
+            continue;
+        }
+        for line in menu.content.split('\n').map(str::trim) {
+            if !line.is_empty() {
+                message.push_str(&format!("• {line}\n"));
+            }
+        }
+        if let Some(calorific_value) = menu.calorific_value {
+            message.push_str(&format!("{calorific_value} kcal\n"));
+        }
+    }
+    message.trim_end().to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::RestaurantConfig;
+    use colored::Color;
+
+    fn time(hour: u32, minute: u32) -> NaiveTime {
+        NaiveTime::from_hms_opt(hour, minute, 0).unwrap()
+    }
+
+    /// 06/03/2025, uma quinta-feira, no horário `hour:minute`.
+    fn now(hour: u32, minute: u32) -> NaiveDateTime {
+        NaiveDate::from_ymd_opt(2025, 3, 6)
+            .unwrap()
+            .and_time(time(hour, minute))
+    }
+
+    fn menu(day: u32, menu_type: MenuType, content: &str) -> Menu {
+        let date = NaiveDate::from_ymd_opt(2025, 3, day).unwrap();
+        Menu {
+            content: content.to_string(),
+            menu_type,
+            weekday: date.weekday(),
+            date: Some(date),
+            calorific_value: Some(1030),
+            observation: String::new(),
+        }
+    }
+
+    fn bot(config: &Config) -> Bot<'_> {
+        let mut menus_cache = MenusCache::new();
+        menus_cache.insert(
+            6,
+            "Restaurante Central",
+            vec![
+                menu(6, MenuType::Lunch, "Arroz\nStrogonoff\n"),
+                menu(6, MenuType::Dinner, "Fechado"),
+                menu(7, MenuType::Lunch, "Peixe"),
+            ],
+        );
+        menus_cache.insert(
+            8,
+            "Restaurante da Física",
+            vec![menu(6, MenuType::Lunch, "Feijoada")],
+        );
+        let subscriptions = Subscriptions::open_in_memory(BotPlatform::Telegram).unwrap();
+        Bot::new(menus_cache, config, subscriptions)
+    }
+
+    fn config() -> Config {
+        Config {
+            restaurants: vec![
+                RestaurantConfig::new(6, Color::Yellow),
+                RestaurantConfig::new(8, Color::Blue),
+            ],
+            ..Config::default()
+        }
+    }
+
+    #[test]
+    fn test_parse_command() {
+        let menus = |day, menu_type, restaurant: Option<&str>| {
+            Some(Ok(BotCommand::Menus {
+                day,
+                menu_type,
+                restaurant: restaurant.map(str::to_string),
+            }))
+        };
+
+        assert_eq!(BotCommand::parse("/hoje"), menus(BotDay::Today, None, None));
+        assert_eq!(
+            BotCommand::parse("/amanhã Jantar"),
+            menus(BotDay::Tomorrow, Some(MenuType::Dinner), None)
+        );
+        assert_eq!(
+            BotCommand::parse("/hoje@bandex_bot almoço física"),
+            menus(BotDay::Today, Some(MenuType::Lunch), Some("fisica"))
+        );
+        assert_eq!(
+            BotCommand::parse("!central amanha"),
+            menus(BotDay::Tomorrow, None, Some("central"))
+        );
+        assert_eq!(
+            BotCommand::parse("/assinar 9:30"),
+            Some(Ok(BotCommand::Subscribe(time(9, 30))))
+        );
+        assert_eq!(
+            BotCommand::parse("/assinar"),
+            Some(Ok(BotCommand::Subscribe(time(10, 0))))
+        );
+        assert_eq!(
+            BotCommand::parse("/cancelar"),
+            Some(Ok(BotCommand::Unsubscribe))
+        );
+        assert_eq!(BotCommand::parse("/start"), Some(Ok(BotCommand::Help)));
+
+        assert!(BotCommand::parse("/assinar 25:00").unwrap().is_err());
+        assert!(BotCommand::parse("/hoje central fisica").unwrap().is_err());
+        assert!(BotCommand::parse("/").unwrap().is_err());
+        assert_eq!(BotCommand::parse("bom dia"), None);
+    }
+
+    #[test]
+    fn test_subscriptions() {
+        let subscriptions = Subscriptions::open_in_memory(BotPlatform::Matrix).unwrap();
+        subscriptions
+            .subscribe("!a:example.org", time(9, 0))
+            .unwrap();
+        subscriptions
+            .subscribe("!b:example.org", time(12, 0))
+            .unwrap();
+
+        assert_eq!(subscriptions.due(now(8, 59)).unwrap(), Vec::<String>::new());
+        assert_eq!(
+            subscriptions.due(now(9, 0)).unwrap(),
+            vec!["!a:example.org"]
+        );
+
+        subscriptions
+            .mark_sent("!a:example.org", now(9, 0).date())
+            .unwrap();
+        assert!(subscriptions.due(now(11, 0)).unwrap().is_empty());
+        assert_eq!(
+            subscriptions.due(now(12, 0)).unwrap(),
+            vec!["!b:example.org"]
+        );
+
+        assert!(subscriptions.unsubscribe("!b:example.org").unwrap());
+        assert!(!subscriptions.unsubscribe("!b:example.org").unwrap());
+        assert!(subscriptions.due(now(23, 0)).unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_reply() {
+        let config = config();
+        let mut bot = bot(&config);
+
+        assert_eq!(bot.reply("1", "bom dia", now(9, 0)).await, None);
+        assert_eq!(
+            bot.reply("1", "/hoje almoco", now(9, 0)).await.unwrap(),
+            "🍽 Almoço de Quinta-feira (06/03/2025)\n\
+             \nRestaurante Central\n• Arroz\n• Strogonoff\n1030 kcal\n\
+             \nRestaurante da Física\n• Feijoada\n1030 kcal"
+        );
+        assert_eq!(
+            bot.reply("1", "/central", now(9, 0)).await.unwrap(),
+            "🍽 Almoço de Quinta-feira (06/03/2025)\n\
+             \nRestaurante Central\n• Arroz\n• Strogonoff\n1030 kcal\n\
+             \n🍽 Jantar de Quinta-feira (06/03/2025)\n\
+             \nRestaurante Central\nFechado"
+        );
+        assert!(bot
+            .reply("1", "/amanha", now(9, 0))
+            .await
+            .unwrap()
+            .contains("• Peixe"));
+        // O cardápio de sábado não foi carregado
+        assert_eq!(
+            bot.reply("1", "/amanha", now(9, 0) + Days::new(1))
+                .await
+                .unwrap(),
+            "Nenhum cardápio encontrado para Sábado (08/03/2025)"
+        );
+        assert_eq!(
+            bot.reply("1", "/quimica", now(9, 0)).await.unwrap(),
+            "Restaurante não encontrado: quimica"
+        );
+        assert!(bot
+            .reply("1", "/8 almoco", now(9, 0))
+            .await
+            .unwrap()
+            .contains("Feijoada"));
+    }
+
+    #[tokio::test]
+    async fn test_due_pushes() {
+        let config = config();
+        let mut bot = bot(&config);
+
+        bot.reply("1", "/assinar 10:30", now(9, 0)).await.unwrap();
+        assert!(bot.due_pushes(now(10, 0)).await.unwrap().is_empty());
+
+        let pushes = bot.due_pushes(now(10, 30)).await.unwrap();
+        assert_eq!(pushes.len(), 1);
+        assert_eq!(pushes[0].0, "1");
+        assert!(pushes[0].1.contains("Strogonoff"));
+        assert!(bot.due_pushes(now(11, 0)).await.unwrap().is_empty());
+
+        assert_eq!(
+            bot.reply("1", "/cancelar", now(12, 0)).await.unwrap(),
+            "Envio diário cancelado"
+        );
+    }
+}
@@ -0,0 +1,530 @@
+/*!
+Clientes das APIs de bots do Telegram e do Matrix
+
+Este módulo recebe as mensagens enviadas ao bot e envia as respostas, escondendo as
+diferenças entre as plataformas:
+- No Telegram, as mensagens são recebidas por long polling (`getUpdates`) e enviadas por
+  `sendMessage`, na Bot API em `https://api.telegram.org`
+- No Matrix, as mensagens são recebidas pela sincronização (`/sync`) e enviadas como eventos
+  `m.room.message` nas salas, na API cliente-servidor do homeserver. Os convites para salas
+  são aceitos, e as mensagens anteriores ao início do bot são ignoradas
+- O endereço base das APIs é configurável, para usar outro servidor (ou um servidor falso, nos
+  testes)
+*/
+
+use std::{
+    collections::HashMap,
+    fmt,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use reqwest::{header::CONTENT_TYPE, Method, Url};
+use serde::{de::DeserializeOwned, Deserialize};
+use serde_json::{json, Value};
+
+/// Endereço da Bot API do Telegram.
+const TELEGRAM_API_URL: &str = "https://api.telegram.org";
+
+/// Tempo padrão, em segundos, que cada consulta por mensagens novas espera por elas.
+const DEFAULT_POLL_TIMEOUT: u64 = 30;
+
+/// Plataforma de chat do bot.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum BotPlatform {
+    /// Bot API do Telegram.
+    Telegram,
+
+    /// API cliente-servidor do Matrix.
+    Matrix,
+}
+
+impl BotPlatform {
+    /// Endereço padrão da API da plataforma, se houver. No Matrix, cada homeserver tem o seu.
+    pub fn default_api_url(&self) -> Option<&'static str> {
+        match self {
+            BotPlatform::Telegram => Some(TELEGRAM_API_URL),
+            BotPlatform::Matrix => None,
+        }
+    }
+}
+
+impl fmt::Display for BotPlatform {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            BotPlatform::Telegram => "telegram",
+            BotPlatform::Matrix => "matrix",
+        })
+    }
+}
+
+/// Mensagem de texto recebida pelo bot.
+#[derive(Debug, Clone, PartialEq)]
+pub struct IncomingMessage {
+    /// Conversa em que a mensagem foi enviada: o ID do chat, no Telegram, ou da sala, no Matrix.
+    pub chat_id: String,
+
+    /// Texto da mensagem.
+    pub text: String,
+}
+
+/// Resposta da Bot API do Telegram.
+#[derive(Debug, Deserialize)]
+struct TelegramResponse<T> {
+    ok: bool,
+    result: Option<T>,
+    description: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct TelegramUpdate {
+    update_id: i64,
+    message: Option<TelegramMessage>,
+}
+
+#[derive(Debug, Deserialize)]
+struct TelegramMessage {
+    chat: TelegramChat,
+    text: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct TelegramChat {
+    id: i64,
+}
+
+/// Resposta da sincronização do Matrix, apenas com as partes usadas.
+#[derive(Debug, Deserialize)]
+struct MatrixSync {
+    next_batch: String,
+    #[serde(default)]
+    rooms: MatrixRooms,
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct MatrixRooms {
+    #[serde(default)]
+    join: HashMap<String, MatrixJoinedRoom>,
+    #[serde(default)]
+    invite: HashMap<String, Value>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct MatrixJoinedRoom {
+    #[serde(default)]
+    timeline: MatrixTimeline,
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct MatrixTimeline {
+    #[serde(default)]
+    events: Vec<MatrixEvent>,
+}
+
+#[derive(Debug, Deserialize)]
+struct MatrixEvent {
+    #[serde(rename = "type")]
+    event_type: String,
+    sender: String,
+    #[serde(default)]
+    content: Value,
+}
+
+#[derive(Debug, Deserialize)]
+struct MatrixWhoAmI {
+    user_id: String,
+}
+
+/// Cliente da API de bots de uma plataforma.
+pub struct BotApi {
+    platform: BotPlatform,
+    base_url: Url,
+    token: String,
+    client: reqwest::Client,
+    poll_timeout: u64,
+
+    /// Próxima atualização esperada do Telegram.
+    offset: Option<i64>,
+
+    /// Ponto da última sincronização do Matrix.
+    since: Option<String>,
+
+    /// Usuário do bot no Matrix, para ignorar as próprias mensagens.
+    user_id: Option<String>,
+
+    /// Prefixo e contador dos IDs de transação do Matrix, que devem ser únicos.
+    transaction_prefix: u128,
+    transactions: u64,
+}
+
+impl BotApi {
+    /// Cria o cliente da plataforma `platform`, com a API em `base_url` e o token `token`.
+    pub fn new(platform: BotPlatform, base_url: &str, token: String) -> Result<Self, String> {
+        let base_url = Url::parse(base_url)
+            .map_err(|error| format!("Endereço da API inválido ({base_url}): {error}"))?;
+        if base_url.cannot_be_a_base() {
+            return Err(format!("Endereço da API inválido: {base_url}"));
+        }
+
+        let transaction_prefix = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |duration| duration.as_millis());
+
+        Ok(BotApi {
+            platform,
+            base_url,
+            token,
+            client: reqwest::Client::new(),
+            poll_timeout: DEFAULT_POLL_TIMEOUT,
+            offset: None,
+            since: None,
+            user_id: None,
+            transaction_prefix,
+            transactions: 0,
+        })
+    }
+
+    /// Define quantos segundos cada consulta por mensagens novas espera por elas.
+    #[cfg(test)]
+    pub fn with_poll_timeout(mut self, poll_timeout: u64) -> Self {
+        self.poll_timeout = poll_timeout;
+        self
+    }
+
+    /// Endereço do caminho `segments` na API.
+    fn endpoint<'a>(&self, segments: impl IntoIterator<Item = &'a str>) -> Url {
+        let mut url = self.base_url.clone();
+        url.path_segments_mut()
+            .expect("o endereço base foi verificado")
+            .pop_if_empty()
+            .extend(segments);
+        url
+    }
+
+    /// Faz uma requisição à API, com o token no cabeçalho no Matrix, e lê a resposta em JSON.
+    async fn request<T: DeserializeOwned>(
+        &self,
+        method: Method,
+        url: Url,
+        body: Option<&Value>,
+    ) -> Result<T, String> {
+        let mut request = self.client.request(method, url);
+        if self.platform == BotPlatform::Matrix {
+            request = request.bearer_auth(&self.token);
+        }
+        if let Some(body) = body {
+            request = request
+                .header(CONTENT_TYPE, "application/json")
+                .body(body.to_string());
+        }
+
+        let response = request
+            .send()
+            .await
+            .map_err(|error| format!("Erro ao acessar a API do {}: {error}", self.platform))?;
+        let status = response.status();
+        let text = response
+            .text()
+            .await
+            .map_err(|error| format!("Erro ao ler a resposta da API: {error}"))?;
+
+        if self.platform == BotPlatform::Matrix && !status.is_success() {
+            return Err(format!(
+                "A API do matrix respondeu com {status}: {}",
+                text.trim()
+            ));
+        }
+        serde_json::from_str(&text)
+            .map_err(|error| format!("Resposta inválida da API do {}: {error}", self.platform))
+    }
+
+    /// Faz uma requisição à Bot API do Telegram, conferindo o campo `ok` da resposta.
+    async fn telegram<T: DeserializeOwned>(
+        &self,
+        method: &str,
+        url: Url,
+        body: Option<&Value>,
+    ) -> Result<T, String> {
+        let http_method = if body.is_some() {
+            Method::POST
+        } else {
+            Method::GET
+        };
+        let response: TelegramResponse<T> = self.request(http_method, url, body).await?;
+        match response {
+            TelegramResponse {
+                ok: true,
+                result: Some(result),
+                ..
+            } => Ok(result),
+            TelegramResponse { description, .. } => Err(format!(
+                "A API do telegram recusou {method}: {}",
+                description.unwrap_or_default()
+            )),
+        }
+    }
+
+    /// Espera e retorna as mensagens de texto novas enviadas ao bot.
+    pub async fn next_messages(&mut self) -> Result<Vec<IncomingMessage>, String> {
+        match self.platform {
+            BotPlatform::Telegram => self.telegram_messages().await,
+            BotPlatform::Matrix => self.matrix_messages().await,
+        }
+    }
+
+    async fn telegram_messages(&mut self) -> Result<Vec<IncomingMessage>, String> {
+        let method = format!("bot{}", self.token);
+        let mut url = self.endpoint([method.as_str(), "getUpdates"]);
+        url.query_pairs_mut()
+            .append_pair("timeout", &self.poll_timeout.to_string())
+            .append_pair("allowed_updates", "[\"message\"]");
+        if let Some(offset) = self.offset {
+            url.query_pairs_mut()
+                .append_pair("offset", &offset.to_string());
+        }
+
+        let updates: Vec<TelegramUpdate> = self.telegram("getUpdates", url, None).await?;
+        if let Some(last) = updates.last() {
+            self.offset = Some(last.update_id + 1);
+        }
+
+        Ok(updates
+            .into_iter()
+            .filter_map(|update| {
+                let message = update.message?;
+                Some(IncomingMessage {
+                    chat_id: message.chat.id.to_string(),
+                    text: message.text?,
+                })
+            })
+            .collect())
+    }
+
+    async fn matrix_messages(&mut self) -> Result<Vec<IncomingMessage>, String> {
+        if self.user_id.is_none() {
+            let url = self.endpoint(["_matrix", "client", "v3", "account", "whoami"]);
+            let whoami: MatrixWhoAmI = self.request(Method::GET, url, None).await?;
+            self.user_id = Some(whoami.user_id);
+        }
+
+        let mut url = self.endpoint(["_matrix", "client", "v3", "sync"]);
+        match &self.since {
+            Some(since) => url
+                .query_pairs_mut()
+                .append_pair("since", since)
+                .append_pair("timeout", &(self.poll_timeout * 1000).to_string()),
+            // A primeira sincronização apenas marca o ponto de partida, sem as mensagens antigas
+            None => url
+                .query_pairs_mut()
+                .append_pair("timeout", "0")
+                .append_pair("filter", "{\"room\":{\"timeline\":{\"limit\":1}}}"),
+        };
+
+        let sync: MatrixSync = self.request(Method::GET, url, None).await?;
+        let first_sync = self.since.is_none();
+        self.since = Some(sync.next_batch);
+
+        for room_id in sync.rooms.invite.keys() {
+            let url = self.endpoint(["_matrix", "client", "v3", "rooms", room_id, "join"]);
+            self.request::<Value>(Method::POST, url, Some(&json!({})))
+                .await?;
+        }
+        if first_sync {
+            return Ok(Vec::new());
+        }
+
+        let mut messages = Vec::new();
+        for (room_id, room) in sync.rooms.join {
+            for event in room.timeline.events {
+                if event.event_type != "m.room.message"
+                    || Some(&event.sender) == self.user_id.as_ref()
+                    || event.content["msgtype"] != "m.text"
+                {
+                    continue;
+                }
+                if let Some(text) = event.content["body"].as_str() {
+                    messages.push(IncomingMessage {
+                        chat_id: room_id.clone(),
+                        text: text.to_string(),
+                    });
+                }
+            }
+        }
+        Ok(messages)
+    }
+
+    /// Envia a mensagem de texto `text` para a conversa `chat_id`.
+    pub async fn send_message(&mut self, chat_id: &str, text: &str) -> Result<(), String> {
+        match self.platform {
+            BotPlatform::Telegram => {
+                let method = format!("bot{}", self.token);
+                let url = self.endpoint([method.as_str(), "sendMessage"]);
+                let body = json!({ "chat_id": chat_id, "text": text });
+                self.telegram::<Value>("sendMessage", url, Some(&body))
+                    .await?;
+            }
+            BotPlatform::Matrix => {
+                self.transactions += 1;
+                let transaction =
+                    format!("bandex-{}-{}", self.transaction_prefix, self.transactions);
+                let url = self.endpoint([
+                    "_matrix",
+                    "client",
+                    "v3",
+                    "rooms",
+                    chat_id,
+                    "send",
+                    "m.room.message",
+                    &transaction,
+                ]);
+                let body = json!({ "msgtype": "m.text", "body": text });
+                self.request::<Value>(Method::PUT, url, Some(&body)).await?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::thread;
+
+    /// Requisição recebida pelo servidor falso: método, endereço e corpo.
+    type Received = (String, String, String);
+
+    /// Inicia um servidor HTTP local que responde as requisições, uma a uma, com `responses`,
+    /// retornando o endereço do servidor e as requisições recebidas.
+    fn fake_server(responses: Vec<&'static str>) -> (String, thread::JoinHandle<Vec<Received>>) {
+        let server = tiny_http::Server::http("127.0.0.1:0").unwrap();
+        let url = format!("http://{}", server.server_addr().to_ip().unwrap());
+
+        let handle = thread::spawn(move || {
+            let mut received = Vec::new();
+            for response in responses {
+                let mut request = server.recv().unwrap();
+                let mut body = String::new();
+                request.as_reader().read_to_string(&mut body).unwrap();
+                received.push((
+                    request.method().to_string(),
+                    request.url().to_string(),
+                    body,
+                ));
+                request
+                    .respond(tiny_http::Response::from_string(response))
+                    .unwrap();
+            }
+            received
+        });
+        (url, handle)
+    }
+
+    #[tokio::test]
+    async fn test_telegram() {
+        let (url, handle) = fake_server(vec![
+            r#"{"ok": true, "result": [
+                {"update_id": 41, "message": {"chat": {"id": -100}, "text": "/hoje"}},
+                {"update_id": 42, "message": {"chat": {"id": 7}, "photo": []}}
+            ]}"#,
+            r#"{"ok": true, "result": []}"#,
+            r#"{"ok": true, "result": {"message_id": 1}}"#,
+            r#"{"ok": false, "description": "Bad Request: chat not found"}"#,
+        ]);
+        let mut api = BotApi::new(BotPlatform::Telegram, &url, "123:abc".to_string())
+            .unwrap()
+            .with_poll_timeout(0);
+
+        let messages = api.next_messages().await.unwrap();
+        assert_eq!(
+            messages,
+            vec![IncomingMessage {
+                chat_id: "-100".to_string(),
+                text: "/hoje".to_string(),
+            }]
+        );
+        assert!(api.next_messages().await.unwrap().is_empty());
+        api.send_message("-100", "Arroz").await.unwrap();
+        let error = api.send_message("8", "Arroz").await.unwrap_err();
+        assert!(error.contains("chat not found"), "{error}");
+
+        let received = handle.join().unwrap();
+        assert!(received[0]
+            .1
+            .starts_with("/bot123:abc/getUpdates?timeout=0&"));
+        assert!(received[1].1.ends_with("&offset=43"));
+        assert_eq!(received[2].0, "POST");
+        assert_eq!(received[2].1, "/bot123:abc/sendMessage");
+        assert_eq!(
+            serde_json::from_str::<Value>(&received[2].2).unwrap(),
+            json!({ "chat_id": "-100", "text": "Arroz" })
+        );
+    }
+
+    #[tokio::test]
+    async fn test_matrix() {
+        let (url, handle) = fake_server(vec![
+            r#"{"user_id": "@bandex:example.org"}"#,
+            r#"{"next_batch": "s1", "rooms": {
+                "join": {"!old:example.org": {"timeline": {"events": [
+                    {"type": "m.room.message", "sender": "@ana:example.org",
+                     "content": {"msgtype": "m.text", "body": "!hoje"}}
+                ]}}},
+                "invite": {"!new:example.org": {}}
+            }}"#,
+            r#"{"room_id": "!new:example.org"}"#,
+            r#"{"next_batch": "s2", "rooms": {"join": {"!new:example.org": {"timeline": {"events": [
+                {"type": "m.room.message", "sender": "@ana:example.org",
+                 "content": {"msgtype": "m.text", "body": "!central jantar"}},
+                {"type": "m.room.message", "sender": "@bandex:example.org",
+                 "content": {"msgtype": "m.text", "body": "Jantar"}},
+                {"type": "m.reaction", "sender": "@ana:example.org", "content": {}}
+            ]}}}}}"#,
+            r#"{"event_id": "$1"}"#,
+        ]);
+        let mut api = BotApi::new(BotPlatform::Matrix, &url, "secret".to_string())
+            .unwrap()
+            .with_poll_timeout(0);
+
+        // As mensagens anteriores ao início do bot são ignoradas
+        assert!(api.next_messages().await.unwrap().is_empty());
+        let messages = api.next_messages().await.unwrap();
+        assert_eq!(
+            messages,
+            vec![IncomingMessage {
+                chat_id: "!new:example.org".to_string(),
+                text: "!central jantar".to_string(),
+            }]
+        );
+        api.send_message("!new:example.org", "Arroz").await.unwrap();
+
+        let received = handle.join().unwrap();
+        assert_eq!(received[0].1, "/_matrix/client/v3/account/whoami");
+        assert!(received[1]
+            .1
+            .starts_with("/_matrix/client/v3/sync?timeout=0&"));
+        assert_eq!(received[2].0, "POST");
+        assert_eq!(
+            received[2].1,
+            "/_matrix/client/v3/rooms/!new:example.org/join"
+        );
+        assert_eq!(received[3].1, "/_matrix/client/v3/sync?since=s1&timeout=0");
+        assert_eq!(received[4].0, "PUT");
+        assert!(received[4]
+            .1
+            .starts_with("/_matrix/client/v3/rooms/!new:example.org/send/m.room.message/bandex-"));
+        assert_eq!(
+            serde_json::from_str::<Value>(&received[4].2).unwrap(),
+            json!({ "msgtype": "m.text", "body": "Arroz" })
+        );
+    }
+
+    #[test]
+    fn test_invalid_api_url() {
+        assert!(BotApi::new(BotPlatform::Telegram, "api.telegram.org", String::new()).is_err());
+        assert!(BotApi::new(
+            BotPlatform::Matrix,
+            "mailto:bandex@example.org",
+            String::new()
+        )
+        .is_err());
+    }
+}
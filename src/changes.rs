@@ -0,0 +1,198 @@
+/*!
+Mudanças nos cardápios
+
+Os cardápios da USP são "sujeitos a modificação", então um prato anunciado pode ser trocado
+depois da publicação. Este módulo compara o cardápio carregado com a primeira versão guardada
+no histórico para a mesma data, restaurante e refeição:
+- A comparação é feita linha a linha, ignorando as linhas vazias e os espaços nas pontas
+- As linhas removidas e adicionadas são encontradas pela maior subsequência comum das linhas
+- Apenas os cardápios com data são comparados, já que o histórico só guarda esses
+*/
+
+use chrono::{NaiveDate, Weekday};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    history::HistoryEntry,
+    types::{iso_weekday, Menu, MenuType, RestaurantID},
+};
+
+/// Linha da comparação entre duas versões de um cardápio, serializada como
+/// `{"removed": "Arroz"}`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DiffLine {
+    /// Linha presente nas duas versões.
+    Same(String),
+    /// Linha presente apenas na versão guardada.
+    Removed(String),
+    /// Linha presente apenas na versão carregada.
+    Added(String),
+}
+
+/// Linhas do cardápio, sem as linhas vazias e os espaços nas pontas.
+fn menu_lines(content: &str) -> Vec<&str> {
+    content
+        .split('\n')
+        .map(str::trim)
+        .filter(|line| !line.is_empty())
+        .collect()
+}
+
+/// Compara as linhas do cardápio antigo `old` com as do novo `new`.
+pub fn diff_lines(old: &str, new: &str) -> Vec<DiffLine> {
+    let old = menu_lines(old);
+    let new = menu_lines(new);
+
+    // lengths[i][j] é o tamanho da maior subsequência comum de old[i..] e new[j..]
+    let mut lengths = vec![vec![0usize; new.len() + 1]; old.len() + 1];
+    for i in (0..old.len()).rev() {
+        for j in (0..new.len()).rev() {
+            lengths[i][j] = if old[i] == new[j] {
+                lengths[i + 1][j + 1] + 1
+            } else {
+                lengths[i + 1][j].max(lengths[i][j + 1])
+            };
+        }
+    }
+
+    let mut lines = Vec::new();
+    let (mut i, mut j) = (0, 0);
+    while i < old.len() && j < new.len() {
+        if old[i] == new[j] {
+            lines.push(DiffLine::Same(old[i].to_string()));
+            i += 1;
+            j += 1;
+        } else if lengths[i + 1][j] >= lengths[i][j + 1] {
+            lines.push(DiffLine::Removed(old[i].to_string()));
+            i += 1;
+        } else {
+            lines.push(DiffLine::Added(new[j].to_string()));
+            j += 1;
+        }
+    }
+    lines.extend(
+        old[i..]
+            .iter()
+            .map(|line| DiffLine::Removed(line.to_string())),
+    );
+    lines.extend(
+        new[j..]
+            .iter()
+            .map(|line| DiffLine::Added(line.to_string())),
+    );
+    lines
+}
+
+/// Mudança em um cardápio desde a primeira versão guardada no histórico.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MenuChange {
+    /// ID do restaurante.
+    pub restaurant_id: RestaurantID,
+
+    /// Data do cardápio.
+    pub date: NaiveDate,
+
+    /// Dia da semana do cardápio.
+    #[serde(with = "iso_weekday")]
+    pub weekday: Weekday,
+
+    /// Refeição do cardápio.
+    pub menu_type: MenuType,
+
+    /// Comparação linha a linha entre a versão guardada e a carregada.
+    pub lines: Vec<DiffLine>,
+}
+
+impl MenuChange {
+    /// Compara o cardápio carregado `menu` com a versão guardada `stored`, ou retorna `None`
+    /// se nenhuma linha mudou.
+    pub fn new(stored: &HistoryEntry, menu: &Menu) -> Option<MenuChange> {
+        let lines = diff_lines(&stored.content, &menu.content);
+        if lines.iter().all(|line| matches!(line, DiffLine::Same(_))) {
+            return None;
+        }
+
+        Some(MenuChange {
+            restaurant_id: stored.restaurant_id,
+            date: stored.date,
+            weekday: menu.weekday,
+            menu_type: menu.menu_type.clone(),
+            lines,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Datelike;
+
+    #[test]
+    fn test_diff_lines() {
+        let lines = diff_lines(
+            "Arroz\nFeijão\nStrogonoff de frango\nSalada\n",
+            "Arroz\nFeijão \nPeixe empanado\nSalada\nPudim",
+        );
+        assert_eq!(
+            lines,
+            vec![
+                DiffLine::Same("Arroz".to_string()),
+                DiffLine::Same("Feijão".to_string()),
+                DiffLine::Removed("Strogonoff de frango".to_string()),
+                DiffLine::Added("Peixe empanado".to_string()),
+                DiffLine::Same("Salada".to_string()),
+                DiffLine::Added("Pudim".to_string()),
+            ]
+        );
+
+        assert_eq!(
+            diff_lines("Fechado", "Arroz"),
+            vec![
+                DiffLine::Removed("Fechado".to_string()),
+                DiffLine::Added("Arroz".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_menu_change() {
+        let date = NaiveDate::from_ymd_opt(2025, 3, 4).unwrap();
+        let stored = HistoryEntry {
+            restaurant_id: 6,
+            restaurant_name: "Restaurante Central".to_string(),
+            date,
+            menu_type: MenuType::Lunch,
+            calorific_value: Some(1030),
+            content: "Arroz\nCarne".to_string(),
+            observation: String::new(),
+        };
+        let menu = |content: &str| Menu {
+            content: content.to_string(),
+            menu_type: MenuType::Lunch,
+            weekday: date.weekday(),
+            date: Some(date),
+            calorific_value: Some(1030),
+            observation: String::new(),
+        };
+
+        assert_eq!(MenuChange::new(&stored, &menu("Arroz\n\nCarne ")), None);
+
+        let change = MenuChange::new(&stored, &menu("Arroz\nPeixe")).unwrap();
+        assert_eq!(change.weekday, Weekday::Tue);
+        assert_eq!(
+            change.lines[1..],
+            [
+                DiffLine::Removed("Carne".to_string()),
+                DiffLine::Added("Peixe".to_string()),
+            ]
+        );
+
+        let json = serde_json::to_value(&change).unwrap();
+        assert_eq!(json["date"], "2025-03-04");
+        assert_eq!(json["weekday"], 2);
+        assert_eq!(json["menu_type"], "lunch");
+        assert_eq!(json["lines"][1], serde_json::json!({ "removed": "Carne" }));
+        assert_eq!(serde_json::from_value::<MenuChange>(json).unwrap(), change);
+    }
+}
@@ -9,6 +9,29 @@ O CLI processa argumentos como:
 - `-w`: Para especificar o dia da semana
 - `-e`: Para mostrar todos os cardápios da semana
 - `-c`: Para especificar um arquivo de configuração personalizado
+- `--config-format`: Para especificar o formato do arquivo de configuração (YAML, TOML ou JSON)
+- `--sort`: Para ordenar os restaurantes pela nota do cardápio (`--sort score`)
+- `--changes`: Para mostrar as mudanças nos cardápios desde a publicação
+- `--strict`: Para recusar arquivos de configuração com chaves desconhecidas
+
+Além disso, o CLI possui alguns subcomandos:
+- `best`: Para recomendar o restaurante com o melhor cardápio na próxima refeição
+- `search`: Para procurar um prato nos cardápios da semana
+- `history`: Para consultar os cardápios guardados no histórico local
+- `watch`: Para avisar sobre as refeições com notificações na área de trabalho
+- `serve`: Para servir uma API REST local com os cardápios em JSON
+- `diff`: Para mostrar os cardápios que mudaram desde a publicação
+- `stats`: Para mostrar estatísticas dos cardápios do histórico, em tabelas ou em JSON
+- `export feed`: Para gerar um feed Atom com os cardápios da semana
+- `post`: Para enviar os cardápios a um webhook do Slack, do Discord ou do Mattermost
+- `bot`: Para responder comandos com os cardápios em um bot do Telegram ou do Matrix
+- `config path`: Para mostrar os arquivos de configuração procurados e quais foram usados
+- `config init`: Para criar um arquivo de configuração com um assistente interativo
+- `config validate`: Para verificar os arquivos de configuração
+- `config show`: Para mostrar as configurações efetivas, com todos os arquivos aplicados
+- `config add-food`: Para adicionar uma comida gostada ou não gostada
+- `config schema`: Para mostrar o JSON Schema do arquivo de configuração
+- `config convert`: Para converter um arquivo de configuração entre YAML, TOML e JSON
 */
 
 use std::{
@@ -16,9 +39,14 @@ use std::{
     path::PathBuf,
 };
 
-use crate::{config::read_env_config_filepath, types::MenuType};
-use chrono::{Datelike, Local, NaiveTime, Weekday};
-use clap::Parser;
+use bandex::{
+    bot_api::BotPlatform,
+    config::{read_env_config_filepath, ConfigFormat, FoodList},
+    types::{MenuSort, MenuType, RestaurantID},
+    webhook::WebhookFormat,
+};
+use chrono::{Datelike, Local, NaiveDate, NaiveDateTime, NaiveTime, Timelike, Weekday};
+use clap::{Parser, Subcommand};
 
 /// Ler dia da semana a partir de um numero na string (segunda: 1, terça: 2, ..., domingo: 7)
 fn parse_weekday(s: &str) -> Result<Weekday, String> {
@@ -34,6 +62,50 @@ fn parse_weekday(s: &str) -> Result<Weekday, String> {
     }
 }
 
+/// Ler a lista de comidas a partir do nome da lista ("liked" ou "disliked")
+fn parse_food_list(s: &str) -> Result<FoodList, String> {
+    match s {
+        "liked" => Ok(FoodList::Liked),
+        "disliked" => Ok(FoodList::Disliked),
+        _ => Err("A lista de comidas é \"liked\" ou \"disliked\"".to_owned()),
+    }
+}
+
+/// Ler uma data no formato "2025-03-06" ou "06/03/2025"
+fn parse_date(s: &str) -> Result<NaiveDate, String> {
+    NaiveDate::parse_from_str(s, "%Y-%m-%d")
+        .or_else(|_| NaiveDate::parse_from_str(s, "%d/%m/%Y"))
+        .map_err(|_| "A data deve estar no formato \"2025-03-06\" ou \"06/03/2025\"".to_owned())
+}
+
+/// Ler a ordem dos restaurantes ("config" ou "score")
+fn parse_menu_sort(s: &str) -> Result<MenuSort, String> {
+    match s {
+        "config" => Ok(MenuSort::Config),
+        "score" => Ok(MenuSort::Score),
+        _ => Err("A ordem dos restaurantes é \"config\" ou \"score\"".to_owned()),
+    }
+}
+
+/// Ler o formato da mensagem do webhook ("slack", "discord" ou "mattermost")
+fn parse_webhook_format(s: &str) -> Result<WebhookFormat, String> {
+    match s {
+        "slack" => Ok(WebhookFormat::Slack),
+        "discord" => Ok(WebhookFormat::Discord),
+        "mattermost" => Ok(WebhookFormat::Mattermost),
+        _ => Err("O formato do webhook é \"slack\", \"discord\" ou \"mattermost\"".to_owned()),
+    }
+}
+
+/// Ler a plataforma do bot ("telegram" ou "matrix")
+fn parse_bot_platform(s: &str) -> Result<BotPlatform, String> {
+    match s {
+        "telegram" => Ok(BotPlatform::Telegram),
+        "matrix" => Ok(BotPlatform::Matrix),
+        _ => Err("A plataforma do bot é \"telegram\" ou \"matrix\"".to_owned()),
+    }
+}
+
 /// Mostra o cardápio dos restaurantes da USP
 ///
 /// Veja as refeições de qualquer bandeijão de acordo com o seu horário.
@@ -63,12 +135,320 @@ struct Cli {
     #[arg(short, long)]
     everything: bool,
 
+    /// Ordem dos restaurantes ("config" ou "score")
+    ///
+    /// Com "score", os restaurantes com a maior nota, calculada pelos pesos das comidas
+    /// gostadas e não gostadas, são mostrados primeiro.
+    #[arg(long, value_parser = parse_menu_sort, default_value = "config")]
+    sort: MenuSort,
+
+    /// Mostra as mudanças nos cardápios desde a primeira versão guardada no histórico
+    ///
+    /// Os cardápios são "sujeitos a modificação", então, abaixo de cada cardápio que mudou
+    /// desde a publicação, são mostradas as linhas removidas e adicionadas.
+    #[arg(long)]
+    changes: bool,
+
     /// Arquivo de configuração do bandex
     ///
-    /// Esse arquivo, em YAML, configura os restaurantes que deseja ver e com quais cores.
+    /// Esse arquivo, em YAML, TOML ou JSON, configura os restaurantes que deseja ver e com quais cores.
     /// Para referencia, há exemplos no repositório do projeto.
-    #[arg(short, long)]
+    #[arg(short, long, global = true)]
     config: Option<PathBuf>,
+
+    /// Formato do arquivo de configuração ("yaml", "toml" ou "json")
+    ///
+    /// Por padrão, o formato é detectado pela extensão do arquivo e, se ela não for conhecida, é YAML.
+    #[arg(long, global = true)]
+    config_format: Option<ConfigFormat>,
+
+    /// Recusa o arquivo de configuração se ele tiver chaves desconhecidas
+    ///
+    /// Sem essa opção, as chaves desconhecidas são ignoradas e apenas geram avisos.
+    #[arg(long, global = true)]
+    strict: bool,
+
+    #[command(subcommand)]
+    command: Option<Command>,
+}
+
+/// Subcomandos do Bandex, que substituem a exibição dos cardápios
+#[derive(Debug, Subcommand)]
+pub enum Command {
+    /// Recomenda onde comer na próxima refeição
+    ///
+    /// Os restaurantes abertos são comparados pelas comidas gostadas e não gostadas das
+    /// configurações. Para escolher a refeição, use `-a` ou `-j` junto de `-w`.
+    Best,
+
+    /// Procura um prato nos cardápios da semana dos restaurantes configurados
+    ///
+    /// O prato é procurado como as comidas das configurações, sem diferenciar letras
+    /// maiúsculas, acentos e plural.
+    Search {
+        /// Prato procurado, por exemplo, "strogonoff"
+        term: String,
+
+        /// Procura apenas a palavra inteira ("ovo" não encontra "ovomaltine")
+        #[arg(long, conflicts_with = "regex")]
+        word: bool,
+
+        /// Procura por uma expressão regular, sem diferenciar letras maiúsculas e minúsculas
+        #[arg(long)]
+        regex: bool,
+
+        /// Procura também nos restaurantes conhecidos da USP que não estão nas configurações
+        // Sem a opção curta, já que `-a` escolhe o almoço
+        #[arg(long)]
+        all_restaurants: bool,
+    },
+
+    /// Mostra os cardápios guardados no histórico local
+    ///
+    /// Todos os cardápios carregados pelo Bandex são guardados no histórico. Por padrão, são
+    /// mostrados os cardápios dos últimos 7 dias.
+    History {
+        /// Data inicial ("2025-03-06" ou "06/03/2025")
+        #[arg(long, value_parser = parse_date)]
+        from: Option<NaiveDate>,
+
+        /// Data final ("2025-03-06" ou "06/03/2025")
+        #[arg(long, value_parser = parse_date)]
+        to: Option<NaiveDate>,
+
+        /// Restaurante consultado (por padrão, todos)
+        #[arg(short, long = "restaurant")]
+        restaurants: Vec<RestaurantID>,
+    },
+
+    /// Fica aberto e avisa sobre as refeições com notificações na área de trabalho
+    ///
+    /// Os cardápios são atualizados periodicamente e, pouco antes de o primeiro restaurante
+    /// abrir, é enviada uma notificação com o restaurante com a melhor nota e as comidas
+    /// gostadas dos cardápios. Os horários das refeições são definidos em `hours` em cada
+    /// restaurante das configurações.
+    Watch {
+        /// Minutos de antecedência das notificações
+        #[arg(long, default_value_t = 15)]
+        before: u32,
+
+        /// Intervalo, em minutos, entre as atualizações dos cardápios
+        #[arg(long, default_value_t = 60, value_parser = clap::value_parser!(u32).range(1..))]
+        refresh: u32,
+    },
+
+    /// Serve uma API REST local com os cardápios em JSON
+    ///
+    /// A API responde em `/restaurants`, `/menus?date=&meal=&restaurant=` e `/menus/week`.
+    /// Os cardápios ficam em cache, de modo que o sistema da USP só é consultado de novo
+    /// quando o tempo de validade acaba.
+    Serve {
+        /// Porta em que a API é servida
+        #[arg(short, long, default_value_t = 8080)]
+        port: u16,
+
+        /// Endereço em que a API é servida
+        #[arg(long, default_value = "127.0.0.1")]
+        host: String,
+
+        /// Tempo de validade, em minutos, dos cardápios em cache
+        #[arg(long, default_value_t = 30)]
+        ttl: u64,
+    },
+
+    /// Mostra os cardápios da semana que mudaram desde a publicação
+    ///
+    /// Os cardápios carregados são comparados, linha a linha, com as primeiras versões
+    /// guardadas no histórico local, para avisar quando um prato anunciado foi trocado.
+    Diff {
+        /// Restaurante comparado (por padrão, os restaurantes das configurações)
+        #[arg(short, long = "restaurant")]
+        restaurants: Vec<RestaurantID>,
+    },
+
+    /// Mostra estatísticas dos cardápios guardados no histórico local
+    ///
+    /// São mostrados os pratos mais frequentes de cada restaurante, os dias fechados, o valor
+    /// energético de cada refeição e quantas vezes as comidas das configurações apareceram.
+    /// Por padrão, todo o histórico é considerado.
+    Stats {
+        /// Data inicial ("2025-03-06" ou "06/03/2025")
+        #[arg(long, value_parser = parse_date)]
+        from: Option<NaiveDate>,
+
+        /// Data final ("2025-03-06" ou "06/03/2025")
+        #[arg(long, value_parser = parse_date)]
+        to: Option<NaiveDate>,
+
+        /// Restaurante consultado (por padrão, todos)
+        #[arg(short, long = "restaurant")]
+        restaurants: Vec<RestaurantID>,
+
+        /// Quantidade de pratos mais frequentes mostrados por restaurante
+        #[arg(long, default_value_t = 10)]
+        top: usize,
+
+        /// Escreve as estatísticas em JSON
+        #[arg(long)]
+        json: bool,
+    },
+
+    /// Envia os cardápios escolhidos a um webhook do Slack, do Discord ou do Mattermost
+    ///
+    /// É enviada uma mensagem por refeição, com um anexo por restaurante na cor do restaurante
+    /// nas configurações. Para escolher as refeições, use `-a`, `-j`, `-w` e `-e`, como na
+    /// exibição dos cardápios.
+    Post {
+        /// Endereço do webhook
+        #[arg(long)]
+        webhook: String,
+
+        /// Formato da mensagem ("slack", "discord" ou "mattermost")
+        #[arg(long, value_parser = parse_webhook_format, default_value = "slack")]
+        format: WebhookFormat,
+    },
+
+    /// Responde comandos com os cardápios em um bot do Telegram ou do Matrix
+    ///
+    /// O bot responde `/hoje`, `/amanha jantar`, `/central` e outros comandos (veja `/ajuda`)
+    /// e envia os cardápios do dia nas conversas que os assinarem com `/assinar`. O token do
+    /// bot pode ser passado pela variável de ambiente `BANDEX_BOT_TOKEN`.
+    Bot {
+        /// Plataforma do bot ("telegram" ou "matrix")
+        #[arg(value_parser = parse_bot_platform)]
+        platform: BotPlatform,
+
+        /// Endereço da API do bot (por padrão, a API do Telegram; no Matrix, o homeserver)
+        #[arg(long)]
+        api_url: Option<String>,
+
+        /// Token do bot no Telegram ou token de acesso do usuário do bot no Matrix
+        #[arg(long)]
+        token: Option<String>,
+    },
+
+    /// Exporta os cardápios da semana em outros formatos
+    Export {
+        #[command(subcommand)]
+        command: ExportCommand,
+    },
+
+    /// Gerencia os arquivos de configuração do bandex
+    Config {
+        #[command(subcommand)]
+        command: ConfigCommand,
+    },
+}
+
+/// Subcomandos de `bandex export`
+#[derive(Debug, Subcommand)]
+pub enum ExportCommand {
+    /// Gera um feed Atom com os cardápios da semana dos restaurantes configurados
+    ///
+    /// O feed tem uma entrada por restaurante, refeição e dia, com IDs estáveis, para que os
+    /// leitores de feeds mostrem o cardápio do dia.
+    Feed {
+        /// Arquivo em que o feed é salvo, sobrescrevendo-o (por padrão, o feed é mostrado na
+        /// saída padrão)
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+    },
+}
+
+/// Subcomandos de `bandex config`
+#[derive(Debug, Subcommand)]
+pub enum ConfigCommand {
+    /// Mostra os arquivos de configuração procurados, na ordem em que são aplicados, e quais foram usados
+    Path,
+
+    /// Cria um arquivo de configuração com um assistente interativo
+    ///
+    /// Por padrão, o arquivo é criado em `$XDG_CONFIG_HOME/bandex/config.yaml`.
+    Init {
+        /// Arquivo que será criado
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+
+        /// Sobrescreve o arquivo, se ele já existir
+        #[arg(short, long)]
+        force: bool,
+    },
+
+    /// Verifica os arquivos de configuração, mostrando todos os problemas encontrados
+    Validate,
+
+    /// Mostra as configurações efetivas, em YAML, com todos os arquivos aplicados
+    Show,
+
+    /// Adiciona uma comida à lista de comidas gostadas ou não gostadas
+    ///
+    /// A comida é adicionada ao arquivo passado por `-c` ou, se nenhum for passado, ao arquivo
+    /// `$XDG_CONFIG_HOME/bandex/config.yaml`. O arquivo é reescrito, então apenas os comentários
+    /// do início do arquivo são mantidos.
+    AddFood {
+        /// Lista em que a comida será adicionada ("liked" ou "disliked")
+        #[arg(value_parser = parse_food_list)]
+        list: FoodList,
+
+        /// Nome da comida
+        name: String,
+
+        /// Restaurante em que a comida é gostada ou não gostada (por padrão, todos)
+        #[arg(short, long = "restaurant")]
+        restaurants: Vec<u64>,
+    },
+
+    /// Mostra o JSON Schema do arquivo de configuração, usado pelos editores para o autocompletar
+    Schema,
+
+    /// Converte um arquivo de configuração para outro formato (YAML, TOML ou JSON)
+    ///
+    /// O formato do arquivo lido é detectado pela extensão ou escolhido por `--config-format`.
+    /// Os comentários e as âncoras do arquivo original não são mantidos.
+    Convert {
+        /// Arquivo que será convertido
+        input: PathBuf,
+
+        /// Arquivo que será criado (por padrão, as configurações são mostradas na saída padrão)
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+
+        /// Formato do arquivo convertido, por padrão, detectado pela extensão do arquivo criado
+        #[arg(short, long)]
+        to: Option<ConfigFormat>,
+
+        /// Sobrescreve o arquivo criado, se ele já existir
+        #[arg(short, long)]
+        force: bool,
+    },
+}
+
+/// Opções escolhidas na linha de comando, já interpretadas.
+#[derive(Debug)]
+pub struct CliOptions {
+    /// Tipo da refeição escolhida, ou `None` para todas as refeições.
+    pub menu_type: Option<MenuType>,
+
+    /// Dia da semana escolhido, ou `None` para todos os dias da semana.
+    pub weekday: Option<Weekday>,
+
+    /// Ordem em que os restaurantes são exibidos.
+    pub sort: MenuSort,
+
+    /// Se as mudanças nos cardápios desde a publicação devem ser mostradas.
+    pub changes: bool,
+
+    /// Arquivo de configuração, passado pelo CLI ou pela variável de ambiente.
+    pub config_filepath: Option<PathBuf>,
+
+    /// Formato do arquivo de configuração, ou `None` para detectá-lo pela extensão.
+    pub config_format: Option<ConfigFormat>,
+
+    /// Se o arquivo de configuração deve ser lido no modo estrito.
+    pub strict_config: bool,
+
+    /// Subcomando escolhido, ou `None` para mostrar os cardápios.
+    pub command: Option<Command>,
 }
 
 /// Retorna o tipo da refeição com base na hora atual.
@@ -91,6 +471,20 @@ fn get_menu_type_by_datetime(time: NaiveTime) -> Option<MenuType> {
     }
 }
 
+/// Retorna o dia da semana e o tipo da próxima refeição com base na data e hora atuais.
+///
+/// Durante o almoço ou o jantar, retorna a refeição atual. Depois do jantar, retorna o
+/// almoço do dia seguinte e, antes do almoço, o almoço do próprio dia.
+pub fn get_next_meal(datetime: NaiveDateTime) -> (Weekday, MenuType) {
+    let weekday = datetime.weekday();
+
+    match get_menu_type_by_datetime(datetime.time()) {
+        Some(menu_type) => (weekday, menu_type),
+        None if datetime.hour() >= 12 => (weekday.succ(), MenuType::Lunch),
+        None => (weekday, MenuType::Lunch),
+    }
+}
+
 /// Lê os parâmetros de linha de comando e os intepreta extraindo:
 /// - O horário da refeição que se busca
 ///     - `Some(time)`: se o usuário especificou um horário específico
@@ -98,10 +492,11 @@ fn get_menu_type_by_datetime(time: NaiveTime) -> Option<MenuType> {
 /// - O dia da semana escolhido:
 ///     - `Some(weekday)`: se o usuário especificou um dia
 ///     - `None`: se o usuário não quer todos os dias da semana
-pub fn parse_cli() -> Result<(Option<MenuType>, Option<Weekday>, Option<PathBuf>), Error> {
+/// - O arquivo de configuração e se ele deve ser lido no modo estrito
+pub fn parse_cli() -> Result<CliOptions, Error> {
     let cli = Cli::parse();
 
-    if cli.weekday != None && cli.everything {
+    if cli.weekday.is_some() && cli.everything {
         return Err(Error::new(
             ErrorKind::InvalidInput,
             "Escolha mostrar um dia especifico (-w <WEEKDAY>) ou todos os dias (-E)",
@@ -125,12 +520,22 @@ pub fn parse_cli() -> Result<(Option<MenuType>, Option<Weekday>, Option<PathBuf>
 
     let config_filepath = cli.config.or_else(read_env_config_filepath);
 
-    Ok((menu_type, weekday, config_filepath))
+    Ok(CliOptions {
+        menu_type,
+        weekday,
+        sort: cli.sort,
+        changes: cli.changes,
+        config_filepath,
+        config_format: cli.config_format,
+        strict_config: cli.strict,
+        command: cli.command,
+    })
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use std::path::Path;
 
     #[test]
     fn test_parse_weekday() {
@@ -158,6 +563,7 @@ mod tests {
     }
 
     #[test]
+    #[allow(clippy::cmp_owned)]
     fn test_cli_get_right_info() {
         let cli = Cli::try_parse_from(vec!["bandex", "-a", "-w", "2", "-c", "config.yaml"]);
         assert!(cli.is_ok());
@@ -204,6 +610,337 @@ mod tests {
         assert!(cli.is_err());
     }
 
+    #[test]
+    fn test_cli_strict() {
+        let cli = Cli::try_parse_from(vec!["bandex", "-aj", "-e"]);
+        assert!(
+            !cli.unwrap().strict,
+            "CLI parses \"-aj -e\": strict must be false"
+        );
+
+        let cli = Cli::try_parse_from(vec!["bandex", "-c", "config.yaml", "--strict"]);
+        assert!(cli.is_ok());
+        assert!(cli.unwrap().strict);
+    }
+
+    #[test]
+    fn test_cli_config_path() {
+        let cli = Cli::try_parse_from(vec!["bandex", "--strict", "config", "path"]);
+        assert!(cli.is_ok());
+        assert!(matches!(
+            cli.unwrap().command,
+            Some(Command::Config {
+                command: ConfigCommand::Path
+            })
+        ));
+    }
+
+    #[test]
+    fn test_cli_config_add_food() {
+        let cli = Cli::try_parse_from(vec![
+            "bandex", "config", "add-food", "disliked", "pvt", "-r", "8", "-r", "9", "-c", "a.yaml",
+        ]);
+        assert!(cli.is_ok());
+        let cli = cli.unwrap();
+        assert!(cli.config.is_some());
+        let Some(Command::Config {
+            command:
+                ConfigCommand::AddFood {
+                    list,
+                    name,
+                    restaurants,
+                },
+        }) = cli.command
+        else {
+            panic!("CLI parses \"config add-food\": command must be AddFood");
+        };
+        assert_eq!(list, FoodList::Disliked);
+        assert_eq!(name, "pvt");
+        assert_eq!(restaurants, vec![8, 9]);
+
+        let cli = Cli::try_parse_from(vec!["bandex", "config", "add-food", "amado", "pudim"]);
+        assert!(cli.is_err());
+    }
+
+    #[test]
+    fn test_cli_config_convert() {
+        let cli = Cli::try_parse_from(vec![
+            "bandex",
+            "config",
+            "convert",
+            "a.yaml",
+            "--to",
+            "toml",
+            "--config-format",
+            "yml",
+        ]);
+        assert!(cli.is_ok());
+        let cli = cli.unwrap();
+        assert_eq!(cli.config_format, Some(ConfigFormat::Yaml));
+        let Some(Command::Config {
+            command: ConfigCommand::Convert {
+                input, output, to, ..
+            },
+        }) = cli.command
+        else {
+            panic!("CLI parses \"config convert\": command must be Convert");
+        };
+        assert_eq!(input, Path::new("a.yaml"));
+        assert!(output.is_none());
+        assert_eq!(to, Some(ConfigFormat::Toml));
+
+        let cli = Cli::try_parse_from(vec!["bandex", "--config-format", "xml"]);
+        assert!(cli.is_err());
+    }
+
+    #[test]
+    fn test_cli_sort() {
+        let cli = Cli::try_parse_from(vec!["bandex", "-a"]);
+        assert_eq!(cli.unwrap().sort, MenuSort::Config);
+
+        let cli = Cli::try_parse_from(vec!["bandex", "-j", "--sort", "score"]);
+        assert_eq!(cli.unwrap().sort, MenuSort::Score);
+
+        let cli = Cli::try_parse_from(vec!["bandex", "--sort", "nota"]);
+        assert!(cli.is_err());
+    }
+
+    #[test]
+    fn test_get_next_meal() {
+        let datetime = |day, hour, min| {
+            chrono::NaiveDate::from_ymd_opt(2024, 6, day)
+                .unwrap()
+                .and_hms_opt(hour, min, 0)
+                .unwrap()
+        };
+
+        // 03/06/2024 é uma segunda-feira
+        assert_eq!(
+            get_next_meal(datetime(3, 11, 30)),
+            (Weekday::Mon, MenuType::Lunch)
+        );
+        assert_eq!(
+            get_next_meal(datetime(3, 17, 0)),
+            (Weekday::Mon, MenuType::Dinner)
+        );
+        assert_eq!(
+            get_next_meal(datetime(3, 5, 0)),
+            (Weekday::Mon, MenuType::Lunch)
+        );
+        assert_eq!(
+            get_next_meal(datetime(3, 21, 0)),
+            (Weekday::Tue, MenuType::Lunch)
+        );
+        assert_eq!(
+            get_next_meal(datetime(9, 22, 0)),
+            (Weekday::Mon, MenuType::Lunch)
+        );
+    }
+
+    #[test]
+    fn test_cli_best() {
+        let cli = Cli::try_parse_from(vec!["bandex", "-a", "-w", "2", "best"]).unwrap();
+        assert!(matches!(cli.command, Some(Command::Best)));
+        assert!(cli.lunch);
+        assert_eq!(cli.weekday, Some(Weekday::Tue));
+    }
+
+    #[test]
+    fn test_cli_search() {
+        let cli = Cli::try_parse_from(vec![
+            "bandex",
+            "search",
+            "strogonoff",
+            "--all-restaurants",
+            "--word",
+        ]);
+        let Some(Command::Search {
+            term,
+            word,
+            regex,
+            all_restaurants,
+        }) = cli.unwrap().command
+        else {
+            panic!("CLI parses \"search\": command must be Search");
+        };
+        assert_eq!(term, "strogonoff");
+        assert!(word && !regex && all_restaurants);
+
+        let cli = Cli::try_parse_from(vec!["bandex", "search", "ovo", "--word", "--regex"]);
+        assert!(cli.is_err());
+
+        // `-a` continua escolhendo o almoço
+        let cli = Cli::try_parse_from(vec!["bandex", "-a", "search", "ovo"]).unwrap();
+        assert!(cli.lunch);
+        assert!(matches!(
+            cli.command,
+            Some(Command::Search {
+                all_restaurants: false,
+                ..
+            })
+        ));
+    }
+
+    #[test]
+    fn test_cli_history() {
+        let cli = Cli::try_parse_from(vec![
+            "bandex",
+            "history",
+            "--from",
+            "2025-03-01",
+            "--to",
+            "06/03/2025",
+            "-r",
+            "6",
+        ]);
+        let Some(Command::History {
+            from,
+            to,
+            restaurants,
+        }) = cli.unwrap().command
+        else {
+            panic!("CLI parses \"history\": command must be History");
+        };
+        assert_eq!(from, NaiveDate::from_ymd_opt(2025, 3, 1));
+        assert_eq!(to, NaiveDate::from_ymd_opt(2025, 3, 6));
+        assert_eq!(restaurants, vec![6]);
+
+        let cli = Cli::try_parse_from(vec!["bandex", "history", "--from", "ontem"]);
+        assert!(cli.is_err());
+    }
+
+    #[test]
+    fn test_cli_watch() {
+        let cli = Cli::try_parse_from(vec!["bandex", "watch", "--before", "30"]);
+        let Some(Command::Watch { before, refresh }) = cli.unwrap().command else {
+            panic!("CLI parses \"watch\": command must be Watch");
+        };
+        assert_eq!((before, refresh), (30, 60));
+
+        let cli = Cli::try_parse_from(vec!["bandex", "watch", "--refresh", "0"]);
+        assert!(cli.is_err());
+    }
+
+    #[test]
+    fn test_cli_serve() {
+        let cli = Cli::try_parse_from(vec!["bandex", "serve", "-p", "3000", "--ttl", "5"]);
+        let Some(Command::Serve { port, host, ttl }) = cli.unwrap().command else {
+            panic!("CLI parses \"serve\": command must be Serve");
+        };
+        assert_eq!((port, host.as_str(), ttl), (3000, "127.0.0.1", 5));
+
+        let cli = Cli::try_parse_from(vec!["bandex", "serve", "--port", "70000"]);
+        assert!(cli.is_err());
+    }
+
+    #[test]
+    fn test_cli_export_feed() {
+        let cli = Cli::try_parse_from(vec!["bandex", "export", "feed", "-o", "menus.xml"]);
+        let Some(Command::Export {
+            command: ExportCommand::Feed { output },
+        }) = cli.unwrap().command
+        else {
+            panic!("CLI parses \"export feed\": command must be Export Feed");
+        };
+        assert_eq!(output, Some(PathBuf::from("menus.xml")));
+
+        let cli = Cli::try_parse_from(vec!["bandex", "export"]);
+        assert!(cli.is_err());
+    }
+
+    #[test]
+    fn test_cli_post() {
+        let cli = Cli::try_parse_from(vec![
+            "bandex",
+            "-a",
+            "post",
+            "--webhook",
+            "http://localhost/hooks/abc",
+            "--format",
+            "discord",
+        ])
+        .unwrap();
+        assert!(cli.lunch);
+        let Some(Command::Post { webhook, format }) = cli.command else {
+            panic!("CLI parses \"post\": command must be Post");
+        };
+        assert_eq!(webhook, "http://localhost/hooks/abc");
+        assert_eq!(format, WebhookFormat::Discord);
+
+        let cli = Cli::try_parse_from(vec!["bandex", "post", "--webhook", "http://localhost"]);
+        let Some(Command::Post { format, .. }) = cli.unwrap().command else {
+            panic!("CLI parses \"post\": command must be Post");
+        };
+        assert_eq!(format, WebhookFormat::Slack);
+
+        let cli = Cli::try_parse_from(vec!["bandex", "post", "--webhook", "x", "--format", "irc"]);
+        assert!(cli.is_err());
+        assert!(Cli::try_parse_from(vec!["bandex", "post"]).is_err());
+    }
+
+    #[test]
+    fn test_cli_bot() {
+        let cli = Cli::try_parse_from(vec![
+            "bandex",
+            "bot",
+            "matrix",
+            "--api-url",
+            "http://localhost:8008",
+        ]);
+        let Some(Command::Bot {
+            platform,
+            api_url,
+            token,
+        }) = cli.unwrap().command
+        else {
+            panic!("CLI parses \"bot\": command must be Bot");
+        };
+        assert_eq!(platform, BotPlatform::Matrix);
+        assert_eq!(api_url.as_deref(), Some("http://localhost:8008"));
+        assert_eq!(token, None);
+
+        assert!(Cli::try_parse_from(vec!["bandex", "bot", "irc"]).is_err());
+        assert!(Cli::try_parse_from(vec!["bandex", "bot"]).is_err());
+    }
+
+    #[test]
+    fn test_cli_diff() {
+        let cli = Cli::try_parse_from(vec!["bandex", "diff", "-r", "6", "-r", "8"]);
+        let Some(Command::Diff { restaurants }) = cli.unwrap().command else {
+            panic!("CLI parses \"diff\": command must be Diff");
+        };
+        assert_eq!(restaurants, vec![6, 8]);
+
+        let cli = Cli::try_parse_from(vec!["bandex", "-a", "--changes"]).unwrap();
+        assert!(cli.changes && cli.command.is_none());
+    }
+
+    #[test]
+    fn test_cli_stats() {
+        let cli = Cli::try_parse_from(vec!["bandex", "stats", "--top", "3", "--json", "-r", "8"]);
+        let Some(Command::Stats {
+            from,
+            restaurants,
+            top,
+            json,
+            ..
+        }) = cli.unwrap().command
+        else {
+            panic!("CLI parses \"stats\": command must be Stats");
+        };
+        assert_eq!(from, None);
+        assert_eq!(restaurants, vec![8]);
+        assert_eq!(top, 3);
+        assert!(json);
+
+        let cli = Cli::try_parse_from(vec!["bandex", "stats"]);
+        let Some(Command::Stats { top, json, .. }) = cli.unwrap().command else {
+            panic!("CLI parses \"stats\": command must be Stats");
+        };
+        assert_eq!(top, 10);
+        assert!(!json);
+    }
+
     #[test]
     fn test_get_menu_type_by_datetime() {
         let menu_type = get_menu_type_by_datetime(NaiveTime::from_hms_opt(5, 31, 47).unwrap());
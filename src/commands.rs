@@ -0,0 +1,728 @@
+/*!
+Execução dos subcomandos do Bandex
+
+Este módulo contém as funções que executam os subcomandos do CLI, como os
+subcomandos de gerenciamento do arquivo de configuração (`bandex config ...`), a
+recomendação de onde comer (`bandex best`), a busca de pratos (`bandex search`) e a
+consulta ao histórico (`bandex history`).
+*/
+
+use std::{
+    env, fs,
+    io::{self, Error, ErrorKind},
+    path::{Path, PathBuf},
+    process,
+    time::Duration,
+};
+
+use crate::{
+    cli::{get_next_meal, CliOptions, ConfigCommand},
+    wizard::Wizard,
+};
+use bandex::{
+    bot::{self, Bot, Subscriptions},
+    bot_api::{BotApi, BotPlatform},
+    config::{
+        self, Config, ConfigDiagnostic, ConfigFile, ConfigFormat, ConfigLayer, FoodConfig,
+        FoodList, FoodMatch, LoadedConfig, DEFAULT_COLOR,
+    },
+    display::{Display, WEEKDAYS},
+    feed,
+    history::{self, History, HistoryError, HistoryQuery},
+    parse_dwr, recommend, search,
+    server::Api,
+    source::Sources,
+    stats,
+    types::{MenuType, MenusCache, RestaurantID},
+    watch::{self, Notifier, WatchEvent},
+    webhook::{self, WebhookFormat},
+};
+use chrono::{Days, Local, NaiveDate, TimeDelta};
+
+/// Lê os arquivos de configuração, mostrando os erros e os avisos encontrados, incluindo os
+/// restaurantes com IDs repetidos ou reservados por outras fontes.
+///
+/// No modo estrito (`strict`), qualquer problema nos arquivos interrompe o programa.
+pub fn load_config(config_files: &[ConfigFile], strict: bool) -> LoadedConfig {
+    let loaded_config = Config::load(config_files, strict).unwrap_or_else(|message| {
+        // Só ocorre no modo estrito, em que um arquivo de configurações inválido interrompe o programa
+        Display::error_message(format!(
+            "Erro ao ler o arquivo de configurações: {}",
+            message
+        ));
+        process::exit(1);
+    });
+
+    for message in &loaded_config.errors {
+        Display::error_message(format!(
+            "Erro ao ler o arquivo de configurações: {}",
+            message
+        ));
+    }
+    for warning in &loaded_config.warnings {
+        Display::warning_message(format!(
+            "Entrada ignorada no arquivo de configurações: {}",
+            warning
+        ));
+    }
+
+    // Os restaurantes com o mesmo ID misturariam os cardápios no cache e no histórico
+    let id_errors = loaded_config.config.check_restaurant_ids();
+    for message in &id_errors {
+        Display::error_message(format!("Erro nas configurações: {}", message));
+    }
+    if strict && !id_errors.is_empty() {
+        process::exit(1);
+    }
+
+    loaded_config
+}
+
+/// Arquivo que deve ser criado ou alterado pelos subcomandos de configuração: o arquivo
+/// passado pelo CLI ou, se nenhum for passado, o arquivo do usuário.
+fn target_config_file(options: &CliOptions) -> Result<PathBuf, Error> {
+    options
+        .config_filepath
+        .clone()
+        .or_else(config::user_config_file)
+        .ok_or_else(|| {
+            Error::new(
+                ErrorKind::NotFound,
+                "Não foi possível encontrar o diretório de configurações, use -c <CONFIG>",
+            )
+        })
+}
+
+/// Formato do arquivo `path`, que deve ser criado ou alterado pelos subcomandos de configuração.
+///
+/// O formato escolhido pelo CLI (`--config-format`) vale apenas para o arquivo passado por `-c`.
+fn target_config_format(options: &CliOptions, path: &Path) -> ConfigFormat {
+    options
+        .config_format
+        .filter(|_| options.config_filepath.as_deref() == Some(path))
+        .or_else(|| ConfigFormat::from_path(path))
+        .unwrap_or_default()
+}
+
+/// Verifica se o arquivo `path` pode ser criado, ou seja, se ele não existe ou se deve ser
+/// sobrescrito (`force`).
+fn check_overwrite(path: &Path, force: bool) -> Result<(), Error> {
+    if path.exists() && !force {
+        return Err(Error::new(
+            ErrorKind::AlreadyExists,
+            format!(
+                "O arquivo {} já existe, use --force para sobrescrevê-lo",
+                path.display()
+            ),
+        ));
+    }
+    Ok(())
+}
+
+/// Escreve um arquivo de configurações, criando os diretórios necessários.
+fn write_config_file(path: &Path, contents: &str) -> Result<(), Error> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    fs::write(path, contents)
+}
+
+/// Procura os nomes dos restaurantes no sistema da USP.
+async fn discover_restaurants(ids: Vec<RestaurantID>) -> Vec<(RestaurantID, Option<String>)> {
+    let mut restaurants = Vec::new();
+    for id in ids {
+        restaurants.push((id, parse_dwr::fetch_restaurant_name(id).await.ok()));
+    }
+    restaurants
+}
+
+/// Cria um arquivo de configurações com o assistente interativo.
+async fn init(options: &CliOptions, output: Option<PathBuf>, force: bool) -> Result<(), Error> {
+    let path = match output {
+        Some(path) => path,
+        None => target_config_file(options)?,
+    };
+
+    check_overwrite(&path, force)?;
+    let format = target_config_format(options, &path);
+
+    let mut wizard = Wizard::new(io::stdin().lock(), io::stdout());
+    let config = wizard.run(discover_restaurants).await?;
+
+    write_config_file(&path, &format.file_contents(&config.to_format(format)))?;
+    println!("\n   Configurações salvas em {}", path.display());
+
+    Ok(())
+}
+
+/// Verifica os arquivos de configuração, mostrando todos os problemas encontrados.
+///
+/// Termina o programa com erro se algum problema for encontrado (no modo estrito, os
+/// avisos também são considerados erros).
+fn validate(config_files: &[ConfigFile], strict: bool) {
+    // Os arquivos são lidos sem o modo estrito para que todos os problemas sejam encontrados
+    let loaded_config = Config::load(config_files, false)
+        .expect("a leitura sem o modo estrito não falha, os erros são retornados");
+    let checks = loaded_config.config.check();
+
+    Display::show_validation(config_files, &loaded_config, &checks);
+
+    let has_warnings = !loaded_config.warnings.is_empty() || !checks.is_empty();
+    if !loaded_config.errors.is_empty() || (strict && has_warnings) {
+        process::exit(1);
+    }
+}
+
+/// Adiciona uma comida à lista `list` do arquivo de configurações.
+fn add_food(
+    options: &CliOptions,
+    list: FoodList,
+    name: String,
+    restaurants: Vec<u64>,
+) -> Result<(), Error> {
+    let path = target_config_file(options)?;
+    let contents = if path.exists() {
+        fs::read_to_string(&path)?
+    } else {
+        String::new()
+    };
+
+    let restaurants = (!restaurants.is_empty()).then_some(restaurants);
+    let food = FoodConfig::new(name, restaurants);
+
+    let format = target_config_format(options, &path);
+    let contents = config::add_food(&contents, format, list, &food).map_err(|mut diagnostic| {
+        diagnostic.file = Some(path.clone());
+        diagnostic
+    })?;
+
+    match contents {
+        Some(contents) => {
+            write_config_file(&path, &contents)?;
+            println!(
+                "   \"{}\" adicionada à lista {} de {}",
+                food.name,
+                list.key(),
+                path.display()
+            );
+        }
+        None => println!(
+            "   \"{}\" já está na lista {} de {}",
+            food.name,
+            list.key(),
+            path.display()
+        ),
+    }
+
+    Ok(())
+}
+
+/// Converte o arquivo de configurações `input` para o formato `to` (ou para o formato do
+/// arquivo `output`), escrevendo o resultado em `output` ou na saída padrão.
+fn convert(
+    options: &CliOptions,
+    input: PathBuf,
+    output: Option<PathBuf>,
+    to: Option<ConfigFormat>,
+    force: bool,
+) -> Result<(), Error> {
+    let to = to
+        .or_else(|| output.as_deref().and_then(ConfigFormat::from_path))
+        .ok_or_else(|| {
+            Error::new(
+                ErrorKind::InvalidInput,
+                "Escolha o formato do arquivo convertido com --to <FORMAT>",
+            )
+        })?;
+    if let Some(output) = &output {
+        check_overwrite(output, force)?;
+    }
+
+    let from = options
+        .config_format
+        .or_else(|| ConfigFormat::from_path(&input))
+        .unwrap_or_default();
+    let (layer, warnings) = ConfigLayer::from_file(&input, from, options.strict_config)?;
+    for warning in &warnings {
+        Display::warning_message(format!(
+            "Entrada ignorada no arquivo de configurações: {}",
+            warning
+        ));
+    }
+
+    let contents = layer.to_format(to).map_err(|diagnostic| ConfigDiagnostic {
+        file: output.clone(),
+        ..diagnostic
+    })?;
+
+    match output {
+        Some(output) => {
+            write_config_file(&output, &to.file_contents(&contents))?;
+            println!(
+                "   {} convertido para {} em {}",
+                input.display(),
+                to,
+                output.display()
+            );
+        }
+        None => print!("{contents}"),
+    }
+
+    Ok(())
+}
+
+/// Abre o histórico dos cardápios, avisando se não for possível.
+fn open_history() -> Option<History> {
+    let path = history::history_file()?;
+    History::open(&path)
+        .inspect_err(|error| {
+            Display::warning_message(format!(
+                "Não foi possível abrir o histórico {}: {error}",
+                path.display()
+            ))
+        })
+        .ok()
+}
+
+/// Cria o cache dos cardápios, que carrega os cardápios das fontes `sources` e guarda no
+/// histórico todos os cardápios carregados.
+pub fn menus_cache(sources: &Sources) -> MenusCache {
+    MenusCache::new()
+        .with_sources(sources.clone())
+        .with_history(open_history())
+        .with_history_error_handler(show_history_error)
+}
+
+/// Mostra um erro do histórico encontrado ao carregar os cardápios, sem interromper o programa.
+fn show_history_error(error: HistoryError) {
+    Display::warning_message(error.to_string());
+}
+
+/// Mostra os cardápios do histórico entre as datas `from` e `to` dos restaurantes
+/// `restaurants` (ou de todos, se vazio). Sem datas, mostra os últimos 7 dias.
+pub fn history(
+    config: &Config,
+    from: Option<NaiveDate>,
+    to: Option<NaiveDate>,
+    restaurants: Vec<RestaurantID>,
+) -> Result<(), Error> {
+    let Some(history) = open_history() else {
+        return Err(Error::new(
+            ErrorKind::NotFound,
+            "Não foi possível abrir o histórico dos cardápios",
+        ));
+    };
+
+    let from = match (from, to) {
+        (None, None) => Local::now().date_naive().checked_sub_days(Days::new(7)),
+        _ => from,
+    };
+    let query = HistoryQuery {
+        from,
+        to,
+        restaurants,
+    };
+
+    let entries = history
+        .query(&query)
+        .map_err(|error| Error::other(format!("Erro ao consultar o histórico: {error}")))?;
+    Display::show_history(&entries, config);
+    Ok(())
+}
+
+/// Mostra um erro para cada restaurante cujos cardápios não puderam ser carregados.
+fn show_unavailable(restaurants: &[RestaurantID]) {
+    for restaurant_id in restaurants {
+        Display::error_message(format!(
+            "Não foi possível carregar dados desse restaurante (Rest {restaurant_id})"
+        ));
+    }
+}
+
+/// Fica aberto e envia uma notificação `before` minutos antes de cada refeição, atualizando
+/// os cardápios a cada `refresh` minutos.
+pub async fn watch(
+    config: &Config,
+    sources: &Sources,
+    before: u32,
+    refresh: u32,
+) -> Result<(), Error> {
+    let notifier = Notifier::session().await.map_err(|error| {
+        Error::new(
+            ErrorKind::NotConnected,
+            format!("Não foi possível conectar ao D-Bus da sessão: {error}"),
+        )
+    })?;
+
+    let before = TimeDelta::minutes(before.into());
+    let refresh = TimeDelta::minutes(refresh.into());
+
+    watch::watch(
+        config,
+        &notifier,
+        before,
+        refresh,
+        || menus_cache(sources),
+        |event| match event {
+            WatchEvent::Scheduled(alert) => println!(
+                "   ➤  Próxima notificação: {} de {}, às {}\n",
+                alert.menu_type,
+                alert.date.format("%d/%m/%Y"),
+                alert.notify_at.format("%H:%M")
+            ),
+            WatchEvent::Unavailable(restaurant_id) => show_unavailable(&[restaurant_id]),
+            WatchEvent::Notified(notification, result) => {
+                println!(
+                    "   🔔 {}\n      {}\n",
+                    notification.summary, notification.body
+                );
+                if let Err(error) = result {
+                    Display::warning_message(format!(
+                        "Não foi possível enviar a notificação: {error}"
+                    ));
+                }
+            }
+        },
+    )
+    .await;
+    Ok(())
+}
+
+/// Gera o feed Atom dos cardápios da semana e o salva em `output` ou, sem arquivo, mostra-o
+/// na saída padrão.
+pub async fn export_feed(
+    config: &Config,
+    sources: &Sources,
+    output: Option<PathBuf>,
+) -> Result<(), Error> {
+    let entries = feed::feed_entries(&mut menus_cache(sources), config).await;
+    let contents = feed::atom_feed(&entries, Local::now().fixed_offset());
+
+    match output {
+        Some(output) => {
+            fs::write(&output, contents)?;
+            println!(
+                "   Feed com {} cardápios salvo em {}",
+                entries.len(),
+                output.display()
+            );
+        }
+        None => print!("{contents}"),
+    }
+    Ok(())
+}
+
+/// Envia os cardápios escolhidos nas opções ao webhook `url`, com uma mensagem no formato
+/// `format` para cada refeição de cada dia.
+pub async fn post(
+    options: &CliOptions,
+    config: &Config,
+    sources: &Sources,
+    url: String,
+    format: WebhookFormat,
+) -> Result<(), Error> {
+    let weekdays = options
+        .weekday
+        .map_or(WEEKDAYS.to_vec(), |weekday| vec![weekday]);
+    let menu_types = options
+        .menu_type
+        .clone()
+        .map_or(vec![MenuType::Lunch, MenuType::Dinner], |menu_type| {
+            vec![menu_type]
+        });
+
+    let (posts, unavailable) =
+        webhook::meal_posts(&mut menus_cache(sources), config, &weekdays, &menu_types).await;
+    show_unavailable(&unavailable);
+
+    for post in posts {
+        if post.menus.is_empty() {
+            Display::warning_message(format!("Nenhum cardápio para enviar ({})", post.title));
+            continue;
+        }
+
+        webhook::post(&url, &webhook::payload(format, &post.title, &post.menus))
+            .await
+            .map_err(Error::other)?;
+        println!("   ✔ {} enviado ao webhook ({format})", post.title);
+    }
+    Ok(())
+}
+
+/// Variável de ambiente com o token do bot.
+const ENV_VAR_BOT_TOKEN: &str = "BANDEX_BOT_TOKEN";
+
+/// Executa o bot na plataforma `platform`, com a API em `api_url` (ou no endereço padrão da
+/// plataforma), respondendo os comandos e fazendo os envios diários até ser interrompido.
+pub async fn bot(
+    config: &Config,
+    sources: &Sources,
+    platform: BotPlatform,
+    api_url: Option<String>,
+    token: Option<String>,
+) -> Result<(), Error> {
+    let api_url = api_url
+        .or_else(|| platform.default_api_url().map(str::to_string))
+        .ok_or_else(|| {
+            Error::new(
+                ErrorKind::InvalidInput,
+                "Informe o endereço do homeserver do Matrix com --api-url",
+            )
+        })?;
+    let token = token
+        .or_else(|| env::var(ENV_VAR_BOT_TOKEN).ok())
+        .filter(|token| !token.is_empty())
+        .ok_or_else(|| {
+            Error::new(
+                ErrorKind::InvalidInput,
+                format!("Informe o token do bot com --token ou em {ENV_VAR_BOT_TOKEN}"),
+            )
+        })?;
+
+    let path = bot::bot_file().ok_or_else(|| {
+        Error::new(
+            ErrorKind::NotFound,
+            "Não foi possível encontrar o diretório de dados para as assinaturas do bot",
+        )
+    })?;
+    let subscriptions = Subscriptions::open(&path, platform).map_err(|error| {
+        Error::other(format!(
+            "Não foi possível abrir as assinaturas do bot {}: {error}",
+            path.display()
+        ))
+    })?;
+
+    let mut api = BotApi::new(platform, &api_url, token)
+        .map_err(|error| Error::new(ErrorKind::InvalidInput, error))?;
+    let menus_cache = menus_cache(sources).with_ttl(Some(Duration::from_secs(30 * 60)));
+    let mut bot = Bot::new(menus_cache, config, subscriptions);
+    println!("   ➤  Bot do {platform} em {api_url}\n");
+
+    loop {
+        match api.next_messages().await {
+            Ok(messages) => {
+                for message in messages {
+                    let now = Local::now().naive_local();
+                    let Some(reply) = bot.reply(&message.chat_id, &message.text, now).await else {
+                        continue;
+                    };
+                    if let Err(error) = api.send_message(&message.chat_id, &reply).await {
+                        Display::warning_message(error);
+                    }
+                }
+            }
+            Err(error) => {
+                Display::warning_message(error);
+                // Espera um pouco antes de tentar de novo, para não insistir em um servidor fora do ar
+                tokio::time::sleep(Duration::from_secs(5)).await;
+            }
+        }
+
+        match bot.due_pushes(Local::now().naive_local()).await {
+            Ok(pushes) => {
+                for (chat_id, message) in pushes {
+                    if let Err(error) = api.send_message(&chat_id, &message).await {
+                        Display::warning_message(error);
+                    }
+                }
+            }
+            Err(error) => Display::warning_message(error),
+        }
+    }
+}
+
+/// Serve a API REST dos cardápios em `host:port`, com os cardápios em cache por `ttl` minutos.
+pub async fn serve(
+    config: &Config,
+    sources: &Sources,
+    host: String,
+    port: u16,
+    ttl: u64,
+) -> Result<(), Error> {
+    let server = tiny_http::Server::http((host.as_str(), port)).map_err(|error| {
+        Error::other(format!(
+            "Não foi possível servir a API em {host}:{port}: {error}"
+        ))
+    })?;
+    println!("   ➤  API dos cardápios em http://{host}:{port}\n");
+
+    let menus_cache = menus_cache(sources).with_ttl(Some(Duration::from_secs(ttl * 60)));
+    let mut api = Api::new(menus_cache, config);
+    loop {
+        // As requisições são atendidas uma de cada vez, para compartilhar o cache
+        let request = tokio::task::block_in_place(|| server.recv())?;
+        let response = api
+            .handle(
+                request.method().as_str(),
+                request.url(),
+                Local::now().fixed_offset(),
+            )
+            .await;
+
+        let header = tiny_http::Header::from_bytes("Content-Type", response.content_type)
+            .expect("cabeçalho válido");
+        let http_response = tiny_http::Response::from_string(response.body)
+            .with_status_code(response.status)
+            .with_header(header);
+        if let Err(error) = request.respond(http_response) {
+            Display::warning_message(format!("Erro ao responder a requisição: {error}"));
+        }
+    }
+}
+
+/// Mostra as mudanças nos cardápios da semana dos restaurantes `restaurants` (ou dos
+/// restaurantes das configurações, se vazio) desde as primeiras versões guardadas no histórico.
+pub async fn diff(
+    config: &Config,
+    sources: &Sources,
+    restaurants: Vec<RestaurantID>,
+) -> Result<(), Error> {
+    let Some(history) = open_history() else {
+        return Err(Error::new(
+            ErrorKind::NotFound,
+            "Não foi possível abrir o histórico dos cardápios",
+        ));
+    };
+
+    let restaurants = if restaurants.is_empty() {
+        config
+            .restaurants
+            .iter()
+            .map(|restaurant| restaurant.id)
+            .collect()
+    } else {
+        restaurants
+    };
+
+    let mut menus_cache = MenusCache::new()
+        .with_sources(sources.clone())
+        .with_history(Some(history))
+        .with_history_error_handler(show_history_error);
+    let mut changes = Vec::new();
+    for restaurant_id in restaurants {
+        let Some((name, _)) = menus_cache.get_name_and_menus(restaurant_id).await else {
+            show_unavailable(&[restaurant_id]);
+            continue;
+        };
+
+        let color = config
+            .restaurants
+            .iter()
+            .find(|restaurant| restaurant.id == restaurant_id)
+            .map_or(DEFAULT_COLOR, |restaurant| restaurant.color);
+        changes.push((name, color, menus_cache.get_changes(restaurant_id).await));
+    }
+
+    Display::show_changes(&changes);
+    Ok(())
+}
+
+/// Mostra as estatísticas dos cardápios do histórico entre as datas `from` e `to` dos
+/// restaurantes `restaurants` (ou de todos, se vazio), em tabelas ou, com `json`, em JSON.
+pub fn stats(
+    config: &Config,
+    from: Option<NaiveDate>,
+    to: Option<NaiveDate>,
+    restaurants: Vec<RestaurantID>,
+    top: usize,
+    json: bool,
+) -> Result<(), Error> {
+    let Some(history) = open_history() else {
+        return Err(Error::new(
+            ErrorKind::NotFound,
+            "Não foi possível abrir o histórico dos cardápios",
+        ));
+    };
+
+    let query = HistoryQuery {
+        from,
+        to,
+        restaurants,
+    };
+    let entries = history
+        .query(&query)
+        .map_err(|error| Error::other(format!("Erro ao consultar o histórico: {error}")))?;
+    let stats = stats::compute(&entries, config, Local::now().date_naive(), top);
+
+    if json {
+        let content = serde_json::to_string_pretty(&stats)
+            .map_err(|error| Error::other(format!("Erro ao gerar o JSON: {error}")))?;
+        println!("{content}");
+    } else {
+        Display::show_stats(&stats, config);
+    }
+    Ok(())
+}
+
+/// Recomenda onde comer na refeição escolhida por `-a`/`-j` e `-w` ou, se nenhuma for
+/// escolhida, na próxima refeição.
+pub async fn best(options: &CliOptions, config: &Config, sources: &Sources) {
+    let (weekday, menu_type) = match (options.weekday, &options.menu_type) {
+        (Some(weekday), Some(menu_type)) => (weekday, menu_type.clone()),
+        _ => get_next_meal(Local::now().naive_local()),
+    };
+
+    let (ranked, unavailable) =
+        recommend::candidates(&mut menus_cache(sources), config, weekday, &menu_type).await;
+    show_unavailable(&unavailable);
+    Display::show_recommendation(weekday, &menu_type, &ranked);
+}
+
+/// Procura o prato `term` nos cardápios da semana dos restaurantes configurados e, com
+/// `all_restaurants`, também nos restaurantes conhecidos pelas fontes fora das configurações.
+pub async fn search(
+    config: &Config,
+    sources: &Sources,
+    term: String,
+    match_kind: FoodMatch,
+    all_restaurants: bool,
+) -> Result<(), Error> {
+    let food = FoodConfig::new(term.clone(), None)
+        .with_modifiers(match_kind, Vec::new())
+        .map_err(|error| {
+            Error::new(
+                ErrorKind::InvalidInput,
+                format!("Expressão regular inválida: {error}"),
+            )
+        })?;
+
+    let (hits, unavailable) =
+        search::search_restaurants(&mut menus_cache(sources), config, &food, all_restaurants).await;
+    show_unavailable(&unavailable);
+    Display::show_search_results(&term, &hits);
+    Ok(())
+}
+
+/// Executa um subcomando de `bandex config`.
+pub async fn run_config_command(
+    command: ConfigCommand,
+    options: &CliOptions,
+    config_files: &[ConfigFile],
+) -> Result<(), Error> {
+    match command {
+        ConfigCommand::Path => {
+            let loaded_config = load_config(config_files, options.strict_config);
+            Display::show_config_files(config_files, &loaded_config);
+        }
+        ConfigCommand::Init { output, force } => init(options, output, force).await?,
+        ConfigCommand::Validate => validate(config_files, options.strict_config),
+        ConfigCommand::Show => {
+            let loaded_config = load_config(config_files, options.strict_config);
+            print!("{}", loaded_config.config.to_format(ConfigFormat::Yaml));
+        }
+        ConfigCommand::AddFood {
+            list,
+            name,
+            restaurants,
+        } => add_food(options, list, name, restaurants)?,
+        ConfigCommand::Schema => print!("{}", config::config_schema()),
+        ConfigCommand::Convert {
+            input,
+            output,
+            to,
+            force,
+        } => convert(options, input, output, to, force)?,
+    }
+
+    Ok(())
+}
@@ -2,24 +2,54 @@
 Leitura do arquivo de configuração
 
 Este módulo contém as estruturas e funções responsáveis pela leitura e
-interpretação do arquivo de configuração YAML do Bandex.
+interpretação do arquivo de configuração do Bandex, que pode ser escrito em
+YAML, TOML ou JSON (o formato é detectado pela extensão do arquivo).
 
 O arquivo de configuração permite personalizar os restaurantes, definindo seus
 IDs e cores para visualização. Se nenhum arquivo for fornecido ou se o arquivo
 estiver inválido, serão utilizadas as configurações padrão.
+
+Além do arquivo passado pelo CLI, os arquivos de configuração são procurados
+automaticamente e aplicados em camadas, cada uma sobre a anterior:
+1. Sistema: `$XDG_CONFIG_DIRS/bandex/config.yaml` (por padrão, `/etc/xdg/bandex/config.yaml`)
+2. Usuário: `~/.bandex.yaml` e `$XDG_CONFIG_HOME/bandex/config.yaml` (por padrão, `~/.config/bandex/config.yaml`)
+3. Local: `.bandex.yaml` no diretório atual ou no diretório pai mais próximo
+4. CLI: arquivo passado por `-c` ou pela variável de ambiente `BANDEX_CONFIG_FILE`
+
+Nos arquivos procurados automaticamente, a extensão `.yaml` pode ser trocada por `.toml` ou `.json`.
+
+A leitura é feita com o `serde`, de modo que qualquer valor inválido gera um erro
+indicando o arquivo, a linha, a coluna e a chave com problema. Chaves desconhecidas
+são ignoradas e geram avisos, a não ser no modo estrito, em que geram erros.
+
+O JSON Schema do arquivo de configuração (`bandex-config.schema.json`), usado pelos
+editores para o autocompletar, é gerado a partir das mesmas estruturas com o `schemars`
+(veja [`config_schema`]).
 */
 
 use std::{
-    env, fs,
+    borrow::Cow,
+    env, fmt, fs,
     io::{Error, ErrorKind},
     path::{Path, PathBuf},
+    str::FromStr,
     vec::Vec,
 };
 
 use colored::Color;
-use yaml_rust::{yaml, Yaml, YamlLoader};
+use regex::{Regex, RegexBuilder};
+use schemars::{generate::SchemaSettings, json_schema, JsonSchema, Schema, SchemaGenerator};
+use serde::{
+    de::{self, MapAccess, SeqAccess, Visitor},
+    ser::{SerializeMap, SerializeSeq},
+    Deserialize, Deserializer, Serialize, Serializer,
+};
+use serde_yaml::{Mapping, Value};
 
-use crate::types::RestaurantID;
+use crate::{
+    diet::DietConfig, menus_file::MenusFile, source::SourceKind, text, types::RestaurantID,
+    watch::MealHours,
+};
 
 /// Padrão para configuração dos restaurantes, definido com os bandeijões da USP São Paulo.
 /// * 8: Restaurante da Física
@@ -34,147 +64,1293 @@ const DEFAULT_RESTAURANTS: [(RestaurantID, Color); 4] = [
 ];
 
 /// Cor padrão dos restaurantes.
-const DEFAULT_COLOR: Color = Color::White;
+pub const DEFAULT_COLOR: Color = Color::White;
 
 /// Variável de ambiente para o arquivo de configuração.
 const ENV_VAR_BANDEX_CONFIG: &str = "BANDEX_CONFIG_FILE";
 
-/// Macro para converter um valor em uma string YAML.
-macro_rules! to_yaml_str {
-    ( $value:expr ) => {{
-        &Yaml::String($value.to_string())
-    }};
+/// Variável de ambiente com o diretório de configurações do usuário (especificação XDG).
+const ENV_VAR_XDG_CONFIG_HOME: &str = "XDG_CONFIG_HOME";
+
+/// Variável de ambiente com os diretórios de configurações do sistema (especificação XDG).
+const ENV_VAR_XDG_CONFIG_DIRS: &str = "XDG_CONFIG_DIRS";
+
+/// Diretórios de configurações do sistema usados quando `$XDG_CONFIG_DIRS` não está definido.
+const DEFAULT_XDG_CONFIG_DIRS: &str = "/etc/xdg";
+
+/// Arquivo de configurações dentro dos diretórios XDG, sem a extensão.
+const XDG_CONFIG_FILE: &str = "bandex/config";
+
+/// Arquivo de configurações no diretório do usuário, sem a extensão.
+const HOME_CONFIG_FILE: &str = ".bandex";
+
+/// Arquivo de configurações de um projeto, procurado no diretório atual e nos diretórios pais,
+/// sem a extensão.
+const LOCAL_CONFIG_FILE: &str = ".bandex";
+
+/// Cabeçalho dos arquivos de configuração criados pelo bandex, que ativa o autocompletar
+/// nos editores com suporte ao `yaml-language-server`.
+pub const SCHEMA_HEADER: &str = "# yaml-language-server: $schema=https://raw.githubusercontent.com/mocno/bandex/master/bandex-config.schema.json";
+
+/// Problema encontrado na leitura do arquivo de configuração.
+///
+/// É usado tanto para os erros (que impedem a leitura) quanto para os avisos
+/// (entradas ignoradas). Sempre que possível, indica o arquivo, a posição
+/// (linha e coluna) e a chave com problema.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ConfigDiagnostic {
+    /// Arquivo de configuração, se a leitura foi feita a partir de um arquivo.
+    pub file: Option<PathBuf>,
+
+    /// Linha e coluna do problema no arquivo (começando em 1).
+    pub location: Option<(usize, usize)>,
+
+    /// Chave com problema, por exemplo: `bandex.restaurants[0].color`.
+    pub key: Option<String>,
+
+    /// Descrição do problema.
+    pub message: String,
+}
+
+impl ConfigDiagnostic {
+    /// Cria um problema com a mensagem `message`, sem arquivo nem posição.
+    pub fn new(message: impl Into<String>) -> Self {
+        ConfigDiagnostic {
+            file: None,
+            location: None,
+            key: None,
+            message: message.into(),
+        }
+    }
+
+    /// Define o arquivo em que o problema foi encontrado.
+    fn in_file(mut self, file: Option<&Path>) -> Self {
+        self.file = file.map(Path::to_path_buf);
+        self
+    }
+
+    /// Cria um diagnóstico a partir de um erro de leitura, extraindo a chave com problema.
+    ///
+    /// A função `describe` extrai, do erro do formato do arquivo, a mensagem e a posição.
+    fn from_path_error<E>(
+        error: serde_path_to_error::Error<E>,
+        describe: impl FnOnce(E) -> (String, Option<(usize, usize)>),
+    ) -> Self {
+        let key = error.path().to_string();
+        let key = (key != ".").then_some(key);
+        let (message, location) = describe(error.into_inner());
+
+        ConfigDiagnostic::from_parts(key, message, location)
+    }
+
+    /// Cria um diagnóstico com a chave, a mensagem e a posição de um erro de leitura.
+    fn from_parts(
+        key: Option<String>,
+        mut message: String,
+        location: Option<(usize, usize)>,
+    ) -> Self {
+        // O serde_yaml e o serde_json já incluem a chave e a posição na mensagem, mas esses
+        // dados são exibidos à parte
+        if let Some((line, column)) = location {
+            message = message.replace(&format!(" at line {line} column {column}"), "");
+        }
+        if let Some(key) = &key {
+            if let Some(stripped) = message.strip_prefix(&format!("{key}: ")) {
+                message = stripped.to_string();
+            }
+        }
+
+        ConfigDiagnostic {
+            file: None,
+            location,
+            key,
+            message,
+        }
+    }
+}
+
+impl fmt::Display for ConfigDiagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if let Some(file) = &self.file {
+            write!(f, "{}:", file.display())?;
+            if let Some((line, column)) = self.location {
+                write!(f, "{line}:{column}:")?;
+            }
+            write!(f, " ")?;
+        } else if let Some((line, column)) = self.location {
+            write!(f, "linha {line}, coluna {column}: ")?;
+        }
+
+        if let Some(key) = &self.key {
+            write!(f, "`{key}`: ")?;
+        }
+
+        write!(f, "{}", self.message)
+    }
+}
+
+impl std::error::Error for ConfigDiagnostic {}
+
+impl From<ConfigDiagnostic> for Error {
+    fn from(diagnostic: ConfigDiagnostic) -> Self {
+        Error::new(ErrorKind::InvalidData, diagnostic)
+    }
+}
+
+/// Descreve um erro do `serde_yaml`, com a mensagem e a posição.
+fn describe_yaml_error(error: serde_yaml::Error) -> (String, Option<(usize, usize)>) {
+    let location = error
+        .location()
+        .map(|location| (location.line(), location.column()));
+    (error.to_string(), location)
+}
+
+/// Descreve um erro do `serde_json`, com a mensagem e a posição.
+fn describe_json_error(error: serde_json::Error) -> (String, Option<(usize, usize)>) {
+    let location = (error.line() > 0).then(|| (error.line(), error.column()));
+    (error.to_string(), location)
+}
+
+/// Descreve um erro do `toml`, com a mensagem e a posição, calculada a partir do conteúdo
+/// do arquivo (`contents`).
+fn describe_toml_error(contents: &str, error: toml::de::Error) -> (String, Option<(usize, usize)>) {
+    let location = error.span().map(|span| {
+        let before = &contents[..span.start.min(contents.len())];
+        let line = before.matches('\n').count() + 1;
+        let column = before.chars().rev().take_while(|&c| c != '\n').count() + 1;
+        (line, column)
+    });
+    (error.message().to_string(), location)
+}
+
+/// Formato de um arquivo de configurações.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ConfigFormat {
+    /// YAML (`.yaml` ou `.yml`), o formato padrão.
+    #[default]
+    Yaml,
+
+    /// TOML (`.toml`).
+    Toml,
+
+    /// JSON (`.json`).
+    Json,
+}
+
+impl ConfigFormat {
+    /// Formatos aceitos, na ordem em que os arquivos são procurados.
+    pub const ALL: [ConfigFormat; 3] = [ConfigFormat::Yaml, ConfigFormat::Toml, ConfigFormat::Json];
+
+    /// Extensão dos arquivos do formato.
+    pub fn extension(&self) -> &'static str {
+        match self {
+            ConfigFormat::Yaml => "yaml",
+            ConfigFormat::Toml => "toml",
+            ConfigFormat::Json => "json",
+        }
+    }
+
+    /// Detecta o formato de um arquivo pela sua extensão.
+    pub fn from_path(path: &Path) -> Option<ConfigFormat> {
+        path.extension()?.to_str()?.to_lowercase().parse().ok()
+    }
+
+    /// Escreve um valor no formato.
+    fn to_string<T: Serialize>(self, value: &T) -> Result<String, String> {
+        match self {
+            ConfigFormat::Yaml => serde_yaml::to_string(value).map_err(|error| error.to_string()),
+            ConfigFormat::Toml => toml::to_string(value).map_err(|error| error.to_string()),
+            ConfigFormat::Json => serde_json::to_string_pretty(value)
+                .map(|json| json + "\n")
+                .map_err(|error| error.to_string()),
+        }
+    }
+
+    /// Lê um valor qualquer no formato, para que ele seja alterado e escrito novamente.
+    ///
+    /// O conteúdo vazio é lido como um valor nulo.
+    fn parse_value(self, contents: &str) -> Result<Value, ConfigDiagnostic> {
+        if contents.trim().is_empty() {
+            return Ok(Value::Null);
+        }
+
+        let (message, location) = match self {
+            ConfigFormat::Yaml => match serde_yaml::from_str(contents) {
+                Ok(value) => return Ok(value),
+                Err(error) => describe_yaml_error(error),
+            },
+            ConfigFormat::Toml => match toml::from_str(contents) {
+                Ok(value) => return Ok(value),
+                Err(error) => describe_toml_error(contents, error),
+            },
+            ConfigFormat::Json => match serde_json::from_str(contents) {
+                Ok(value) => return Ok(value),
+                Err(error) => describe_json_error(error),
+            },
+        };
+
+        Err(ConfigDiagnostic::from_parts(None, message, location))
+    }
+
+    /// Lê um valor do tipo `T` no formato, com a chave e a posição dos valores inválidos nos
+    /// erros, como nos arquivos de configurações.
+    pub fn parse<T: de::DeserializeOwned>(self, contents: &str) -> Result<T, ConfigDiagnostic> {
+        match self {
+            ConfigFormat::Yaml => {
+                serde_path_to_error::deserialize(serde_yaml::Deserializer::from_str(contents))
+                    .map_err(|error| ConfigDiagnostic::from_path_error(error, describe_yaml_error))
+            }
+            ConfigFormat::Toml => {
+                let describe = |error| describe_toml_error(contents, error);
+                let deserializer = toml::Deserializer::parse(contents).map_err(|error| {
+                    let (message, location) = describe(error);
+                    ConfigDiagnostic::from_parts(None, message, location)
+                })?;
+                serde_path_to_error::deserialize(deserializer)
+                    .map_err(|error| ConfigDiagnostic::from_path_error(error, describe))
+            }
+            ConfigFormat::Json => {
+                let mut deserializer = serde_json::Deserializer::from_str(contents);
+                serde_path_to_error::deserialize(&mut deserializer)
+                    .map_err(|error| ConfigDiagnostic::from_path_error(error, describe_json_error))
+            }
+        }
+    }
+
+    /// Conteúdo de um arquivo criado pelo bandex: nos arquivos YAML, adiciona o cabeçalho
+    /// do schema ([`SCHEMA_HEADER`]) antes das configurações.
+    pub fn file_contents(&self, contents: &str) -> String {
+        match self {
+            ConfigFormat::Yaml => format!("{SCHEMA_HEADER}\n{contents}"),
+            ConfigFormat::Toml | ConfigFormat::Json => contents.to_string(),
+        }
+    }
+}
+
+impl FromStr for ConfigFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "yaml" | "yml" => Ok(ConfigFormat::Yaml),
+            "toml" => Ok(ConfigFormat::Toml),
+            "json" => Ok(ConfigFormat::Json),
+            _ => Err(format!(
+                "Formato de configuração \"{s}\" desconhecido, use \"yaml\", \"toml\" ou \"json\""
+            )),
+        }
+    }
+}
+
+impl fmt::Display for ConfigFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.extension())
+    }
 }
 
 /// Configurações de um restaurante como cor e ID.
-#[derive(Debug, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize, JsonSchema)]
+#[schemars(title = "Configuração do restaurante", deny_unknown_fields)]
 pub struct RestaurantConfig {
+    /// ID do restaurante no sistema da USP ou o escolhido para o arquivo de cardápios.
+    #[schemars(title = "ID do restaurante")]
     pub id: RestaurantID,
+
+    /// Cor em que o restaurante será mostrado
+    #[serde(
+        default = "default_color",
+        deserialize_with = "deserialize_color",
+        serialize_with = "serialize_color"
+    )]
+    #[schemars(schema_with = "color_schema")]
     pub color: Color,
+
+    /// Horários do almoço e do jantar, usados nas notificações.
+    #[serde(default, skip_serializing_if = "MealHours::is_empty")]
+    #[schemars(title = "Horários das refeições")]
+    pub hours: MealHours,
+
+    /// Fonte de onde os cardápios do restaurante são carregados.
+    #[serde(default, skip_serializing_if = "SourceKind::is_default")]
+    #[schemars(title = "Fonte dos cardápios")]
+    pub source: SourceKind,
+
+    /// Caminho do arquivo de cardápios, usado com `source: file`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[schemars(title = "Arquivo dos cardápios (com `source: file`)")]
+    pub path: Option<PathBuf>,
 }
 
 impl RestaurantConfig {
+    /// Cria as configurações do restaurante `id` com a cor `color`, os horários padrão e o
+    /// sistema da USP como fonte.
     pub fn new(id: RestaurantID, color: Color) -> Self {
-        RestaurantConfig { id, color }
+        RestaurantConfig {
+            id,
+            color,
+            hours: MealHours::default(),
+            source: SourceKind::default(),
+            path: None,
+        }
     }
 }
 
-impl TryFrom<&Yaml> for RestaurantConfig {
-    type Error = Error;
+/// Cor usada quando o restaurante não define uma cor.
+fn default_color() -> Color {
+    DEFAULT_COLOR
+}
 
-    // Tenta ler as configurações dos restaurantes a partir de um YAML
-    fn try_from(value: &Yaml) -> Result<Self, Self::Error> {
-        let Some(restaurant_config) = value.as_hash() else {
-            return Err(Error::new(
-                ErrorKind::InvalidData,
-                "Não foi possível ler a configuração do restaurante",
-            ));
-        };
+/// Lê uma cor pelo nome (por exemplo: `blue`) ou por RGB (por exemplo: `[187, 35, 51]`).
+fn deserialize_color<'de, D>(deserializer: D) -> Result<Color, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    struct ColorVisitor;
 
-        let Some(id) = restaurant_config
-            .get(to_yaml_str!("id"))
-            .and_then(|yaml| yaml.as_i64())
-        else {
-            return Err(Error::new(
-                ErrorKind::InvalidData,
-                "Todo restaurante deve ter um ID válido",
-            ));
-        };
+    impl<'de> Visitor<'de> for ColorVisitor {
+        type Value = Color;
 
-        let color = match restaurant_config.get(to_yaml_str!("color")) {
-            Some(Yaml::String(color)) => color.parse().ok(),
-            Some(Yaml::Array(values)) if values.len() == 3 => {
-                let r = values[0].as_i64().unwrap_or(0) as u8;
-                let g = values[1].as_i64().unwrap_or(0) as u8;
-                let b = values[2].as_i64().unwrap_or(0) as u8;
+        fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+            formatter.write_str("o nome de uma cor ou uma lista RGB [r, g, b]")
+        }
+
+        fn visit_str<E: de::Error>(self, value: &str) -> Result<Color, E> {
+            value
+                .parse()
+                .map_err(|_| E::custom(format!("cor inválida \"{value}\"")))
+        }
 
-                Some(Color::TrueColor { r, g, b })
+        fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Color, A::Error> {
+            let mut rgb = [0u8; 3];
+            for (index, value) in rgb.iter_mut().enumerate() {
+                *value = seq
+                    .next_element()?
+                    .ok_or_else(|| de::Error::invalid_length(index, &self))?;
             }
-            None => Some(DEFAULT_COLOR),
-            _ => None,
-        };
 
-        let Some(color) = color else {
-            return Err(Error::new(ErrorKind::InvalidData, "Cor inválida"));
-        };
+            if seq.next_element::<de::IgnoredAny>()?.is_some() {
+                return Err(de::Error::custom(
+                    "a cor em RGB deve ter exatamente 3 valores",
+                ));
+            }
+
+            let [r, g, b] = rgb;
+            Ok(Color::TrueColor { r, g, b })
+        }
+    }
+
+    deserializer.deserialize_any(ColorVisitor)
+}
+
+/// Nome de uma cor, no formato aceito pelo arquivo de configurações.
+///
+/// Retorna `None` para as cores em RGB.
+pub fn color_name(color: Color) -> Option<&'static str> {
+    Some(match color {
+        Color::Black => "black",
+        Color::Red => "red",
+        Color::Green => "green",
+        Color::Yellow => "yellow",
+        Color::Blue => "blue",
+        Color::Magenta => "magenta",
+        Color::Cyan => "cyan",
+        Color::White => "white",
+        Color::BrightBlack => "bright black",
+        Color::BrightRed => "bright red",
+        Color::BrightGreen => "bright green",
+        Color::BrightYellow => "bright yellow",
+        Color::BrightBlue => "bright blue",
+        Color::BrightMagenta => "bright magenta",
+        Color::BrightCyan => "bright cyan",
+        Color::BrightWhite => "bright white",
+        Color::TrueColor { .. } => return None,
+    })
+}
+
+/// Nomes de cores aceitos no arquivo de configurações, além das cores em RGB.
+///
+/// São os nomes das cores do `colored` (incluindo o sinônimo `purple`), na forma em
+/// que são escritos por [`color_name`].
+const COLOR_NAMES: [&str; 17] = [
+    "black",
+    "red",
+    "green",
+    "yellow",
+    "blue",
+    "magenta",
+    "purple",
+    "cyan",
+    "white",
+    "bright black",
+    "bright red",
+    "bright green",
+    "bright yellow",
+    "bright blue",
+    "bright magenta",
+    "bright cyan",
+    "bright white",
+];
+
+/// Schema de uma cor, no mesmo formato aceito por `deserialize_color`.
+fn color_schema(_: &mut SchemaGenerator) -> Schema {
+    json_schema!({
+        "title": "Cor do restaurante",
+        "description": "Cor em que o restaurante será mostrado",
+        "default": color_name(DEFAULT_COLOR),
+        "anyOf": [
+            {
+                "type": "string",
+                "enum": COLOR_NAMES
+            },
+            {
+                "type": "array",
+                "minItems": 3,
+                "maxItems": 3,
+                "items": {
+                    "type": "integer",
+                    "minimum": 0,
+                    "maximum": 255
+                }
+            }
+        ]
+    })
+}
 
-        Ok(RestaurantConfig::new(id as usize, color))
+/// Escreve uma cor pelo nome ou, se for uma cor RGB, pela lista `[r, g, b]`.
+fn serialize_color<S: Serializer>(color: &Color, serializer: S) -> Result<S::Ok, S::Error> {
+    match (color_name(*color), color) {
+        (Some(name), _) => serializer.serialize_str(name),
+        (None, Color::TrueColor { r, g, b }) => {
+            let mut seq = serializer.serialize_seq(Some(3))?;
+            for value in [r, g, b] {
+                seq.serialize_element(value)?;
+            }
+            seq.end()
+        }
+        (None, _) => unreachable!("apenas as cores RGB não possuem nome"),
+    }
+}
+
+/// Forma como o nome de uma comida é procurado nas linhas dos cardápios.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum FoodMatch {
+    /// O nome aparece em qualquer parte da linha (`pudim` ou `name: pudim`).
+    #[default]
+    Contains,
+    /// O nome aparece como uma ou mais palavras inteiras (`word: ovo`).
+    Word,
+    /// A linha corresponde a uma expressão regular (`regex: ^arroz`).
+    Regex,
+}
+
+impl FoodMatch {
+    /// Chave que define a comida no formato com modificadores.
+    pub fn key(&self) -> &'static str {
+        match self {
+            FoodMatch::Contains => "name",
+            FoodMatch::Word => "word",
+            FoodMatch::Regex => "regex",
+        }
     }
 }
 
+/// Chaves aceitas no formato de comida com modificadores (`word: ovo`, `except: [...]`, etc).
+const FOOD_MODIFIER_KEYS: [&str; 6] = ["name", "word", "regex", "except", "restaurants", "weight"];
+
+/// Nome da comida preparado para a comparação com as linhas dos cardápios.
+#[derive(Debug, Clone)]
+enum FoodMatcher {
+    /// Nome normalizado (veja [`text::normalize`]), usado em [`FoodMatch::Contains`] e [`FoodMatch::Word`].
+    Normalized(String),
+    /// Expressão regular compilada, usada em [`FoodMatch::Regex`].
+    Regex(Regex),
+}
+
 /// Representa uma configuração de uma comida - relacionando uma comida com um
 /// ou alguns restaurantes
-#[derive(Debug)]
+///
+/// A comida pode ser escrita apenas pelo nome (`pudim`), pelo nome com os restaurantes
+/// (`pudim: [6, 8]`), pelo nome com o peso (`pudim: +3`) ou com modificadores, que mudam
+/// a forma como ela é procurada:
+///
+/// ```yaml
+/// - word: ovo                  # apenas a palavra inteira, não "ovomaltine"
+/// - regex: "^arroz (branco|integral)"
+///   restaurants: [6]
+/// - name: carne
+///   except: [carne de soja]    # linhas que não devem ser consideradas
+///   weight: -5
+/// ```
+#[derive(Debug, Clone)]
 pub struct FoodConfig {
+    /// Nome da comida (ou expressão regular), como foi escrito no arquivo de configurações.
     pub name: String,
+
+    /// Restaurantes em que a comida é procurada, ou `None` para todos.
     pub restaurants: Option<Vec<u64>>,
+
+    /// Forma como o nome é procurado nas linhas dos cardápios.
+    pub match_kind: FoodMatch,
+
+    /// Comidas que, se estiverem na linha, fazem com que ela não seja considerada.
+    pub except: Vec<String>,
+
+    /// Peso da comida na nota dos cardápios, ou `None` para o peso padrão da lista
+    /// (veja [`FoodList::default_weight`]).
+    pub weight: Option<i32>,
+
+    /// Nome preparado para a comparação, criado uma única vez, na leitura das configurações.
+    matcher: FoodMatcher,
+
+    /// Exceções normalizadas (veja [`text::normalize`]).
+    normalized_except: Vec<String>,
+}
+
+impl PartialEq for FoodConfig {
+    fn eq(&self, other: &Self) -> bool {
+        self.name == other.name
+            && self.restaurants == other.restaurants
+            && self.match_kind == other.match_kind
+            && self.except == other.except
+            && self.weight == other.weight
+    }
 }
 
 impl FoodConfig {
+    /// Cria a comida `name`, procurada sem diferenciar maiúsculas, acentos e plural nos
+    /// restaurantes `restaurants` (ou em todos, se `None`).
     pub fn new(name: String, restaurants: Option<Vec<u64>>) -> Self {
-        FoodConfig { name, restaurants }
+        let matcher = FoodMatcher::Normalized(text::normalize(&name));
+        FoodConfig {
+            name,
+            restaurants,
+            match_kind: FoodMatch::Contains,
+            except: Vec::new(),
+            weight: None,
+            matcher,
+            normalized_except: Vec::new(),
+        }
+    }
+
+    /// Define a forma como a comida é procurada e as suas exceções.
+    ///
+    /// Retorna um erro se a comida for uma expressão regular inválida.
+    pub fn with_modifiers(
+        mut self,
+        match_kind: FoodMatch,
+        except: Vec<String>,
+    ) -> Result<Self, regex::Error> {
+        if match_kind == FoodMatch::Regex {
+            let regex = RegexBuilder::new(&self.name)
+                .case_insensitive(true)
+                .build()?;
+            self.matcher = FoodMatcher::Regex(regex);
+        }
+        self.match_kind = match_kind;
+        self.normalized_except = except.iter().map(|name| text::normalize(name)).collect();
+        self.except = except;
+
+        Ok(self)
+    }
+
+    /// Verifica se duas comidas são iguais, desconsiderando a forma como os nomes foram escritos.
+    pub fn same_food(&self, other: &FoodConfig) -> bool {
+        let same_name = match (&self.matcher, &other.matcher) {
+            (FoodMatcher::Normalized(name), FoodMatcher::Normalized(other_name)) => {
+                name == other_name
+            }
+            _ => self.name == other.name,
+        };
+
+        same_name
+            && self.match_kind == other.match_kind
+            && self.normalized_except == other.normalized_except
+            && self.restaurants == other.restaurants
+    }
+
+    /// Verifica se a comida não possui um nome (ignorando os espaços e a pontuação).
+    pub fn is_unnamed(&self) -> bool {
+        match &self.matcher {
+            FoodMatcher::Normalized(name) => name.is_empty(),
+            FoodMatcher::Regex(regex) => regex.as_str().is_empty(),
+        }
+    }
+
+    /// Define o peso da comida na nota dos cardápios.
+    pub fn with_weight(mut self, weight: Option<i32>) -> Self {
+        self.weight = weight;
+        self
+    }
+
+    /// Verifica se a comida precisa ser escrita com os modificadores, ou seja, se ela não pode
+    /// ser escrita apenas pelo nome, com os restaurantes ou com o peso.
+    fn has_modifiers(&self) -> bool {
+        self.match_kind != FoodMatch::Contains
+            || !self.except.is_empty()
+            || (self.restaurants.is_some() && self.weight.is_some())
     }
 }
 
-impl TryFrom<&Yaml> for FoodConfig {
-    type Error = Error;
+/// Valor de uma comida escrita como `comida: valor`: os restaurantes (`pudim: [6, 8]`)
+/// ou o peso (`pudim: +3`).
+enum FoodValue {
+    Restaurants(Vec<u64>),
+    Weight(i32),
+}
 
-    // Tenta ler as configurações das comidas a partir de um YAML
-    fn try_from(yaml: &Yaml) -> Result<Self, Self::Error> {
-        match yaml {
-            Yaml::String(value) => Ok(FoodConfig::new(value.clone(), None)),
-            Yaml::Hash(value) if value.len() == 1 => {
-                let (key, value) = value.iter().next().unwrap();
+impl<'de> Deserialize<'de> for FoodValue {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct FoodValueVisitor;
+
+        impl<'de> Visitor<'de> for FoodValueVisitor {
+            type Value = FoodValue;
+
+            fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+                formatter.write_str("uma lista de IDs dos restaurantes ou o peso da comida")
+            }
+
+            fn visit_i64<E: de::Error>(self, value: i64) -> Result<FoodValue, E> {
+                i32::try_from(value)
+                    .map(FoodValue::Weight)
+                    .map_err(|_| E::custom(format!("peso {value} muito grande")))
+            }
+
+            fn visit_u64<E: de::Error>(self, value: u64) -> Result<FoodValue, E> {
+                i32::try_from(value)
+                    .map(FoodValue::Weight)
+                    .map_err(|_| E::custom(format!("peso {value} muito grande")))
+            }
+
+            fn visit_seq<A: SeqAccess<'de>>(self, seq: A) -> Result<FoodValue, A::Error> {
+                Vec::deserialize(de::value::SeqAccessDeserializer::new(seq))
+                    .map(FoodValue::Restaurants)
+            }
+        }
 
-                let Some(key) = key.as_str() else {
-                    return Err(Error::new(ErrorKind::InvalidData, "Comida inválida"));
+        deserializer.deserialize_any(FoodValueVisitor)
+    }
+}
+
+/// Lê uma comida no formato com modificadores, em que a primeira chave (`first_key`) já foi lida.
+fn deserialize_food_modifiers<'de, A: MapAccess<'de>>(
+    first_key: String,
+    mut map: A,
+) -> Result<FoodConfig, A::Error> {
+    let mut pattern: Option<(FoodMatch, String)> = None;
+    let mut except = None;
+    let mut restaurants = None;
+    let mut weight = None;
+
+    let mut key = Some(first_key);
+    while let Some(current_key) = key {
+        match current_key.as_str() {
+            "name" | "word" | "regex" => {
+                let match_kind = match current_key.as_str() {
+                    "name" => FoodMatch::Contains,
+                    "word" => FoodMatch::Word,
+                    _ => FoodMatch::Regex,
                 };
-                let Some(restaurants) = value.as_vec() else {
-                    return Err(Error::new(
-                        ErrorKind::InvalidData,
-                        "Restaurante das comidas inválida",
-                    ));
+                if let Some((previous, _)) = pattern {
+                    return Err(de::Error::custom(format!(
+                        "a comida deve ter apenas uma das chaves `name`, `word` ou `regex`, mas tem `{}` e `{}`",
+                        previous.key(),
+                        match_kind.key()
+                    )));
+                }
+                pattern = Some((match_kind, map.next_value()?));
+            }
+            "except" => {
+                if except.is_some() {
+                    return Err(de::Error::duplicate_field("except"));
+                }
+                except = Some(map.next_value::<Vec<String>>()?);
+            }
+            "restaurants" => {
+                if restaurants.is_some() {
+                    return Err(de::Error::duplicate_field("restaurants"));
+                }
+                restaurants = Some(map.next_value::<Vec<u64>>()?);
+            }
+            "weight" => {
+                if weight.is_some() {
+                    return Err(de::Error::duplicate_field("weight"));
+                }
+                weight = Some(map.next_value::<i32>()?);
+            }
+            _ => return Err(de::Error::unknown_field(&current_key, &FOOD_MODIFIER_KEYS)),
+        }
+        key = map.next_key()?;
+    }
+
+    let Some((match_kind, name)) = pattern else {
+        return Err(de::Error::custom(
+            "a comida deve ter uma das chaves `name`, `word` ou `regex`",
+        ));
+    };
+
+    FoodConfig::new(name, restaurants)
+        .with_weight(weight)
+        .with_modifiers(match_kind, except.unwrap_or_default())
+        .map_err(|error| de::Error::custom(format!("expressão regular inválida: {error}")))
+}
+
+impl<'de> Deserialize<'de> for FoodConfig {
+    // Lê uma comida a partir do nome (`pudim`), do nome com os restaurantes (`pudim: [6, 8]`),
+    // do nome com o peso (`pudim: +3`) ou com os modificadores (`word: ovo`, `except: [...]`, etc)
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct FoodVisitor;
+
+        impl<'de> Visitor<'de> for FoodVisitor {
+            type Value = FoodConfig;
+
+            fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+                formatter.write_str(
+                    "o nome de uma comida, `comida: [IDs dos restaurantes]`, `comida: peso` ou uma comida com modificadores (`word`, `regex`, `except`, `weight`)",
+                )
+            }
+
+            fn visit_str<E: de::Error>(self, value: &str) -> Result<FoodConfig, E> {
+                Ok(FoodConfig::new(value.to_string(), None))
+            }
+
+            fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<FoodConfig, A::Error> {
+                let Some(name) = map.next_key::<String>()? else {
+                    return Err(de::Error::invalid_length(0, &self));
                 };
-                let mut restaurants_ids = Vec::new();
-                for restaurant in restaurants {
-                    let Some(restaurant_id) = restaurant.as_i64() else {
-                        return Err(Error::new(
-                            ErrorKind::InvalidData,
-                            "Restaurante das comidas inválida",
-                        ));
-                    };
-                    restaurants_ids.push(restaurant_id as u64);
+
+                // Uma comida com o nome de um modificador (como `word: [6]`) deve usar `name: word`
+                if FOOD_MODIFIER_KEYS.contains(&name.as_str()) {
+                    return deserialize_food_modifiers(name, map);
                 }
 
-                Ok(FoodConfig::new(key.to_lowercase(), Some(restaurants_ids)))
+                let value = map.next_value::<FoodValue>()?;
+                if map.next_key::<de::IgnoredAny>()?.is_some() {
+                    return Err(de::Error::custom(
+                        "cada comida deve ser definida separadamente, uma por item da lista",
+                    ));
+                }
+
+                Ok(match value {
+                    FoodValue::Restaurants(restaurants) => FoodConfig::new(name, Some(restaurants)),
+                    FoodValue::Weight(weight) => {
+                        FoodConfig::new(name, None).with_weight(Some(weight))
+                    }
+                })
             }
-            _ => Err(Error::new(
-                ErrorKind::InvalidData,
-                "Comida com formato inválido",
-            )),
+        }
+
+        deserializer.deserialize_any(FoodVisitor)
+    }
+}
+
+impl JsonSchema for FoodConfig {
+    fn schema_name() -> Cow<'static, str> {
+        "FoodConfig".into()
+    }
+
+    // Aceita os mesmos formatos lidos por `FoodConfig::deserialize`
+    fn json_schema(generator: &mut SchemaGenerator) -> Schema {
+        let restaurants = generator.subschema_for::<Vec<u64>>();
+        let names = generator.subschema_for::<Vec<String>>();
+        let weight = generator.subschema_for::<i32>();
+
+        json_schema!({
+            "title": "Comida",
+            "anyOf": [
+                {
+                    "title": "Comida em todos os restaurantes",
+                    "type": "string"
+                },
+                {
+                    "title": "Comida em certos restaurantes ou com peso",
+                    "type": "object",
+                    "minProperties": 1,
+                    "maxProperties": 1,
+                    "additionalProperties": {
+                        "anyOf": [restaurants, weight]
+                    }
+                },
+                {
+                    "title": "Comida com modificadores",
+                    "type": "object",
+                    "properties": {
+                        "name": {
+                            "description": "Nome que aparece em qualquer parte da linha",
+                            "type": "string"
+                        },
+                        "word": {
+                            "description": "Nome que aparece como palavras inteiras",
+                            "type": "string"
+                        },
+                        "regex": {
+                            "description": "Expressão regular, sem diferenciar maiúsculas e minúsculas",
+                            "type": "string"
+                        },
+                        "except": {
+                            "description": "Comidas que, se estiverem na linha, fazem com que ela não seja considerada",
+                            "allOf": [names]
+                        },
+                        "restaurants": restaurants,
+                        "weight": {
+                            "description": "Peso da comida na nota dos cardápios",
+                            "allOf": [weight]
+                        }
+                    },
+                    "oneOf": [
+                        { "required": ["name"] },
+                        { "required": ["word"] },
+                        { "required": ["regex"] }
+                    ],
+                    "additionalProperties": false
+                }
+            ]
+        })
+    }
+}
+
+impl Serialize for FoodConfig {
+    // Escreve a comida no mesmo formato em que ela é lida
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        if self.has_modifiers() {
+            let mut map = serializer.serialize_map(None)?;
+            map.serialize_entry(self.match_kind.key(), &self.name)?;
+            if !self.except.is_empty() {
+                map.serialize_entry("except", &self.except)?;
+            }
+            if let Some(restaurants) = &self.restaurants {
+                map.serialize_entry("restaurants", restaurants)?;
+            }
+            if let Some(weight) = &self.weight {
+                map.serialize_entry("weight", weight)?;
+            }
+            return map.end();
+        }
+
+        match (&self.restaurants, &self.weight) {
+            (Some(restaurants), _) => {
+                let mut map = serializer.serialize_map(Some(1))?;
+                map.serialize_entry(&self.name, restaurants)?;
+                map.end()
+            }
+            (None, Some(weight)) => {
+                let mut map = serializer.serialize_map(Some(1))?;
+                map.serialize_entry(&self.name, weight)?;
+                map.end()
+            }
+            (None, None) => serializer.serialize_str(&self.name),
         }
     }
 }
 
 impl FoodConfig {
     /// Verifica se uma linha contém o nome da comida.
+    ///
+    /// A comparação desconsidera as letras maiúsculas, os acentos e o plural das palavras
+    /// (veja [`text::normalize`]), a não ser nas expressões regulares, que são aplicadas
+    /// à linha original (desconsiderando apenas as letras maiúsculas).
     pub fn check_line(&self, line: &str, restaurant_id: u64) -> bool {
         let restaurant_match = if let Some(restaurants) = &self.restaurants {
             restaurants.contains(&restaurant_id)
         } else {
             true
         };
+        if !restaurant_match || self.is_unnamed() {
+            return false;
+        }
+
+        let normalized_line = text::normalize(line);
+        let line_match = match (&self.matcher, self.match_kind) {
+            (FoodMatcher::Regex(regex), _) => regex.is_match(line),
+            (FoodMatcher::Normalized(name), FoodMatch::Word) => {
+                format!(" {normalized_line} ").contains(&format!(" {name} "))
+            }
+            (FoodMatcher::Normalized(name), _) => normalized_line.contains(name.as_str()),
+        };
+
+        let excepted = self
+            .normalized_except
+            .iter()
+            .any(|except| !except.is_empty() && normalized_line.contains(except.as_str()));
+
+        line_match && !excepted
+    }
+}
+
+/// Documento do arquivo de configurações, que guarda as configurações dentro da chave `bandex`.
+#[derive(Debug, Deserialize, Serialize, JsonSchema)]
+#[schemars(title = "Configuração geral")]
+struct ConfigDocument {
+    #[schemars(title = "Configuração do Bandex")]
+    bandex: Option<BandexSection>,
+}
+
+/// Conteúdo da chave `bandex` do arquivo de configurações.
+#[derive(Debug, Deserialize, Serialize, JsonSchema)]
+#[schemars(deny_unknown_fields)]
+struct BandexSection {
+    #[schemars(title = "Configuração dos Restaurantes")]
+    restaurants: Option<Vec<RestaurantConfig>>,
+    #[serde(default, skip_serializing_if = "FoodsSection::is_empty")]
+    #[schemars(title = "Configuração dos Alimentos")]
+    foods: FoodsSection,
+    #[serde(default, skip_serializing_if = "DietConfig::is_empty")]
+    #[schemars(title = "Restrições alimentares")]
+    diet: DietConfig,
+}
+
+/// Conteúdo da chave `bandex.foods` do arquivo de configurações.
+#[derive(Debug, Default, Deserialize, Serialize, JsonSchema)]
+#[schemars(deny_unknown_fields)]
+struct FoodsSection {
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    #[schemars(title = "Alimentos Favoritos")]
+    liked: Vec<FoodConfig>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    #[schemars(title = "Alimentos não gostados")]
+    disliked: Vec<FoodConfig>,
+}
 
-        let line_match = line.to_lowercase().contains(&self.name);
+impl FoodsSection {
+    fn is_empty(&self) -> bool {
+        self.liked.is_empty() && self.disliked.is_empty()
+    }
+}
 
-        restaurant_match && line_match
+/// Lista de comidas do arquivo de configurações.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum FoodList {
+    /// Comidas gostadas (`bandex.foods.liked`).
+    Liked,
+    /// Comidas não gostadas (`bandex.foods.disliked`).
+    Disliked,
+}
+
+impl FoodList {
+    /// Chave da lista dentro de `bandex.foods`.
+    pub fn key(&self) -> &'static str {
+        match self {
+            FoodList::Liked => "liked",
+            FoodList::Disliked => "disliked",
+        }
+    }
+
+    /// Peso das comidas da lista que não definem um peso.
+    pub fn default_weight(&self) -> i32 {
+        match self {
+            FoodList::Liked => 1,
+            FoodList::Disliked => -1,
+        }
     }
 }
 
-/// Configurações do Bandex - um objeto criado a partir de um arquivo YAML.
+/// Formata o caminho de uma chave ignorada no mesmo formato das chaves dos erros,
+/// por exemplo: `bandex.restaurants[0].colour`.
+fn format_ignored_path(path: &serde_ignored::Path) -> String {
+    match path {
+        serde_ignored::Path::Root => String::new(),
+        serde_ignored::Path::Seq { parent, index } => {
+            format!("{}[{index}]", format_ignored_path(parent))
+        }
+        serde_ignored::Path::Map { parent, key } => match format_ignored_path(parent) {
+            parent if parent.is_empty() => key.clone(),
+            parent => format!("{parent}.{key}"),
+        },
+        serde_ignored::Path::Some { parent }
+        | serde_ignored::Path::NewtypeStruct { parent }
+        | serde_ignored::Path::NewtypeVariant { parent } => format_ignored_path(parent),
+    }
+}
+
+/// Origem de um arquivo de configurações.
+///
+/// A ordem das variantes é a ordem em que as camadas são aplicadas, ou seja,
+/// as configurações do CLI têm prioridade sobre as locais, que têm prioridade
+/// sobre as do usuário, que têm prioridade sobre as do sistema.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum ConfigScope {
+    /// Configurações do sistema, em `$XDG_CONFIG_DIRS/bandex/config.yaml`.
+    System,
+    /// Configurações do usuário, em `~/.bandex.yaml` e `$XDG_CONFIG_HOME/bandex/config.yaml`.
+    User,
+    /// Configurações do projeto, em `.bandex.yaml` no diretório atual ou em um diretório pai.
+    Local,
+    /// Arquivo passado pelo CLI (`-c`) ou pela variável de ambiente `BANDEX_CONFIG_FILE`.
+    Cli,
+}
+
+impl fmt::Display for ConfigScope {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            ConfigScope::System => "sistema",
+            ConfigScope::User => "usuário",
+            ConfigScope::Local => "local",
+            ConfigScope::Cli => "CLI",
+        })
+    }
+}
+
+/// Arquivo de configurações que pode ser lido, junto de sua origem e de seu formato.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ConfigFile {
+    /// Origem do arquivo.
+    pub scope: ConfigScope,
+
+    /// Caminho do arquivo.
+    pub path: PathBuf,
+
+    /// Formato em que o arquivo é lido.
+    pub format: ConfigFormat,
+}
+
+impl ConfigFile {
+    /// Cria um arquivo de configurações, com o formato detectado pela extensão (por padrão, YAML).
+    pub fn new(scope: ConfigScope, path: PathBuf) -> Self {
+        let format = ConfigFormat::from_path(&path).unwrap_or_default();
+        ConfigFile {
+            scope,
+            path,
+            format,
+        }
+    }
+}
+
+/// Configurações lidas de um único arquivo, que são aplicadas sobre as camadas anteriores.
+#[derive(Debug, Default)]
+pub struct ConfigLayer {
+    /// Restaurantes do arquivo, se definidos - substituem os das camadas anteriores.
+    pub restaurants: Option<Vec<RestaurantConfig>>,
+
+    /// Comidas favoritas do arquivo - somadas às das camadas anteriores.
+    pub liked_foods: Vec<FoodConfig>,
+
+    /// Comidas não gostadas do arquivo - somadas às das camadas anteriores.
+    pub disliked_foods: Vec<FoodConfig>,
+
+    /// Restrições alimentares do arquivo - aplicadas sobre as das camadas anteriores.
+    pub diet: DietConfig,
+}
+
+/// Documento lido do arquivo de configurações, junto das chaves ignoradas.
+type ParsedDocument = (Option<ConfigDocument>, Vec<String>);
+
+/// Lê um documento do arquivo de configurações, retornando também as chaves ignoradas.
+fn deserialize_document<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<ParsedDocument, serde_path_to_error::Error<D::Error>> {
+    let mut ignored_keys = Vec::new();
+    let mut ignore_key = |path: serde_ignored::Path| {
+        ignored_keys.push(format_ignored_path(&path));
+    };
+    let deserializer = serde_ignored::Deserializer::new(deserializer, &mut ignore_key);
+    let document = serde_path_to_error::deserialize(deserializer)?;
+
+    Ok((document, ignored_keys))
+}
+
+impl ConfigLayer {
+    /// Extrai uma camada de configurações a partir do conteúdo do arquivo, no formato `format`.
+    ///
+    /// Retorna a camada e os avisos das entradas ignoradas. O caminho `file` é
+    /// usado apenas nas mensagens. Se `strict` for verdadeiro, as chaves desconhecidas
+    /// geram erros em vez de avisos.
+    pub fn parse(
+        contents: &str,
+        format: ConfigFormat,
+        file: Option<&Path>,
+        strict: bool,
+    ) -> Result<(ConfigLayer, Vec<ConfigDiagnostic>), ConfigDiagnostic> {
+        let mut layer = ConfigLayer::default();
+        let mut warnings = Vec::new();
+
+        // Apenas o YAML permite vários documentos no mesmo arquivo
+        let documents = match format {
+            ConfigFormat::Yaml => serde_yaml::Deserializer::from_str(contents)
+                .map(|document| {
+                    deserialize_document(document).map_err(|error| {
+                        ConfigDiagnostic::from_path_error(error, describe_yaml_error)
+                    })
+                })
+                .collect(),
+            ConfigFormat::Toml => {
+                let describe = |error| describe_toml_error(contents, error);
+                let document = toml::Deserializer::parse(contents)
+                    .map_err(|error| {
+                        let (message, location) = describe(error);
+                        ConfigDiagnostic::from_parts(None, message, location)
+                    })
+                    .and_then(|deserializer| {
+                        deserialize_document(deserializer)
+                            .map_err(|error| ConfigDiagnostic::from_path_error(error, describe))
+                    });
+                vec![document]
+            }
+            ConfigFormat::Json => {
+                let mut deserializer = serde_json::Deserializer::from_str(contents);
+                let document = deserialize_document(&mut deserializer)
+                    .map_err(|error| ConfigDiagnostic::from_path_error(error, describe_json_error))
+                    .and_then(|document| {
+                        deserializer.end().map(|_| document).map_err(|error| {
+                            let (message, location) = describe_json_error(error);
+                            ConfigDiagnostic::from_parts(None, message, location)
+                        })
+                    });
+                vec![document]
+            }
+        };
+
+        let mut found_content = false;
+        for (index, document) in documents.into_iter().enumerate() {
+            let (document, ignored_keys): ParsedDocument =
+                document.map_err(|diagnostic| diagnostic.in_file(file))?;
+
+            for key in ignored_keys {
+                let diagnostic = ConfigDiagnostic {
+                    key: Some(key),
+                    ..ConfigDiagnostic::new("chave desconhecida")
+                }
+                .in_file(file);
+
+                if strict {
+                    return Err(diagnostic);
+                }
+                warnings.push(diagnostic);
+            }
+
+            let Some(document) = document else {
+                continue;
+            };
+            found_content = true;
+
+            let Some(bandex) = document.bandex else {
+                warnings.push(
+                    ConfigDiagnostic::new(format!(
+                        "documento {} ignorado, pois não possui a chave `bandex`",
+                        index + 1
+                    ))
+                    .in_file(file),
+                );
+                continue;
+            };
+
+            if let Some(restaurants) = bandex.restaurants {
+                layer
+                    .restaurants
+                    .get_or_insert_with(Vec::new)
+                    .extend(restaurants);
+            }
+            layer.liked_foods.extend(bandex.foods.liked);
+            layer.disliked_foods.extend(bandex.foods.disliked);
+            layer.diet.merge(bandex.diet);
+        }
+
+        if !found_content {
+            return Err(ConfigDiagnostic::new(
+                "Não foi encontrado nada para ser lido no arquivo de configurações",
+            )
+            .in_file(file));
+        }
+
+        if layer.restaurants.as_ref().is_some_and(Vec::is_empty) {
+            return Err(ConfigDiagnostic::new(
+                "Nenhum restaurante encontrado, verifique se o arquivo de configurações está correto",
+            )
+            .in_file(file));
+        }
+
+        Ok((layer, warnings))
+    }
+
+    /// Extrai uma camada de configurações a partir de um arquivo no formato `format`,
+    /// retornando também os avisos.
+    ///
+    /// Veja [`ConfigLayer::parse`] para o significado de `strict`.
+    pub fn from_file(
+        file_path: &Path,
+        format: ConfigFormat,
+        strict: bool,
+    ) -> Result<(ConfigLayer, Vec<ConfigDiagnostic>), ConfigDiagnostic> {
+        let contents = fs::read_to_string(file_path)
+            .map_err(|error| ConfigDiagnostic::new(error.to_string()).in_file(Some(file_path)))?;
+
+        ConfigLayer::parse(&contents, format, Some(file_path), strict)
+    }
+
+    /// Resolve os caminhos dos arquivos dos cardápios dos restaurantes, que são relativos ao
+    /// diretório do arquivo de configurações `file`.
+    pub fn resolve_paths(&mut self, file: &Path) {
+        let directory = file.parent().unwrap_or(Path::new(""));
+        for restaurant in self.restaurants.iter_mut().flatten() {
+            if let Some(path) = &mut restaurant.path {
+                if path.is_relative() {
+                    *path = directory.join(&*path);
+                }
+            }
+        }
+    }
+
+    /// Verifica as fontes dos restaurantes da camada, lida do arquivo `file`: os restaurantes
+    /// com `source: file` precisam de um arquivo dos cardápios válido (`path`), que só pode ser
+    /// usado com essa fonte.
+    pub fn check_sources(&self, file: Option<&Path>) -> Vec<ConfigDiagnostic> {
+        let mut diagnostics = Vec::new();
+
+        for (index, restaurant) in self.restaurants.iter().flatten().enumerate() {
+            let key = |name: &str| Some(format!("bandex.restaurants[{index}].{name}"));
+            let message = match (restaurant.source, &restaurant.path) {
+                (SourceKind::File, None) => format!(
+                    "o restaurante {} usa `source: file`, mas não define o arquivo dos cardápios",
+                    restaurant.id
+                ),
+                (SourceKind::File, Some(path)) => match MenusFile::from_file(path) {
+                    Ok(_) => continue,
+                    Err(error) => format!("arquivo dos cardápios inválido: {error}"),
+                },
+                (_, Some(_)) => format!(
+                    "o arquivo dos cardápios só é usado com `source: file`, mas a fonte do restaurante {} é `{}`",
+                    restaurant.id, restaurant.source
+                ),
+                (_, None) => continue,
+            };
+
+            diagnostics.push(
+                ConfigDiagnostic {
+                    key: key("path"),
+                    ..ConfigDiagnostic::new(message)
+                }
+                .in_file(file),
+            );
+        }
+
+        diagnostics
+    }
+
+    /// Escreve a camada de configurações no formato `format`.
+    ///
+    /// Os comentários e as âncoras do arquivo original não são mantidos.
+    pub fn to_format(&self, format: ConfigFormat) -> Result<String, ConfigDiagnostic> {
+        let document = ConfigDocument {
+            bandex: Some(BandexSection {
+                restaurants: self.restaurants.clone(),
+                foods: FoodsSection {
+                    liked: self.liked_foods.clone(),
+                    disliked: self.disliked_foods.clone(),
+                },
+                diet: self.diet.clone(),
+            }),
+        };
+
+        format.to_string(&document).map_err(ConfigDiagnostic::new)
+    }
+}
+
+/// Configurações do Bandex - um objeto criado a partir dos arquivos de configuração.
 ///
 /// Essa estrutura contém as configurações do Bandex, incluindo informações sobre os restaurantes.
-#[derive(Debug)]
+///
+/// As configurações também podem ser serializadas diretamente (diferente do arquivo, sem a
+/// chave `bandex`), e os campos ausentes ficam com os valores padrão.
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+#[serde(default)]
 pub struct Config {
     /// Lista dos restaurantes que serão apresentados.
     pub restaurants: Vec<RestaurantConfig>,
@@ -184,14 +1360,9 @@ pub struct Config {
 
     /// Lista das comidas não gostadas.
     pub disliked_foods: Vec<FoodConfig>,
-}
 
-/// Extrai objetos YAML a partir do conteúdo do arquivo YAML.
-fn parse_yaml_from_content(contents: &str) -> Result<Vec<Yaml>, Error> {
-    let docs = YamlLoader::load_from_str(contents)
-        .map_err(|err| Error::new(ErrorKind::InvalidData, err.to_string()))?;
-
-    Ok(docs)
+    /// Restrições alimentares e as palavras-chave de cada uma.
+    pub diet: DietConfig,
 }
 
 impl Default for Config {
@@ -199,134 +1370,482 @@ impl Default for Config {
     fn default() -> Self {
         let restaurants = DEFAULT_RESTAURANTS
             .iter()
-            .map(|(id, color)| RestaurantConfig {
-                id: *id,
-                color: *color,
-            })
+            .map(|(id, color)| RestaurantConfig::new(*id, *color))
             .collect();
 
         Config {
             restaurants,
             liked_foods: vec![],
             disliked_foods: vec![],
+            diet: DietConfig::default(),
         }
     }
 }
 
+/// Resultado da leitura das configurações em camadas.
+#[derive(Debug)]
+pub struct LoadedConfig {
+    /// Configurações efetivas, com todas as camadas aplicadas.
+    pub config: Config,
+
+    /// Arquivos lidos, na ordem em que foram aplicados.
+    pub files: Vec<ConfigFile>,
+
+    /// Avisos das entradas ignoradas nos arquivos lidos.
+    pub warnings: Vec<ConfigDiagnostic>,
+
+    /// Erros dos arquivos que não puderam ser lidos e, por isso, foram ignorados, e das fontes
+    /// dos restaurantes (veja [`ConfigLayer::check_sources`]), que não ignoram o arquivo.
+    pub errors: Vec<ConfigDiagnostic>,
+}
+
 impl Config {
-    /// Extrai a configuração do bandex do documento YAML.
-    fn get_bandex_yaml(doc: &Yaml) -> Option<&yaml::Hash> {
-        doc.as_hash()?.get(to_yaml_str!("bandex"))?.as_hash()
+    /// Aplica uma camada de configurações sobre as configurações atuais.
+    ///
+    /// Os restaurantes da camada, se definidos, substituem os atuais, enquanto as
+    /// comidas gostadas e não gostadas são somadas às atuais. As restrições alimentares
+    /// seguem [`DietConfig::merge`].
+    pub fn merge(&mut self, layer: ConfigLayer) {
+        if let Some(restaurants) = layer.restaurants {
+            self.restaurants = restaurants;
+        }
+        self.liked_foods.extend(layer.liked_foods);
+        self.disliked_foods.extend(layer.disliked_foods);
+        self.diet.merge(layer.diet);
+    }
+
+    /// Escreve as configurações no formato `format`, como um arquivo de configurações.
+    pub fn to_format(&self, format: ConfigFormat) -> String {
+        let layer = ConfigLayer {
+            restaurants: Some(self.restaurants.clone()),
+            liked_foods: self.liked_foods.clone(),
+            disliked_foods: self.disliked_foods.clone(),
+            diet: self.diet.clone(),
+        };
+
+        layer
+            .to_format(format)
+            .expect("as configurações sempre podem ser escritas")
     }
 
-    /// Extrai os restaurantes, em yaml, da configuração do bandex.
-    fn get_restaurants_yaml(bandex_config: &yaml::Hash) -> Option<&yaml::Array> {
-        bandex_config.get(to_yaml_str!("restaurants"))?.as_vec()
+    /// Calcula a nota de um cardápio (`content`) no restaurante `restaurant_id`, somando os
+    /// pesos das comidas gostadas e não gostadas encontradas em cada linha.
+    ///
+    /// Retorna `None` se o restaurante estiver fechado.
+    pub fn menu_score(&self, content: &str, restaurant_id: RestaurantID) -> Option<i32> {
+        if content == "Fechado" {
+            return None;
+        }
+
+        let foods = [
+            (FoodList::Liked, &self.liked_foods),
+            (FoodList::Disliked, &self.disliked_foods),
+        ];
+
+        let score = content
+            .split('\n')
+            .filter(|line| !line.is_empty())
+            .flat_map(|line| {
+                foods.iter().flat_map(move |(list, foods)| {
+                    foods
+                        .iter()
+                        .filter(move |food| food.check_line(line, restaurant_id as u64))
+                        .map(|food| food.weight.unwrap_or(list.default_weight()))
+                })
+            })
+            .sum();
+
+        Some(score)
     }
 
-    /// Extrai os alimentos, em yaml, da configuração do bandex.
-    fn get_foods_yaml(bandex_config: &yaml::Hash) -> Option<&yaml::Hash> {
-        bandex_config.get(to_yaml_str!("foods"))?.as_hash()
-    }
+    /// Retorna as comidas da lista `list` encontradas em alguma linha do cardápio (`content`)
+    /// no restaurante `restaurant_id`.
+    pub fn found_foods(
+        &self,
+        list: FoodList,
+        content: &str,
+        restaurant_id: RestaurantID,
+    ) -> Vec<&FoodConfig> {
+        let foods = match list {
+            FoodList::Liked => &self.liked_foods,
+            FoodList::Disliked => &self.disliked_foods,
+        };
 
-    /// Extrai os alimentos favoritos, em yaml, da configuração do bandex.
-    fn get_liked_foods_yaml(foods_config: &yaml::Hash) -> Option<&yaml::Array> {
-        foods_config.get(to_yaml_str!("liked"))?.as_vec()
+        foods
+            .iter()
+            .filter(|food| {
+                content
+                    .split('\n')
+                    .filter(|line| !line.is_empty())
+                    .any(|line| food.check_line(line, restaurant_id as u64))
+            })
+            .collect()
     }
 
-    /// Extrai os alimentos não gostados, em yaml, da configuração do bandex.
-    fn get_disliked_foods_yaml(foods_config: &yaml::Hash) -> Option<&yaml::Array> {
-        foods_config.get(to_yaml_str!("disliked"))?.as_vec()
+    /// Verifica se há alguma comida configurada, ou seja, se as notas dos cardápios fazem sentido.
+    pub fn has_foods(&self) -> bool {
+        !self.liked_foods.is_empty() || !self.disliked_foods.is_empty()
     }
 
-    /// Extrai as configurações a partir do conteúdo do arquivo YAML.
-    pub fn from_file_content(contents: &str) -> Result<Config, Error> {
-        let docs = parse_yaml_from_content(contents)?;
+    /// Verifica os IDs dos restaurantes, que identificam os restaurantes no cache e no
+    /// histórico: os restaurantes repetidos e os restaurantes com `source: file` que usam o ID
+    /// de um restaurante conhecido de outra fonte misturariam os cardápios de dois
+    /// restaurantes.
+    pub fn check_restaurant_ids(&self) -> Vec<ConfigDiagnostic> {
+        let mut diagnostics = Vec::new();
+
+        for (index, restaurant) in self.restaurants.iter().enumerate() {
+            let message = if self.restaurants[..index]
+                .iter()
+                .any(|other| other.id == restaurant.id)
+            {
+                format!("restaurante {} repetido", restaurant.id)
+            } else if let Some(owner) = SourceKind::reserved_by(restaurant.id)
+                .filter(|_| restaurant.source == SourceKind::File)
+            {
+                format!(
+                    "o restaurante {} usa `source: file`, mas o ID {} já é de um restaurante da fonte `{owner}`",
+                    restaurant.id, restaurant.id
+                )
+            } else {
+                continue;
+            };
 
-        if docs.is_empty() {
-            return Err(Error::new(
-                ErrorKind::InvalidData,
-                "Não foi encontrado nada para ser lido no arquivo de configurações",
-            ));
+            diagnostics.push(ConfigDiagnostic {
+                key: Some(format!("bandex.restaurants[{index}].id")),
+                ..ConfigDiagnostic::new(message)
+            });
         }
 
-        let mut restaurants = Vec::new();
-        let mut liked_foods = Vec::new();
-        let mut disliked_foods = Vec::new();
+        diagnostics
+    }
 
-        for doc in docs {
-            let Some(bandex_config) = Self::get_bandex_yaml(&doc) else {
-                continue;
-            };
+    /// Verifica a consistência das configurações efetivas, retornando os problemas encontrados.
+    ///
+    /// São verificados os IDs dos restaurantes (veja [`Config::check_restaurant_ids`]), as
+    /// comidas sem nome, as comidas com o peso contrário à sua lista e as comidas que se
+    /// referem a restaurantes que não estão na lista de restaurantes.
+    pub fn check(&self) -> Vec<ConfigDiagnostic> {
+        let mut diagnostics = self.check_restaurant_ids();
+
+        for (list, foods) in [
+            (FoodList::Liked, &self.liked_foods),
+            (FoodList::Disliked, &self.disliked_foods),
+        ] {
+            for (index, food) in foods.iter().enumerate() {
+                let key = Some(format!("bandex.foods.{}[{index}]", list.key()));
+
+                if food.is_unnamed() {
+                    diagnostics.push(ConfigDiagnostic {
+                        key: key.clone(),
+                        ..ConfigDiagnostic::new("comida sem nome")
+                    });
+                }
 
-            if let Some(restaurants_config) = Self::get_restaurants_yaml(bandex_config) {
-                for restaurant_yaml in restaurants_config {
-                    if let Ok(restaurant) = RestaurantConfig::try_from(restaurant_yaml) {
-                        restaurants.push(restaurant);
+                if let Some(weight) = food.weight {
+                    if weight.signum() == -list.default_weight().signum() {
+                        diagnostics.push(ConfigDiagnostic {
+                            key: key.clone(),
+                            ..ConfigDiagnostic::new(format!(
+                                "a comida \"{}\" tem peso {weight:+}, contrário à lista `{}`",
+                                food.name,
+                                list.key()
+                            ))
+                        });
                     }
                 }
-            }
 
-            if let Some(foods_config) = Self::get_foods_yaml(bandex_config) {
-                if let Some(liked) = Self::get_liked_foods_yaml(foods_config) {
-                    for food_yaml in liked {
-                        if let Ok(food) = FoodConfig::try_from(food_yaml) {
-                            liked_foods.push(food);
-                        }
+                for restaurant_id in food.restaurants.iter().flatten() {
+                    if !self
+                        .restaurants
+                        .iter()
+                        .any(|restaurant| restaurant.id as u64 == *restaurant_id)
+                    {
+                        diagnostics.push(ConfigDiagnostic {
+                            key: key.clone(),
+                            ..ConfigDiagnostic::new(format!(
+                                "a comida \"{}\" se refere ao restaurante {}, que não está na lista de restaurantes",
+                                food.name, restaurant_id
+                            ))
+                        });
                     }
                 }
-                if let Some(disliked) = Self::get_disliked_foods_yaml(foods_config) {
-                    for food_yaml in disliked {
-                        if let Ok(food) = FoodConfig::try_from(food_yaml) {
-                            disliked_foods.push(food);
-                        }
+            }
+        }
+
+        diagnostics
+    }
+
+    /// Lê os arquivos de configuração `files`, em ordem, aplicando cada um sobre as
+    /// configurações padrão.
+    ///
+    /// Os arquivos descobertos automaticamente que não existem são pulados, mas o arquivo
+    /// do CLI deve existir. Os arquivos com erro são ignorados e informados em
+    /// [`LoadedConfig::errors`], a não ser no modo estrito (`strict`), em que qualquer
+    /// problema interrompe a leitura.
+    pub fn load(files: &[ConfigFile], strict: bool) -> Result<LoadedConfig, Error> {
+        let mut loaded = LoadedConfig {
+            config: Config::default(),
+            files: Vec::new(),
+            warnings: Vec::new(),
+            errors: Vec::new(),
+        };
+
+        for file in files {
+            if file.scope != ConfigScope::Cli && !file.path.is_file() {
+                continue;
+            }
+
+            match ConfigLayer::from_file(&file.path, file.format, strict) {
+                Ok((mut layer, warnings)) => {
+                    layer.resolve_paths(&file.path);
+                    let errors = layer.check_sources(Some(&file.path));
+                    if let Some(error) = errors.first().filter(|_| strict) {
+                        return Err(error.clone().into());
                     }
+                    loaded.errors.extend(errors);
+
+                    loaded.config.merge(layer);
+                    loaded.files.push(file.clone());
+                    loaded.warnings.extend(warnings);
                 }
+                Err(diagnostic) if strict => return Err(diagnostic.into()),
+                Err(diagnostic) => loaded.errors.push(diagnostic),
             }
         }
 
-        if restaurants.is_empty() {
-            return Err(Error::new(
-                ErrorKind::InvalidData,
-                "Nenhum restaurante encontrado, verifique se o arquivo de configurações está correto",
-            ));
-        }
+        Ok(loaded)
+    }
+}
 
-        Ok(Config {
-            restaurants,
-            liked_foods,
-            disliked_foods,
-        })
+/// Gera o JSON Schema (draft 7) do arquivo de configurações, a partir das estruturas usadas
+/// na leitura.
+///
+/// É o conteúdo do arquivo `bandex-config.schema.json`, referenciado pelo [`SCHEMA_HEADER`].
+pub fn config_schema() -> String {
+    let schema = SchemaSettings::draft07()
+        .into_generator()
+        .into_root_schema_for::<ConfigDocument>();
+
+    let mut schema =
+        serde_json::to_string_pretty(&schema).expect("o schema sempre pode ser escrito");
+    schema.push('\n');
+    schema
+}
+
+/// Adiciona uma comida à lista `list` de um arquivo de configurações no formato `format`,
+/// cujo conteúdo é `contents`.
+///
+/// Retorna o novo conteúdo do arquivo, ou `None` se a comida já estiver na lista. Nos arquivos
+/// YAML, os comentários do início do arquivo (como o cabeçalho do schema) são mantidos, mas os
+/// demais são perdidos, pois o arquivo é reescrito.
+pub fn add_food(
+    contents: &str,
+    format: ConfigFormat,
+    list: FoodList,
+    food: &FoodConfig,
+) -> Result<Option<String>, ConfigDiagnostic> {
+    let mut document = format.parse_value(contents)?;
+    if document.is_null() {
+        document = Value::Mapping(Mapping::new());
+    }
+
+    let mut value = &mut document;
+    let mut path = Vec::new();
+    for (key, empty) in [
+        ("bandex", Value::Mapping(Mapping::new())),
+        ("foods", Value::Mapping(Mapping::new())),
+        (list.key(), Value::Sequence(Vec::new())),
+    ] {
+        let Value::Mapping(mapping) = value else {
+            return Err(ConfigDiagnostic {
+                key: (!path.is_empty()).then(|| path.join(".")),
+                ..ConfigDiagnostic::new("a chave deveria ser um objeto")
+            });
+        };
+        path.push(key);
+
+        // Uma chave ausente ou vazia (`liked:`) é tratada como um objeto ou uma lista vazia
+        value = mapping.entry(Value::from(key)).or_insert(Value::Null);
+        if value.is_null() {
+            *value = empty;
+        }
     }
 
-    /// Extrai a configuração do bandex a partir de um arquivo YAML.
-    pub fn from_file<P>(file_path: P) -> Result<Config, Error>
-    where
-        P: AsRef<Path>,
-    {
-        let contents = fs::read_to_string(file_path)?;
+    let key = path.join(".");
+    let Value::Sequence(foods) = value else {
+        return Err(ConfigDiagnostic {
+            key: Some(key),
+            ..ConfigDiagnostic::new("a chave deveria ser uma lista")
+        });
+    };
+
+    let already_added = foods
+        .iter()
+        .filter_map(|value| FoodConfig::deserialize(value).ok())
+        .any(|existing| existing.same_food(food));
+    if already_added {
+        return Ok(None);
+    }
 
-        Config::from_file_content(&contents)
+    foods.push(
+        serde_yaml::to_value(food).map_err(|error| ConfigDiagnostic {
+            key: Some(key),
+            ..ConfigDiagnostic::new(error.to_string())
+        })?,
+    );
+
+    let new_contents = format.to_string(&document).map_err(ConfigDiagnostic::new)?;
+
+    // Os comentários do início são mantidos nos formatos que aceitam comentários com `#`
+    let header: Vec<&str> = contents
+        .lines()
+        .take_while(|line| line.starts_with('#') || line.trim().is_empty())
+        .filter(|line| !line.trim().is_empty())
+        .collect();
+    if header.is_empty() || format == ConfigFormat::Json {
+        return Ok(Some(format.file_contents(&new_contents)));
     }
+
+    Ok(Some(format!("{}\n{new_contents}", header.join("\n"))))
+}
+
+/// Arquivo de configurações do usuário no diretório XDG, usado pelos comandos que criam
+/// ou alteram as configurações quando nenhum arquivo é passado pelo CLI.
+pub fn user_config_file() -> Option<PathBuf> {
+    read_env_dir(ENV_VAR_XDG_CONFIG_HOME)
+        .or_else(|| env::var_os("HOME").map(|home| PathBuf::from(home).join(".config")))
+        .map(|dir| find_config_file(&dir.join(XDG_CONFIG_FILE)))
+}
+
+/// Procura o arquivo de configurações `path` (sem a extensão) com cada uma das extensões aceitas.
+///
+/// Retorna o primeiro arquivo encontrado ou, se nenhum existir, o arquivo YAML.
+fn find_config_file(path: &Path) -> PathBuf {
+    let with_extension = |format: ConfigFormat| {
+        let mut file = path.as_os_str().to_owned();
+        file.push(".");
+        file.push(format.extension());
+        PathBuf::from(file)
+    };
+
+    ConfigFormat::ALL
+        .into_iter()
+        .map(with_extension)
+        .find(|file| file.is_file())
+        .unwrap_or_else(|| with_extension(ConfigFormat::Yaml))
 }
 
 /// Lê o caminho de configuração do bandex a partir de uma variável de ambiente.
 pub fn read_env_config_filepath() -> Option<PathBuf> {
-    env::var(ENV_VAR_BANDEX_CONFIG)
-        .ok()
-        .map(|path| PathBuf::from(path))
+    env::var(ENV_VAR_BANDEX_CONFIG).ok().map(PathBuf::from)
+}
+
+/// Lê um diretório de uma variável de ambiente, ignorando valores vazios ou relativos
+/// (como pede a especificação XDG).
+pub fn read_env_dir(name: &str) -> Option<PathBuf> {
+    env::var_os(name)
+        .map(PathBuf::from)
+        .filter(|path| path.is_absolute())
+}
+
+/// Lista os arquivos de configuração candidatos, do sistema, do usuário e do projeto,
+/// na ordem em que são aplicados.
+///
+/// - `home`: diretório do usuário
+/// - `xdg_config_home`: valor de `$XDG_CONFIG_HOME` (por padrão, `~/.config`)
+/// - `xdg_config_dirs`: valor de `$XDG_CONFIG_DIRS` (por padrão, `/etc/xdg`)
+/// - `current_dir`: diretório atual, a partir do qual o `.bandex.yaml` é procurado
+///
+/// Em cada local, é usado o primeiro arquivo existente dentre os formatos aceitos (veja
+/// [`ConfigFormat::ALL`]), ou o arquivo YAML se nenhum existir.
+fn candidate_config_files(
+    home: Option<&Path>,
+    xdg_config_home: Option<&Path>,
+    xdg_config_dirs: Option<&str>,
+    current_dir: Option<&Path>,
+) -> Vec<ConfigFile> {
+    let mut files = Vec::new();
+
+    // O primeiro diretório do $XDG_CONFIG_DIRS é o mais importante, então é aplicado por último
+    let system_dirs = xdg_config_dirs
+        .filter(|dirs| !dirs.is_empty())
+        .unwrap_or(DEFAULT_XDG_CONFIG_DIRS);
+    for dir in system_dirs.split(':').rev() {
+        let dir = Path::new(dir);
+        if dir.is_absolute() {
+            files.push(ConfigFile::new(
+                ConfigScope::System,
+                find_config_file(&dir.join(XDG_CONFIG_FILE)),
+            ));
+        }
+    }
+
+    let home_file = home.map(|home| find_config_file(&home.join(HOME_CONFIG_FILE)));
+    if let Some(home_file) = &home_file {
+        files.push(ConfigFile::new(ConfigScope::User, home_file.clone()));
+    }
+
+    let xdg_config_home = xdg_config_home
+        .map(Path::to_path_buf)
+        .or_else(|| home.map(|home| home.join(".config")));
+    if let Some(xdg_config_home) = xdg_config_home {
+        files.push(ConfigFile::new(
+            ConfigScope::User,
+            find_config_file(&xdg_config_home.join(XDG_CONFIG_FILE)),
+        ));
+    }
+
+    // O arquivo local é o `.bandex.yaml` mais próximo, desconsiderando o do usuário
+    if let Some(current_dir) = current_dir {
+        let local_file = current_dir
+            .ancestors()
+            .map(|dir| find_config_file(&dir.join(LOCAL_CONFIG_FILE)))
+            .filter(|file| Some(file) != home_file.as_ref())
+            .find(|file| file.is_file())
+            .unwrap_or_else(|| find_config_file(&current_dir.join(LOCAL_CONFIG_FILE)));
+        files.push(ConfigFile::new(ConfigScope::Local, local_file));
+    }
+
+    files
+}
+
+/// Lista os arquivos de configuração que devem ser lidos, na ordem em que são aplicados:
+/// sistema → usuário → local → CLI.
+///
+/// O arquivo do CLI (`cli_file`) é sempre o último, de modo que tem prioridade sobre os demais.
+/// O seu formato é `cli_format` ou, se nenhum for escolhido, o formato detectado pela extensão.
+pub fn config_files(
+    cli_file: Option<PathBuf>,
+    cli_format: Option<ConfigFormat>,
+) -> Vec<ConfigFile> {
+    let home = env::var_os("HOME").map(PathBuf::from);
+    let xdg_config_home = read_env_dir(ENV_VAR_XDG_CONFIG_HOME);
+    let xdg_config_dirs = env::var(ENV_VAR_XDG_CONFIG_DIRS).ok();
+    let current_dir = env::current_dir().ok();
+
+    let mut files = candidate_config_files(
+        home.as_deref(),
+        xdg_config_home.as_deref(),
+        xdg_config_dirs.as_deref(),
+        current_dir.as_deref(),
+    );
+
+    if let Some(cli_file) = cli_file {
+        let mut file = ConfigFile::new(ConfigScope::Cli, cli_file);
+        if let Some(format) = cli_format {
+            file.format = format;
+        }
+        files.push(file);
+    }
+
+    files
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-
-    #[test]
-    fn test_to_yaml_str() {
-        let name = to_yaml_str!("name");
-        let name = name.as_str();
-        assert!(name.is_some() && name.unwrap() == "name");
-    }
+    use crate::diet::Diet;
 
     #[test]
     fn test_new_restaurant_config() {
@@ -345,9 +1864,7 @@ mod tests {
     #[test]
     fn test_restaurant_config_parse() {
         // without color
-        let values = parse_yaml_from_content("id: 2").unwrap();
-        let value = values.first().unwrap();
-        let restaurant = RestaurantConfig::try_from(value);
+        let restaurant = serde_yaml::from_str::<RestaurantConfig>("id: 2");
 
         assert!(restaurant.is_ok());
 
@@ -356,9 +1873,7 @@ mod tests {
         assert_eq!(restaurant.color, DEFAULT_COLOR);
 
         // name color
-        let values = parse_yaml_from_content("color: BluE\nid: 6").unwrap();
-        let value = values.first().unwrap();
-        let restaurant = RestaurantConfig::try_from(value);
+        let restaurant = serde_yaml::from_str::<RestaurantConfig>("color: BluE\nid: 6");
 
         assert!(restaurant.is_ok());
 
@@ -367,9 +1882,7 @@ mod tests {
         assert_eq!(restaurant.color, Color::Blue);
 
         // RGB color
-        let values = parse_yaml_from_content("id: 12\ncolor: [187, 35, 51]").unwrap();
-        let value = values.first().unwrap();
-        let restaurant = RestaurantConfig::try_from(value);
+        let restaurant = serde_yaml::from_str::<RestaurantConfig>("id: 12\ncolor: [187, 35, 51]");
 
         assert!(restaurant.is_ok());
 
@@ -385,22 +1898,22 @@ mod tests {
         );
 
         // Test Errors
-        let values = parse_yaml_from_content("color: [187, 35, 51]").unwrap();
-        let value = values.first().unwrap();
-        let restaurant = RestaurantConfig::try_from(value);
+        let restaurant = serde_yaml::from_str::<RestaurantConfig>("color: [187, 35, 51]");
+        assert!(restaurant.is_err());
 
+        let restaurant = serde_yaml::from_str::<RestaurantConfig>("id: \"12\"\ncolor: blue");
         assert!(restaurant.is_err());
 
-        let values = parse_yaml_from_content("id: \"12\"\ncolor: blue").unwrap();
-        let value = values.first().unwrap();
-        let restaurant = RestaurantConfig::try_from(value);
+        let restaurant = serde_yaml::from_str::<RestaurantConfig>("id: 89\ncolor: not a color");
+        assert!(restaurant.is_err());
 
+        let restaurant = serde_yaml::from_str::<RestaurantConfig>("id: 89\ncolor: [187, 35]");
         assert!(restaurant.is_err());
 
-        let values = parse_yaml_from_content("id: 89\ncolor: not a color").unwrap();
-        let value = values.first().unwrap();
-        let restaurant = RestaurantConfig::try_from(value);
+        let restaurant = serde_yaml::from_str::<RestaurantConfig>("id: 89\ncolor: [1, 2, 3, 4]");
+        assert!(restaurant.is_err());
 
+        let restaurant = serde_yaml::from_str::<RestaurantConfig>("id: 89\ncolor: [187, 35, 256]");
         assert!(restaurant.is_err());
     }
 
@@ -417,9 +1930,7 @@ mod tests {
 
     #[test]
     fn test_food_config_from_yaml() {
-        let values = parse_yaml_from_content("pizza").unwrap();
-        let value = values.first().unwrap();
-        let food_config = FoodConfig::try_from(value);
+        let food_config = serde_yaml::from_str::<FoodConfig>("pizza");
 
         assert!(food_config.is_ok());
 
@@ -427,15 +1938,19 @@ mod tests {
         assert_eq!(food_config.name, "pizza");
         assert_eq!(food_config.restaurants, None);
 
-        let values = parse_yaml_from_content("feijoada: [1, 2, 3]").unwrap();
-        let value = values.first().unwrap();
-        let food_config = FoodConfig::try_from(value);
+        let food_config = serde_yaml::from_str::<FoodConfig>("feijoada: [1, 2, 3]");
 
         assert!(food_config.is_ok());
 
         let food_config = food_config.unwrap();
         assert_eq!(food_config.name, "feijoada");
         assert_eq!(food_config.restaurants, Some(vec![1, 2, 3]));
+
+        let food_config = serde_yaml::from_str::<FoodConfig>("{feijoada: [1], pudim: [2]}");
+        assert!(food_config.is_err());
+
+        let food_config = serde_yaml::from_str::<FoodConfig>("feijoada: [central]");
+        assert!(food_config.is_err());
     }
 
     #[test]
@@ -452,22 +1967,225 @@ mod tests {
     }
 
     #[test]
-    fn test_parse_yaml_from_content() {
-        let docs = parse_yaml_from_content("key: value");
-        assert!(docs.is_ok());
+    fn test_check_line_normalized() {
+        // As duas formas do arquivo de configurações são comparadas da mesma forma
+        let (layer, _) = ConfigLayer::parse(
+            "bandex:\n  foods:\n    liked: [Feijao, {OVOS: [1]}]",
+            ConfigFormat::Yaml,
+            None,
+            true,
+        )
+        .unwrap();
+        let [feijao, ovos] = &layer.liked_foods[..] else {
+            panic!("a configuração deve ter duas comidas");
+        };
+
+        assert!(feijao.check_line("FEIJÃO PRETO", 1));
+        assert!(feijao.check_line("Arroz/feijões", 1));
+        assert!(!feijao.check_line("Feijoada", 1));
+        assert!(ovos.check_line("Omelete de ovo", 1));
+        assert!(ovos.check_line("Ovos cozidos", 1));
+        assert!(!ovos.check_line("Ovos cozidos", 2));
+
+        assert!(FoodConfig::new("Pudins".to_string(), None)
+            .same_food(&FoodConfig::new("pudim".to_string(), None)));
+        assert!(FoodConfig::new(" ! ".to_string(), None).is_unnamed());
+    }
+
+    #[test]
+    fn test_food_modifiers() {
+        let (layer, _) = ConfigLayer::parse(
+            "
+            bandex:
+                foods:
+                    disliked:
+                        - word: ovo
+                        - name: carne
+                          except: [carne de soja]
+                        - regex: \"^arroz (branco|integral)$\"
+                          restaurants: [6]",
+            ConfigFormat::Yaml,
+            None,
+            true,
+        )
+        .unwrap();
+        let [ovo, carne, arroz] = &layer.disliked_foods[..] else {
+            panic!("a configuração deve ter três comidas");
+        };
+
+        assert_eq!(ovo.match_kind, FoodMatch::Word);
+        assert!(ovo.check_line("Ovos mexidos", 1));
+        assert!(!ovo.check_line("Ovomaltine", 1));
+
+        assert_eq!(carne.except, vec!["carne de soja"]);
+        assert!(carne.check_line("Carne assada", 1));
+        assert!(!carne.check_line("Carnes de soja ao molho", 1));
+
+        assert_eq!(arroz.match_kind, FoodMatch::Regex);
+        assert!(arroz.check_line("Arroz Integral", 6));
+        assert!(!arroz.check_line("Arroz integral", 8));
+        assert!(!arroz.check_line("Arroz carreteiro", 6));
+
+        // As comidas com modificadores são escritas no mesmo formato
+        for format in ConfigFormat::ALL {
+            let contents = layer.to_format(format).unwrap();
+            let (written, _) = ConfigLayer::parse(&contents, format, None, true).unwrap();
+            assert_eq!(written.disliked_foods, layer.disliked_foods);
+        }
+    }
+
+    #[test]
+    fn test_food_modifiers_errors() {
+        for (foods, message) in [
+            ("[{regex: \"(arroz\"}]", "expressão regular inválida"),
+            ("[{word: ovo, name: ovo}]", "apenas uma das chaves"),
+            ("[{except: [soja]}]", "uma das chaves"),
+            ("[{word: ovo, color: red}]", "color"),
+        ] {
+            let diagnostic = ConfigLayer::parse(
+                &format!("bandex:\n  foods:\n    liked: {foods}"),
+                ConfigFormat::Yaml,
+                None,
+                false,
+            )
+            .unwrap_err();
+            assert_eq!(diagnostic.key.as_deref(), Some("bandex.foods.liked[0]"));
+            assert!(
+                diagnostic.message.contains(message),
+                "{foods}: {}",
+                diagnostic.message
+            );
+        }
+    }
+
+    #[test]
+    fn test_food_weights() {
+        let yaml = "
+            bandex:
+                foods:
+                    liked:
+                        - pudim: +3
+                        - brigadeiro
+                    disliked:
+                        - pvt: -5
+                        - name: acelga
+                          restaurants: [6]
+                          weight: -2";
+        let toml = r#"
+            [bandex.foods]
+            liked = [{ pudim = 3 }, "brigadeiro"]
+            disliked = [{ pvt = -5 }, { name = "acelga", restaurants = [6], weight = -2 }]"#;
+
+        let (layer, _) = ConfigLayer::parse(yaml, ConfigFormat::Yaml, None, true).unwrap();
+        let (toml_layer, _) = ConfigLayer::parse(toml, ConfigFormat::Toml, None, true).unwrap();
+        assert_eq!(layer.liked_foods, toml_layer.liked_foods);
+        assert_eq!(layer.disliked_foods, toml_layer.disliked_foods);
+
+        assert_eq!(
+            layer.liked_foods,
+            vec![
+                FoodConfig::new("pudim".to_string(), None).with_weight(Some(3)),
+                FoodConfig::new("brigadeiro".to_string(), None),
+            ]
+        );
+        assert_eq!(
+            layer.disliked_foods,
+            vec![
+                FoodConfig::new("pvt".to_string(), None).with_weight(Some(-5)),
+                FoodConfig::new("acelga".to_string(), Some(vec![6])).with_weight(Some(-2)),
+            ]
+        );
+
+        // Os pesos são mantidos ao escrever as configurações
+        for format in ConfigFormat::ALL {
+            let contents = layer.to_format(format).unwrap();
+            let (written, _) = ConfigLayer::parse(&contents, format, None, true).unwrap();
+            assert_eq!(written.liked_foods, layer.liked_foods);
+            assert_eq!(written.disliked_foods, layer.disliked_foods);
+        }
+
+        let diagnostic = ConfigLayer::parse(
+            "bandex:\n  foods:\n    liked: [pudim: 9999999999]",
+            ConfigFormat::Yaml,
+            None,
+            false,
+        )
+        .unwrap_err();
+        assert!(diagnostic.message.contains("muito grande"));
+    }
+
+    #[test]
+    fn test_menu_score() {
+        let config = Config {
+            restaurants: vec![
+                RestaurantConfig::new(6, DEFAULT_COLOR),
+                RestaurantConfig::new(8, DEFAULT_COLOR),
+            ],
+            liked_foods: vec![
+                FoodConfig::new("pudim".to_string(), None).with_weight(Some(3)),
+                FoodConfig::new("feijão".to_string(), None),
+            ],
+            disliked_foods: vec![
+                FoodConfig::new("pvt".to_string(), Some(vec![8])).with_weight(Some(-5)),
+                FoodConfig::new("acelga".to_string(), None),
+            ],
+            diet: DietConfig::default(),
+        };
+        let menu = "Arroz/Feijão\nPVT ao molho\nSalada de acelga\n\nPudim de leite";
 
-        let docs = docs.unwrap();
-        assert!(docs.len() == 1);
+        assert_eq!(config.menu_score(menu, 6), Some(3));
+        assert_eq!(config.menu_score(menu, 8), Some(-2));
+        assert_eq!(config.menu_score("Arroz\nFeijão", 6), Some(1));
+        assert_eq!(config.menu_score("Fechado", 6), None);
+
+        let names = |foods: Vec<&FoodConfig>| -> Vec<String> {
+            foods.into_iter().map(|food| food.name.clone()).collect()
+        };
+        assert_eq!(
+            names(config.found_foods(FoodList::Liked, menu, 8)),
+            vec!["pudim", "feijão"]
+        );
+        assert_eq!(
+            names(config.found_foods(FoodList::Disliked, menu, 6)),
+            vec!["acelga"]
+        );
+        assert!(config.has_foods());
+        assert!(!Config::default().has_foods());
+    }
 
-        let doc = docs[0].as_hash();
-        assert!(doc.is_some());
+    #[test]
+    fn test_config_diet() {
+        let (layer, _) = ConfigLayer::parse(
+            "
+            bandex:
+                diet:
+                    profiles: [vegetarian, lactose]
+                    keywords:
+                        lactose:
+                            - leite
+                            - word: nata",
+            ConfigFormat::Yaml,
+            None,
+            true,
+        )
+        .unwrap();
+        assert_eq!(layer.diet.profiles(), [Diet::Vegetarian, Diet::LactoseFree]);
+        assert_eq!(layer.diet.keywords[&Diet::LactoseFree].len(), 2);
 
-        let doc = doc.unwrap();
-        let value = doc.get(to_yaml_str!("key"));
-        assert!(value.is_some());
+        for format in ConfigFormat::ALL {
+            let contents = layer.to_format(format).unwrap();
+            let (written, _) = ConfigLayer::parse(&contents, format, None, true).unwrap();
+            assert_eq!(written.diet, layer.diet);
+        }
 
-        let value = value.unwrap().as_str();
-        assert!(value.is_some() && value.unwrap() == "value");
+        let diagnostic = ConfigLayer::parse(
+            "bandex:\n  diet:\n    profiles: [carnivore]",
+            ConfigFormat::Yaml,
+            None,
+            false,
+        )
+        .unwrap_err();
+        assert_eq!(diagnostic.key.as_deref(), Some("bandex.diet.profiles[0]"));
     }
 
     #[test]
@@ -479,108 +2197,609 @@ mod tests {
     }
 
     #[test]
-    fn test_get_bandex_yaml() {
-        let values = parse_yaml_from_content("bandex: {restaurants: [{id: 1}]}").unwrap();
-        let value = values.first().unwrap();
-        let config = Config::get_bandex_yaml(value);
+    fn test_config_layer_parse() {
+        let (layer, _) = ConfigLayer::parse(
+            "
+            bandex:
+                restaurants:
+                    - id: 123
+                    - id: 456
+                      color: red",
+            ConfigFormat::Yaml,
+            None,
+            false,
+        )
+        .unwrap();
 
-        assert!(config.is_some());
-        assert!(config.unwrap().get(to_yaml_str!("restaurants")).is_some());
+        let restaurants = layer.restaurants.unwrap();
+        assert!(restaurants.len() == 2);
+        assert_eq!(restaurants[0], RestaurantConfig::new(123, DEFAULT_COLOR));
+        assert_eq!(restaurants[1], RestaurantConfig::new(456, Color::Red));
 
-        let values = parse_yaml_from_content("nao_bandex: 42").unwrap();
-        let value = values.first().unwrap();
-        let config = Config::get_bandex_yaml(value);
+        let layer = ConfigLayer::parse("", ConfigFormat::Yaml, None, false);
+        assert!(layer.is_err());
 
-        assert_eq!(config, None);
+        let layer = ConfigLayer::parse(
+            "bandex:\n  restaurants: []",
+            ConfigFormat::Yaml,
+            None,
+            false,
+        );
+        assert!(layer.is_err());
+
+        // Uma camada pode definir apenas as comidas
+        let (layer, _) = ConfigLayer::parse(
+            "bandex:\n  foods:\n    liked: [pudim]",
+            ConfigFormat::Yaml,
+            None,
+            false,
+        )
+        .unwrap();
+        assert!(layer.restaurants.is_none());
+        assert_eq!(layer.liked_foods.len(), 1);
     }
 
     #[test]
-    fn test_get_restaurants_yaml() {
-        let values = parse_yaml_from_content("restaurants:\n  - id: 1\n  - id: 2").unwrap();
-        let value = values.first().unwrap().as_hash().unwrap();
-        let restaurant = Config::get_restaurants_yaml(value);
+    fn test_config_with_foods_and_anchors() {
+        let (layer, _) = ConfigLayer::parse(
+            "
+            bandex:
+                restaurants:
+                    - id: &CENTRAL 6
+                    - id: 8
+                foods:
+                    liked:
+                        - pudim
+                        - bananada: [*CENTRAL]
+                    disliked:
+                        - pvt: [8]",
+            ConfigFormat::Yaml,
+            None,
+            false,
+        )
+        .unwrap();
 
-        assert!(restaurant.is_some());
-        assert_eq!(restaurant.unwrap().len(), 2);
+        assert_eq!(layer.restaurants.unwrap().len(), 2);
+        assert_eq!(layer.liked_foods.len(), 2);
+        assert_eq!(layer.liked_foods[1].name, "bananada");
+        assert_eq!(layer.liked_foods[1].restaurants, Some(vec![6]));
+        assert_eq!(layer.disliked_foods.len(), 1);
+    }
+
+    #[test]
+    fn test_config_invalid_entry_reports_position() {
+        let diagnostic = ConfigLayer::parse(
+            "bandex:\n  restaurants:\n    - id: 1\n    - id: 2\n      color: azul\n",
+            ConfigFormat::Yaml,
+            Some(Path::new("bandex.yaml")),
+            false,
+        )
+        .unwrap_err();
 
-        let values = parse_yaml_from_content("restaurants:\n  1").unwrap();
-        let value = values.first().unwrap().as_hash().unwrap();
-        let restaurant = Config::get_restaurants_yaml(value);
+        assert_eq!(diagnostic.file, Some(PathBuf::from("bandex.yaml")));
+        assert_eq!(diagnostic.location, Some((5, 14)));
+        assert_eq!(
+            diagnostic.key.as_deref(),
+            Some("bandex.restaurants[1].color")
+        );
+        assert!(diagnostic.message.contains("azul"));
 
-        assert_eq!(restaurant, None);
+        let error = Error::from(diagnostic);
+        assert!(error
+            .to_string()
+            .starts_with("bandex.yaml:5:14: `bandex.restaurants[1].color`: "));
     }
 
     #[test]
-    fn test_get_foods_yaml() {
-        let values = parse_yaml_from_content("foods:\n  liked: [bom]\n  disliked: [ruim]").unwrap();
-        let value = values.first().unwrap().as_hash().unwrap();
-        let config = Config::get_foods_yaml(value);
+    fn test_config_unknown_keys() {
+        let contents = "bandex:\n  restaurants:\n    - id: 1\n      colour: red\n  extra: true\n";
 
-        assert!(config.is_some());
-        assert_eq!(config.unwrap().len(), 2);
+        let (layer, warnings) =
+            ConfigLayer::parse(contents, ConfigFormat::Yaml, None, false).unwrap();
+        assert_eq!(
+            layer.restaurants,
+            Some(vec![RestaurantConfig::new(1, DEFAULT_COLOR)])
+        );
 
-        let values = parse_yaml_from_content("restaurants:\n  - id: 1").unwrap();
-        let value = values.first().unwrap().as_hash().unwrap();
-        let config = Config::get_foods_yaml(value);
+        let keys: Vec<_> = warnings
+            .iter()
+            .map(|warning| warning.key.clone().unwrap())
+            .collect();
+        assert_eq!(keys, vec!["bandex.restaurants[0].colour", "bandex.extra"]);
 
-        assert_eq!(config, None);
+        let error = ConfigLayer::parse(contents, ConfigFormat::Yaml, None, true).unwrap_err();
+        assert!(error.to_string().contains("bandex.restaurants[0].colour"));
     }
 
     #[test]
-    fn test_get_liked_foods() {
-        let values = parse_yaml_from_content("liked: [bom, muito bom]\ndisliked: [ruim]").unwrap();
-        let value = values.first().unwrap().as_hash().unwrap();
-        let config = Config::get_liked_foods_yaml(value);
-
-        assert!(config.is_some());
-        assert_eq!(config.unwrap().len(), 2);
+    fn test_config_documents_without_bandex() {
+        let (layer, warnings) = ConfigLayer::parse(
+            "outro: 1\n---\nbandex:\n  restaurants: [{id: 3}]",
+            ConfigFormat::Yaml,
+            None,
+            false,
+        )
+        .unwrap();
 
-        let values = parse_yaml_from_content("disliked: [ruim]").unwrap();
-        let value = values.first().unwrap().as_hash().unwrap();
-        let config = Config::get_liked_foods_yaml(value);
+        assert_eq!(layer.restaurants.unwrap().len(), 1);
+        assert!(warnings
+            .iter()
+            .any(|warning| warning.key.as_deref() == Some("outro")));
+        assert!(warnings
+            .iter()
+            .any(|warning| warning.message.contains("documento 1 ignorado")));
+    }
 
-        assert_eq!(config, None);
+    #[test]
+    fn test_example_configs_are_valid() {
+        for file in [
+            "configs-examples/bandex-config.yaml",
+            "configs-examples/bandex-config-each.yaml",
+            "configs-examples/bandex-config-sao_carlos.yaml",
+            "configs-examples/bandex-config.toml",
+            "configs-examples/bandex-config.json",
+            "configs-examples/bandex-config-cafe.yaml",
+        ] {
+            let result = ConfigLayer::from_file(
+                Path::new(file),
+                ConfigFormat::from_path(Path::new(file)).unwrap(),
+                true,
+            );
+            assert!(result.is_ok(), "{file}: {:?}", result.err());
+
+            let (mut layer, _) = result.unwrap();
+            layer.resolve_paths(Path::new(file));
+            assert_eq!(layer.check_sources(Some(Path::new(file))), vec![]);
+        }
     }
 
     #[test]
-    fn test_get_disliked_foods() {
-        let values = parse_yaml_from_content("liked: [bom]\ndisliked: [ruim, muito_ruim]").unwrap();
-        let value = values.first().unwrap().as_hash().unwrap();
-        let config = Config::get_disliked_foods_yaml(value);
+    fn test_config_merge() {
+        let mut config = Config::default();
+
+        let (layer, _) = ConfigLayer::parse(
+            "bandex:\n  foods:\n    liked: [pudim]",
+            ConfigFormat::Yaml,
+            None,
+            false,
+        )
+        .unwrap();
+        config.merge(layer);
+        assert_eq!(config.restaurants.len(), DEFAULT_RESTAURANTS.len());
+        assert_eq!(config.liked_foods.len(), 1);
+
+        let (layer, _) = ConfigLayer::parse(
+            "bandex:\n  restaurants: [{id: 2}]\n  foods:\n    liked: [bananada]\n    disliked: [pvt]",
+            ConfigFormat::Yaml,
+            None,
+            false,
+        )
+        .unwrap();
+        config.merge(layer);
+        assert_eq!(
+            config.restaurants,
+            vec![RestaurantConfig::new(2, DEFAULT_COLOR)]
+        );
+        assert_eq!(config.liked_foods.len(), 2);
+        assert_eq!(config.disliked_foods.len(), 1);
+    }
 
-        assert!(config.is_some());
-        assert_eq!(config.unwrap().len(), 2);
+    /// Cria um diretório temporário vazio para os testes
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir = env::temp_dir().join(format!("bandex-{name}-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
 
-        let values = parse_yaml_from_content("liked: [bom]").unwrap();
-        let value = values.first().unwrap().as_hash().unwrap();
-        let config = Config::get_disliked_foods_yaml(value);
+    #[test]
+    fn test_candidate_config_files() {
+        let dir = temp_dir("candidates");
+        let home = dir.join("home");
+        let project = home.join("projeto");
+        let current_dir = project.join("src");
+        fs::create_dir_all(&current_dir).unwrap();
+        fs::write(home.join(".bandex.yaml"), "").unwrap();
+
+        // O `.bandex.yaml` do usuário não é considerado um arquivo local
+        let files = candidate_config_files(Some(&home), None, None, Some(&current_dir));
+        let expected = vec![
+            ConfigFile::new(
+                ConfigScope::System,
+                PathBuf::from("/etc/xdg/bandex/config.yaml"),
+            ),
+            ConfigFile::new(ConfigScope::User, home.join(".bandex.yaml")),
+            ConfigFile::new(ConfigScope::User, home.join(".config/bandex/config.yaml")),
+            ConfigFile::new(ConfigScope::Local, current_dir.join(".bandex.yaml")),
+        ];
+        assert_eq!(files, expected);
+
+        fs::write(project.join(".bandex.yaml"), "").unwrap();
+        let files = candidate_config_files(
+            Some(&home),
+            Some(Path::new("/xdg")),
+            Some("/a:/b:relativo"),
+            Some(&current_dir),
+        );
+        let expected = vec![
+            ConfigFile::new(ConfigScope::System, PathBuf::from("/b/bandex/config.yaml")),
+            ConfigFile::new(ConfigScope::System, PathBuf::from("/a/bandex/config.yaml")),
+            ConfigFile::new(ConfigScope::User, home.join(".bandex.yaml")),
+            ConfigFile::new(ConfigScope::User, PathBuf::from("/xdg/bandex/config.yaml")),
+            ConfigFile::new(ConfigScope::Local, project.join(".bandex.yaml")),
+        ];
+        assert_eq!(files, expected);
+
+        // Os arquivos também podem estar em TOML ou JSON
+        fs::write(current_dir.join(".bandex.toml"), "").unwrap();
+        let files = candidate_config_files(Some(&home), None, None, Some(&current_dir));
+        let local = files.last().unwrap();
+        assert_eq!(local.path, current_dir.join(".bandex.toml"));
+        assert_eq!(local.format, ConfigFormat::Toml);
+
+        fs::remove_dir_all(dir).unwrap();
+    }
 
-        assert_eq!(config, None);
+    #[test]
+    fn test_config_load_layers() {
+        let dir = temp_dir("load");
+        let user = dir.join("user.yaml");
+        let local = dir.join("local.yaml");
+        let broken = dir.join("broken.yaml");
+        fs::write(
+            &user,
+            "bandex:\n  restaurants: [{id: 1}]\n  foods: {liked: [pudim]}",
+        )
+        .unwrap();
+        fs::write(&local, "bandex:\n  foods: {disliked: [pvt]}\n  outro: 1").unwrap();
+        fs::write(&broken, "bandex:\n  restaurants: [{id: um}]").unwrap();
+
+        let files = vec![
+            ConfigFile::new(ConfigScope::System, dir.join("nao-existe.yaml")),
+            ConfigFile::new(ConfigScope::User, user.clone()),
+            ConfigFile::new(ConfigScope::Local, local.clone()),
+            ConfigFile::new(ConfigScope::Cli, broken.clone()),
+        ];
+
+        let loaded = Config::load(&files, false).unwrap();
+        assert_eq!(loaded.files, files[1..3].to_vec());
+        assert_eq!(
+            loaded.config.restaurants,
+            vec![RestaurantConfig::new(1, DEFAULT_COLOR)]
+        );
+        assert_eq!(loaded.config.liked_foods.len(), 1);
+        assert_eq!(loaded.config.disliked_foods.len(), 1);
+        assert_eq!(loaded.warnings.len(), 1);
+        assert_eq!(loaded.errors.len(), 1);
+        assert_eq!(loaded.errors[0].file, Some(broken));
+
+        assert!(Config::load(&files, true).is_err());
+
+        // O arquivo do CLI deve existir
+        let files = vec![ConfigFile::new(
+            ConfigScope::Cli,
+            dir.join("nao-existe.yaml"),
+        )];
+        let loaded = Config::load(&files, false).unwrap();
+        assert_eq!(loaded.errors.len(), 1);
+
+        fs::remove_dir_all(dir).unwrap();
     }
 
     #[test]
-    fn test_config_from_file_content() {
-        let config = Config::from_file_content(
-            "
-            bandex:
-                restaurants:
-                    - id: 123
-                    - id: 456
-                      color: red",
+    fn test_config_load_file_sources() {
+        let dir = temp_dir("sources");
+        let config = dir.join("config.yaml");
+        fs::create_dir_all(dir.join("cardapios")).unwrap();
+        fs::write(
+            dir.join("cardapios/cafe.yaml"),
+            "name: Café\nmenus:\n  - {weekday: 1, menu_type: lunch, content: Pão}",
+        )
+        .unwrap();
+        fs::write(
+            dir.join("cardapios/lanche.json"),
+            r#"{"name": "Lanche", "menus": [{"weekday": 1, "menu_type": "almoço"}]}"#,
         )
         .unwrap();
+        fs::write(
+            &config,
+            "bandex:\n  restaurants:\n    - {id: 100, source: file, path: cardapios/cafe.yaml}\n    - {id: 101, source: file, path: cardapios/lanche.json}\n    - {id: 102, source: file}\n    - {id: 6, path: cardapios/cafe.yaml}",
+        )
+        .unwrap();
+
+        let files = vec![ConfigFile::new(ConfigScope::Cli, config.clone())];
+        let loaded = Config::load(&files, false).unwrap();
+        assert_eq!(loaded.files, files);
+        assert_eq!(loaded.config.restaurants.len(), 4);
+        assert_eq!(
+            loaded.config.restaurants[0].path,
+            Some(dir.join("cardapios/cafe.yaml"))
+        );
+
+        let errors = &loaded.errors;
+        assert_eq!(errors.len(), 3);
+        assert!(errors
+            .iter()
+            .all(|error| error.file == Some(config.clone())));
+        assert_eq!(errors[0].key.as_deref(), Some("bandex.restaurants[1].path"));
+        assert!(errors[0].message.contains("lanche.json:1:"));
+        assert!(errors[0].message.contains("menus[0].menu_type"));
+        assert!(errors[1]
+            .message
+            .contains("não define o arquivo dos cardápios"));
+        assert!(errors[2].message.contains("só é usado com `source: file`"));
+
+        assert!(Config::load(&files, true).is_err());
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn test_config_to_format_round_trip() {
+        let config = Config {
+            restaurants: vec![
+                RestaurantConfig::new(6, Color::BrightBlue),
+                RestaurantConfig::new(8, Color::TrueColor { r: 1, g: 2, b: 3 }),
+            ],
+            liked_foods: vec![FoodConfig::new("pudim".to_string(), None)],
+            disliked_foods: vec![FoodConfig::new("pvt".to_string(), Some(vec![8]))],
+            diet: DietConfig::default(),
+        };
+
+        let yaml = config.to_format(ConfigFormat::Yaml);
+        assert!(yaml.contains("color: bright blue"));
+
+        let (layer, warnings) = ConfigLayer::parse(&yaml, ConfigFormat::Yaml, None, true).unwrap();
+        assert!(warnings.is_empty());
+        assert_eq!(layer.restaurants, Some(config.restaurants.clone()));
+        assert_eq!(layer.liked_foods, config.liked_foods);
+        assert_eq!(layer.disliked_foods, config.disliked_foods);
+
+        let yaml = Config::default().to_format(ConfigFormat::Yaml);
+        assert!(!yaml.contains("foods"));
+
+        for format in [ConfigFormat::Toml, ConfigFormat::Json] {
+            let contents = config.to_format(format);
+            let (layer, warnings) = ConfigLayer::parse(&contents, format, None, true).unwrap();
+            assert!(warnings.is_empty());
+            assert_eq!(layer.restaurants.as_ref(), Some(&config.restaurants));
+            assert_eq!(layer.liked_foods, config.liked_foods);
+            assert_eq!(layer.disliked_foods, config.disliked_foods);
+        }
+    }
+
+    #[test]
+    fn test_config_format() {
+        assert_eq!(
+            ConfigFormat::from_path(Path::new("a/config.yml")),
+            Some(ConfigFormat::Yaml)
+        );
+        assert_eq!(
+            ConfigFormat::from_path(Path::new("CONFIG.TOML")),
+            Some(ConfigFormat::Toml)
+        );
+        assert_eq!(
+            ConfigFormat::from_path(Path::new(".bandex.json")),
+            Some(ConfigFormat::Json)
+        );
+        assert_eq!(ConfigFormat::from_path(Path::new(".bandex")), None);
+        assert!("xml".parse::<ConfigFormat>().is_err());
+
+        let file = ConfigFile::new(ConfigScope::Cli, PathBuf::from("bandex.conf"));
+        assert_eq!(file.format, ConfigFormat::Yaml);
+    }
+
+    #[test]
+    fn test_config_layer_parse_toml_and_json() {
+        let yaml = "bandex:\n  restaurants: [{id: 6, color: red}]\n  foods:\n    liked: [pudim, {pvt: [6]}]";
+        let toml = "[bandex]\nrestaurants = [{ id = 6, color = \"red\" }]\n\n[bandex.foods]\nliked = [\"pudim\", { pvt = [6] }]";
+        let json = r#"{"bandex": {"restaurants": [{"id": 6, "color": "red"}], "foods": {"liked": ["pudim", {"pvt": [6]}]}}}"#;
+
+        let (expected, _) = ConfigLayer::parse(yaml, ConfigFormat::Yaml, None, true).unwrap();
+        for (contents, format) in [(toml, ConfigFormat::Toml), (json, ConfigFormat::Json)] {
+            let (layer, warnings) = ConfigLayer::parse(contents, format, None, true).unwrap();
+            assert!(warnings.is_empty());
+            assert_eq!(layer.restaurants, expected.restaurants);
+            assert_eq!(layer.liked_foods, expected.liked_foods);
+        }
+
+        // Os erros indicam a posição e a chave em todos os formatos
+        let toml = "[bandex]\nrestaurants = [{ id = 6, color = \"azul\" }]";
+        let diagnostic = ConfigLayer::parse(toml, ConfigFormat::Toml, None, false).unwrap_err();
+        assert_eq!(diagnostic.location, Some((2, 34)));
+        assert_eq!(
+            diagnostic.key.as_deref(),
+            Some("bandex.restaurants[0].color")
+        );
+
+        let json = "{\"bandex\": {\n  \"restaurants\": [{\"id\": 6, \"color\": \"azul\"}]}}";
+        let diagnostic = ConfigLayer::parse(json, ConfigFormat::Json, None, false).unwrap_err();
+        assert_eq!(diagnostic.location, Some((2, 43)));
+        assert_eq!(
+            diagnostic.key.as_deref(),
+            Some("bandex.restaurants[0].color")
+        );
+
+        let diagnostic =
+            ConfigLayer::parse("[bandex", ConfigFormat::Toml, None, false).unwrap_err();
+        assert!(diagnostic.location.is_some());
+        assert!(ConfigLayer::parse("{} {}", ConfigFormat::Json, None, false).is_err());
+
+        // Chaves desconhecidas também geram avisos
+        let json = r#"{"bandex": {"restaurants": [{"id": 6}]}, "extra": 1}"#;
+        let (_, warnings) = ConfigLayer::parse(json, ConfigFormat::Json, None, false).unwrap();
+        assert_eq!(warnings[0].key.as_deref(), Some("extra"));
+    }
+
+    #[test]
+    fn test_config_check() {
+        let config = Config {
+            restaurants: vec![
+                RestaurantConfig::new(6, DEFAULT_COLOR),
+                RestaurantConfig::new(6, Color::Red),
+            ],
+            liked_foods: vec![
+                FoodConfig::new(" ".to_string(), None),
+                FoodConfig::new("pudim".to_string(), None).with_weight(Some(-2)),
+            ],
+            disliked_foods: vec![FoodConfig::new("pvt".to_string(), Some(vec![6, 8]))],
+            diet: DietConfig::default(),
+        };
+
+        let keys: Vec<_> = config
+            .check()
+            .into_iter()
+            .map(|diagnostic| diagnostic.key.unwrap())
+            .collect();
+        assert_eq!(
+            keys,
+            vec![
+                "bandex.restaurants[1].id",
+                "bandex.foods.liked[0]",
+                "bandex.foods.liked[1]",
+                "bandex.foods.disliked[0]"
+            ]
+        );
+
+        assert!(Config::default().check().is_empty());
+    }
+
+    #[test]
+    fn test_check_restaurant_ids() {
+        let restaurant = |id, source| {
+            let mut restaurant = RestaurantConfig::new(id, Color::Blue);
+            restaurant.source = source;
+            restaurant.path = (source == SourceKind::File).then(|| PathBuf::from("cafe.yaml"));
+            restaurant
+        };
+        let config = Config {
+            restaurants: vec![
+                restaurant(6, SourceKind::Usp),
+                restaurant(100, SourceKind::File),
+                restaurant(8, SourceKind::File),
+                restaurant(13, SourceKind::File),
+                restaurant(100, SourceKind::File),
+            ],
+            ..Config::default()
+        };
 
-        assert!(config.restaurants.len() == 2);
+        let diagnostics = config.check_restaurant_ids();
+        let keys: Vec<_> = diagnostics
+            .iter()
+            .map(|diagnostic| diagnostic.key.as_deref().unwrap())
+            .collect();
         assert_eq!(
-            config.restaurants[0],
-            RestaurantConfig::new(123, DEFAULT_COLOR)
+            keys,
+            vec![
+                "bandex.restaurants[2].id",
+                "bandex.restaurants[3].id",
+                "bandex.restaurants[4].id"
+            ]
         );
+        assert!(diagnostics[0].message.contains("da fonte `usp`"));
+        assert!(diagnostics[1]
+            .message
+            .contains("o ID 13 já é de um restaurante"));
+        assert_eq!(diagnostics[2].message, "restaurante 100 repetido");
+        assert_eq!(config.check()[..3], diagnostics[..]);
+    }
+
+    #[test]
+    fn test_add_food() {
+        let pudim = FoodConfig::new("pudim".to_string(), None);
+        let pvt = FoodConfig::new("pvt".to_string(), Some(vec![8]));
+
+        // Arquivo novo, com o cabeçalho do schema
+        let contents = add_food("", ConfigFormat::Yaml, FoodList::Liked, &pudim)
+            .unwrap()
+            .unwrap();
+        assert!(contents.starts_with(SCHEMA_HEADER));
+        let (layer, _) = ConfigLayer::parse(&contents, ConfigFormat::Yaml, None, true).unwrap();
+        assert_eq!(layer.liked_foods, vec![pudim.clone()]);
+
+        // Arquivo existente, mantendo os comentários do início e as demais chaves
+        let contents =
+            "# Minhas configurações\nbandex:\n  restaurants: [{id: 8}]\n  foods:\n    disliked:\n";
+        let contents = add_food(contents, ConfigFormat::Yaml, FoodList::Disliked, &pvt)
+            .unwrap()
+            .unwrap();
+        assert!(contents.starts_with("# Minhas configurações\n"));
+        let (layer, _) = ConfigLayer::parse(&contents, ConfigFormat::Yaml, None, true).unwrap();
+        assert_eq!(layer.restaurants.unwrap().len(), 1);
+        assert_eq!(layer.disliked_foods, vec![pvt.clone()]);
+
+        // Comida repetida
+        let contents = add_food(&contents, ConfigFormat::Yaml, FoodList::Disliked, &pvt).unwrap();
+        assert!(contents.is_none());
+
+        let contents = add_food("bandex: 1", ConfigFormat::Yaml, FoodList::Liked, &pudim);
+        assert!(contents.is_err());
+
+        // Arquivos TOML e JSON
+        let contents = "# Minhas configurações\n[[bandex.restaurants]]\nid = 8\n";
+        let contents = add_food(contents, ConfigFormat::Toml, FoodList::Liked, &pvt)
+            .unwrap()
+            .unwrap();
+        assert!(contents.starts_with("# Minhas configurações\n"));
+        let (layer, _) = ConfigLayer::parse(&contents, ConfigFormat::Toml, None, true).unwrap();
+        assert_eq!(layer.restaurants.unwrap().len(), 1);
+        assert_eq!(layer.liked_foods, vec![pvt.clone()]);
+
+        let contents = add_food("", ConfigFormat::Json, FoodList::Disliked, &pudim)
+            .unwrap()
+            .unwrap();
+        let (layer, _) = ConfigLayer::parse(&contents, ConfigFormat::Json, None, true).unwrap();
+        assert_eq!(layer.disliked_foods, vec![pudim]);
+    }
+
+    #[test]
+    fn test_config_serde() {
+        let yaml = "bandex:
+  restaurants:
+    - {id: 8, color: [187, 35, 51], hours: {lunch: \"10:45-14:15\"}}
+    - {id: 6}
+  foods:
+    liked: [pudim, {word: ovo, except: [ovomaltine], weight: 2}]
+    disliked: [{pvt: [8]}]
+  diet:
+    profiles: [vegetarian]";
+        let (layer, _) = ConfigLayer::parse(yaml, ConfigFormat::Yaml, None, true).unwrap();
+        let mut config = Config::default();
+        config.merge(layer);
+
+        let json = serde_json::to_value(&config).unwrap();
         assert_eq!(
-            config.restaurants[1],
-            RestaurantConfig::new(456, Color::Red)
+            json["restaurants"][0],
+            serde_json::json!({"id": 8, "color": [187, 35, 51], "hours": {"lunch": "10:45-14:15"}})
         );
+        assert_eq!(json["liked_foods"][0], "pudim");
+        assert_eq!(json["disliked_foods"][0], serde_json::json!({"pvt": [8]}));
+        assert_eq!(json["diet"]["profiles"], serde_json::json!(["vegetarian"]));
+        assert_eq!(serde_json::from_value::<Config>(json).unwrap(), config);
+
+        // Os campos ausentes ficam com os valores padrão
+        let config: Config = serde_json::from_str(r#"{"liked_foods": ["pudim"]}"#).unwrap();
+        assert_eq!(config.restaurants, Config::default().restaurants);
+        assert_eq!(config.liked_foods.len(), 1);
+    }
+
+    #[test]
+    fn test_config_schema_is_up_to_date() {
+        // Se este teste falhar, atualize o arquivo com `bandex config schema > bandex-config.schema.json`
+        let committed = include_str!("../bandex-config.schema.json");
+        assert_eq!(committed, config_schema());
+    }
 
-        let config = Config::from_file_content("");
-        assert!(config.is_err());
+    #[test]
+    fn test_schema_color_names() {
+        for name in COLOR_NAMES {
+            assert!(
+                name.parse::<Color>().is_ok(),
+                "cor inválida no schema: {name}"
+            );
+        }
+        for (_, color) in DEFAULT_RESTAURANTS {
+            assert!(COLOR_NAMES.contains(&color_name(color).unwrap()));
+        }
     }
 }
@@ -0,0 +1,412 @@
+/*!
+Restrições alimentares
+
+Este módulo verifica os cardápios de acordo com as restrições alimentares escolhidas em
+`bandex.diet.profiles` (vegetariana, vegana, sem lactose e sem glúten):
+- Cada restrição possui um dicionário de palavras-chave, como "leite" e "queijo", que pode
+  ser substituído em `bandex.diet.keywords`
+- As palavras-chave são procuradas como as comidas das configurações, sem diferenciar
+  maiúsculas, acentos e plural
+- O prato principal e a opção ("Opção: ...") são identificados, de modo que se possa saber
+  se o restaurante tem algum prato principal compatível com as restrições
+*/
+
+use std::{collections::BTreeMap, fmt};
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    config::{Config, FoodConfig, FoodMatch},
+    text,
+    types::RestaurantID,
+};
+
+/// Carnes e peixes, incompatíveis com as dietas vegetariana e vegana.
+const MEAT_KEYWORDS: [(&str, &[&str]); 24] = [
+    ("carne", &["carne de soja", "carne vegetal"]),
+    ("frango", &[]),
+    ("peixe", &[]),
+    ("porco", &[]),
+    ("suíno", &[]),
+    ("bovino", &[]),
+    ("bacon", &[]),
+    ("linguiça", &[]),
+    ("salsicha", &[]),
+    ("presunto", &[]),
+    ("calabresa", &[]),
+    ("bife", &[]),
+    ("costela", &[]),
+    ("almôndega", &["almôndega de soja", "almôndega vegetal"]),
+    ("hambúrguer", &["hambúrguer de soja", "hambúrguer vegetal"]),
+    ("pernil", &[]),
+    ("lombo", &[]),
+    ("feijoada", &["feijoada vegana", "feijoada vegetariana"]),
+    ("atum", &[]),
+    ("sardinha", &[]),
+    ("tilápia", &[]),
+    ("merluza", &[]),
+    ("camarão", &[]),
+    ("frutos do mar", &[]),
+];
+
+/// Derivados do leite, incompatíveis com as dietas vegana e sem lactose.
+const DAIRY_KEYWORDS: [(&str, &[&str]); 12] = [
+    (
+        "leite",
+        &["leite de coco", "leite de soja", "leite vegetal"],
+    ),
+    ("queijo", &["queijo vegano", "queijo vegetal"]),
+    ("manteiga", &[]),
+    ("iogurte", &[]),
+    ("requeijão", &[]),
+    ("nata", &[]),
+    ("parmesão", &[]),
+    ("muçarela", &[]),
+    ("mussarela", &[]),
+    ("catupiry", &[]),
+    ("chantilly", &[]),
+    ("pudim", &[]),
+];
+
+/// Outros alimentos de origem animal, incompatíveis com a dieta vegana.
+const ANIMAL_KEYWORDS: [(&str, &[&str]); 5] = [
+    ("ovo", &[]),
+    ("omelete", &[]),
+    ("mel", &[]),
+    ("maionese", &["maionese vegana"]),
+    ("gelatina", &[]),
+];
+
+/// Alimentos com glúten, incompatíveis com a dieta sem glúten.
+const GLUTEN_KEYWORDS: [(&str, &[&str]); 20] = [
+    ("trigo", &[]),
+    ("pão", &["pão sem glúten"]),
+    ("minipão", &[]),
+    ("macarrão", &["macarrão de arroz", "macarrão sem glúten"]),
+    ("massa", &["massa sem glúten"]),
+    ("espaguete", &[]),
+    ("lasanha", &[]),
+    ("nhoque", &[]),
+    ("empanado", &[]),
+    ("milanesa", &[]),
+    ("torta", &[]),
+    ("bolo", &["bolo sem glúten"]),
+    ("biscoito", &[]),
+    ("bolacha", &[]),
+    ("pizza", &[]),
+    ("panqueca", &[]),
+    ("quibe", &[]),
+    ("pastel", &[]),
+    ("aveia", &[]),
+    ("cevada", &[]),
+];
+
+/// Restrições alimentares que podem ser escolhidas em `bandex.diet.profiles`.
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Deserialize, Serialize, JsonSchema,
+)]
+pub enum Diet {
+    /// Sem carnes e peixes.
+    #[serde(rename = "vegetarian")]
+    Vegetarian,
+    /// Sem nenhum alimento de origem animal.
+    #[serde(rename = "vegan")]
+    Vegan,
+    /// Sem leite e derivados.
+    #[serde(rename = "lactose")]
+    LactoseFree,
+    /// Sem trigo e outros cereais com glúten.
+    #[serde(rename = "gluten")]
+    GlutenFree,
+}
+
+impl Diet {
+    /// Palavras-chave padrão da restrição, usadas se `bandex.diet.keywords` não a definir.
+    pub fn default_keywords(&self) -> Vec<FoodConfig> {
+        let keywords: Vec<(&str, &[&str])> = match self {
+            Diet::Vegetarian => MEAT_KEYWORDS.to_vec(),
+            Diet::Vegan => [&MEAT_KEYWORDS[..], &DAIRY_KEYWORDS, &ANIMAL_KEYWORDS].concat(),
+            Diet::LactoseFree => DAIRY_KEYWORDS.to_vec(),
+            Diet::GlutenFree => GLUTEN_KEYWORDS.to_vec(),
+        };
+
+        keywords
+            .into_iter()
+            .map(|(name, except)| {
+                FoodConfig::new(name.to_string(), None)
+                    .with_modifiers(
+                        FoodMatch::Word,
+                        except.iter().map(|except| except.to_string()).collect(),
+                    )
+                    .expect("as palavras-chave padrão não usam expressões regulares")
+            })
+            .collect()
+    }
+}
+
+impl fmt::Display for Diet {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Diet::Vegetarian => "vegetariana",
+            Diet::Vegan => "vegana",
+            Diet::LactoseFree => "sem lactose",
+            Diet::GlutenFree => "sem glúten",
+        })
+    }
+}
+
+/// Conteúdo da chave `bandex.diet` do arquivo de configurações.
+#[derive(Debug, Default, Clone, PartialEq, Deserialize, Serialize, JsonSchema)]
+#[schemars(deny_unknown_fields)]
+pub struct DietConfig {
+    /// Restrições alimentares escolhidas (se não forem definidas, valem as dos arquivos anteriores).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[schemars(title = "Restrições alimentares")]
+    pub profiles: Option<Vec<Diet>>,
+
+    /// Palavras-chave que substituem o dicionário padrão de cada restrição.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    #[schemars(title = "Palavras-chave das restrições")]
+    pub keywords: BTreeMap<Diet, Vec<FoodConfig>>,
+}
+
+impl DietConfig {
+    /// Verifica se nenhuma restrição nem palavra-chave foi definida.
+    pub fn is_empty(&self) -> bool {
+        self.profiles.is_none() && self.keywords.is_empty()
+    }
+
+    /// Aplica as configurações de outra camada: as restrições, se definidas, substituem as
+    /// atuais, e as palavras-chave substituem as da mesma restrição.
+    pub fn merge(&mut self, other: DietConfig) {
+        if let Some(profiles) = other.profiles {
+            self.profiles = Some(profiles);
+        }
+        self.keywords.extend(other.keywords);
+    }
+
+    /// Restrições alimentares escolhidas.
+    pub fn profiles(&self) -> &[Diet] {
+        self.profiles.as_deref().unwrap_or_default()
+    }
+}
+
+/// Papel de uma linha do cardápio.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Dish {
+    /// Prato principal, como "Carne em cubos".
+    Main,
+    /// Opção ao prato principal, como "Opção: Ovos mexidos".
+    Option,
+    /// Acompanhamentos, salada, sobremesa, etc.
+    Side,
+}
+
+/// Resultado da verificação de uma linha do cardápio.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DietLine {
+    /// Papel da linha no cardápio.
+    pub dish: Dish,
+
+    /// Palavras-chave das restrições encontradas na linha.
+    pub keywords: Vec<String>,
+}
+
+impl DietLine {
+    /// Verifica se a linha é compatível com as restrições alimentares.
+    pub fn is_compatible(&self) -> bool {
+        self.keywords.is_empty()
+    }
+}
+
+/// Resultado da verificação de um cardápio, com uma entrada para cada linha não vazia.
+#[derive(Debug, Clone, PartialEq)]
+pub struct MenuDiet {
+    /// Verificação de cada linha, na ordem do cardápio.
+    pub lines: Vec<DietLine>,
+}
+
+impl MenuDiet {
+    /// Verifica se o prato principal ou a opção são compatíveis com as restrições.
+    ///
+    /// Se nenhum prato principal for identificado, o cardápio é considerado compatível.
+    pub fn has_compatible_main(&self) -> bool {
+        let mut mains = self
+            .lines
+            .iter()
+            .filter(|line| line.dish != Dish::Side)
+            .peekable();
+
+        mains.peek().is_none() || mains.any(DietLine::is_compatible)
+    }
+}
+
+/// Verifica os cardápios de acordo com as restrições alimentares das configurações.
+pub struct DietChecker {
+    /// Palavras-chave de todas as restrições escolhidas.
+    keywords: Vec<FoodConfig>,
+}
+
+impl DietChecker {
+    /// Cria o verificador, ou `None` se nenhuma restrição foi escolhida.
+    pub fn new(config: &Config) -> Option<DietChecker> {
+        let profiles = config.diet.profiles();
+        if profiles.is_empty() {
+            return None;
+        }
+
+        let mut keywords: Vec<FoodConfig> = Vec::new();
+        for diet in profiles {
+            let diet_keywords = match config.diet.keywords.get(diet) {
+                Some(diet_keywords) => diet_keywords.clone(),
+                None => diet.default_keywords(),
+            };
+
+            for keyword in diet_keywords {
+                if !keywords.iter().any(|other| other.same_food(&keyword)) {
+                    keywords.push(keyword);
+                }
+            }
+        }
+
+        Some(DietChecker { keywords })
+    }
+
+    /// Retorna as palavras-chave encontradas em uma linha do cardápio.
+    pub fn check_line(&self, line: &str, restaurant_id: RestaurantID) -> Vec<String> {
+        self.keywords
+            .iter()
+            .filter(|keyword| keyword.check_line(line, restaurant_id as u64))
+            .map(|keyword| keyword.name.clone())
+            .collect()
+    }
+
+    /// Verifica as linhas não vazias do cardápio (`content`).
+    ///
+    /// A opção é a linha que começa com "Opção:" e o prato principal é a linha anterior a
+    /// ela. Se não houver opção, o prato principal é a segunda linha, já que os cardápios
+    /// começam pelo arroz e pelo feijão.
+    pub fn check_menu(&self, content: &str, restaurant_id: RestaurantID) -> MenuDiet {
+        let lines: Vec<&str> = content
+            .split('\n')
+            .filter(|line| !line.is_empty())
+            .collect();
+
+        let option = lines
+            .iter()
+            .position(|line| text::normalize(line).starts_with("opcao"));
+        let main = match option {
+            Some(option) => option.checked_sub(1),
+            None => (lines.len() > 1).then_some(1),
+        };
+
+        let lines = lines
+            .iter()
+            .enumerate()
+            .map(|(index, line)| DietLine {
+                dish: if Some(index) == option {
+                    Dish::Option
+                } else if Some(index) == main {
+                    Dish::Main
+                } else {
+                    Dish::Side
+                },
+                keywords: self.check_line(line, restaurant_id),
+            })
+            .collect();
+
+        MenuDiet { lines }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MENU: &str = "Arroz, feijão, arroz integral\nCarne em cubos com molho ferrugem \nOpção: Ovos mexidos com legumes\nBerinjela com pimentões \nSalada de alface\nPudim de leite\nMinipão, refresco\n";
+
+    fn checker(profiles: Vec<Diet>) -> DietChecker {
+        let config = Config {
+            diet: DietConfig {
+                profiles: Some(profiles),
+                keywords: BTreeMap::new(),
+            },
+            ..Config::default()
+        };
+        DietChecker::new(&config).unwrap()
+    }
+
+    #[test]
+    fn test_check_menu_vegetarian() {
+        let diet = checker(vec![Diet::Vegetarian]).check_menu(MENU, 6);
+
+        let dishes: Vec<_> = diet.lines.iter().map(|line| line.dish).collect();
+        assert_eq!(
+            dishes,
+            vec![
+                Dish::Side,
+                Dish::Main,
+                Dish::Option,
+                Dish::Side,
+                Dish::Side,
+                Dish::Side,
+                Dish::Side
+            ]
+        );
+        assert_eq!(diet.lines[1].keywords, vec!["carne"]);
+        assert!(diet.lines[2].is_compatible());
+        assert!(diet.has_compatible_main());
+    }
+
+    #[test]
+    fn test_check_menu_vegan_and_gluten() {
+        let diet = checker(vec![Diet::Vegan, Diet::GlutenFree]).check_menu(MENU, 6);
+
+        assert_eq!(diet.lines[2].keywords, vec!["ovo"]);
+        assert_eq!(diet.lines[5].keywords, vec!["leite", "pudim"]);
+        assert_eq!(diet.lines[6].keywords, vec!["minipão"]);
+        assert!(!diet.has_compatible_main());
+
+        // Palavras inteiras e exceções
+        let checker = checker(vec![Diet::Vegan]);
+        assert!(checker.check_line("Melancia", 6).is_empty());
+        assert!(checker
+            .check_line("Strogonoff de carne de soja", 6)
+            .is_empty());
+        assert!(checker.check_line("Arroz com leite de coco", 6).is_empty());
+    }
+
+    #[test]
+    fn test_check_menu_without_option() {
+        let checker = checker(vec![Diet::Vegetarian]);
+
+        let diet = checker.check_menu("Arroz/Feijão\nFrango assado\nSalada", 6);
+        assert_eq!(diet.lines[1].dish, Dish::Main);
+        assert!(!diet.has_compatible_main());
+
+        let diet = checker.check_menu("Lanche", 6);
+        assert!(diet.has_compatible_main());
+    }
+
+    #[test]
+    fn test_diet_keywords_override() {
+        let mut config = Config::default();
+        assert!(DietChecker::new(&config).is_none());
+
+        config.diet.merge(DietConfig {
+            profiles: Some(vec![Diet::LactoseFree]),
+            keywords: BTreeMap::from([(
+                Diet::LactoseFree,
+                vec![FoodConfig::new("creme".to_string(), None)],
+            )]),
+        });
+        let checker = DietChecker::new(&config).unwrap();
+        assert_eq!(checker.check_line("Creme de milho", 6), vec!["creme"]);
+        assert!(checker.check_line("Queijo", 6).is_empty());
+
+        // Uma camada sem restrições mantém as anteriores
+        config.diet.merge(DietConfig::default());
+        assert_eq!(config.diet.profiles(), [Diet::LactoseFree]);
+    }
+}
@@ -5,14 +5,20 @@ Este módulo contém estruturas e funções para exibir informações do Bandex.
  */
 
 use crate::{
-    config::Config,
-    types::{Menu, MenuType, MenusCache, RestaurantID},
+    changes::{DiffLine, MenuChange},
+    config::{Config, ConfigDiagnostic, ConfigFile, LoadedConfig, DEFAULT_COLOR},
+    diet::{DietChecker, DietLine, Dish},
+    history::HistoryEntry,
+    recommend::{self, Candidate},
+    search::SearchHit,
+    stats::Stats,
+    types::{Menu, MenuSort, MenuType, MenusCache, RestaurantID},
 };
-use chrono::Weekday;
+use chrono::{Datelike, Weekday};
 use colored::{Color, Colorize};
 
 /// Dias da semana
-const WEEKDAYS: [Weekday; 5] = [
+pub const WEEKDAYS: [Weekday; 5] = [
     Weekday::Mon,
     Weekday::Tue,
     Weekday::Wed,
@@ -21,7 +27,7 @@ const WEEKDAYS: [Weekday; 5] = [
 ];
 
 /// Nome dos dias da semana
-const WEEKDAY_NAMES: [&str; 7] = [
+pub const WEEKDAY_NAMES: [&str; 7] = [
     "Segunda-feira",
     "Terça-feira",
     "Quarta-feira",
@@ -49,6 +55,10 @@ const COLOR_MENU_TYPE: Color = Color::TrueColor {
 pub struct Display {
     /// Cache dos menus e dos nomes dos restaurantes, de modo a evitar requisições desnecessárias.
     menus_cache: MenusCache,
+
+    /// Se as mudanças nos cardápios desde a primeira versão guardada no histórico devem ser
+    /// mostradas.
+    show_changes: bool,
 }
 
 /// Cria 3 tipos de titulos que podem ser coloridos.
@@ -83,13 +93,20 @@ impl Display {
         "\x1b[38;5;170m",
     );
 
-    /// Cria uma instância vazia do Display (sem cache).
-    pub fn new() -> Self {
+    /// Cria uma instância do Display a partir de um cache (normalmente, vazio).
+    pub fn new(menus_cache: MenusCache) -> Self {
         Self {
-            menus_cache: MenusCache::new(),
+            menus_cache,
+            show_changes: false,
         }
     }
 
+    /// Define se as mudanças nos cardápios devem ser mostradas abaixo de cada cardápio.
+    pub fn with_changes(mut self, show_changes: bool) -> Self {
+        self.show_changes = show_changes;
+        self
+    }
+
     /// Mostra a logo do Bandex com ou sem cor.
     ///
     /// Referência da fonte: <https://patorjk.com/software/taag/#p=display&f=Doom&t=Bandex>.
@@ -120,16 +137,158 @@ impl Display {
         println!("   Erro: {}", msg);
     }
 
+    /// Mostra uma mensagem simples de aviso formatada.
+    pub fn warning_message(msg: String) {
+        println!("   Aviso: {}", msg);
+    }
+
+    /// Mostra os arquivos de configuração procurados, na ordem em que são aplicados,
+    /// indicando quais foram usados.
+    pub fn show_config_files(files: &[ConfigFile], loaded_config: &LoadedConfig) {
+        for file in files {
+            let failed = loaded_config
+                .errors
+                .iter()
+                .any(|error| error.file.as_ref() == Some(&file.path));
+
+            let status = if loaded_config.files.contains(file) {
+                "✔ usado".green()
+            } else if failed {
+                "✘ com erro".red()
+            } else {
+                "✘ não encontrado".normal()
+            };
+
+            println!(
+                "   {:<8} {}  {}",
+                file.scope.to_string(),
+                file.path.display(),
+                status
+            );
+        }
+
+        if loaded_config.files.is_empty() {
+            println!("\n   Nenhum arquivo usado, as configurações padrão foram aplicadas.");
+        }
+    }
+
+    /// Mostra o resultado da verificação dos arquivos de configuração: os problemas de cada
+    /// arquivo e, em seguida, os problemas das configurações efetivas (`checks`).
+    pub fn show_validation(
+        files: &[ConfigFile],
+        loaded_config: &LoadedConfig,
+        checks: &[ConfigDiagnostic],
+    ) {
+        for file in files {
+            let errors: Vec<_> = loaded_config
+                .errors
+                .iter()
+                .filter(|error| error.file.as_ref() == Some(&file.path))
+                .collect();
+            let warnings: Vec<_> = loaded_config
+                .warnings
+                .iter()
+                .filter(|warning| warning.file.as_ref() == Some(&file.path))
+                .collect();
+
+            if errors.is_empty() && !loaded_config.files.contains(file) {
+                continue;
+            }
+
+            let status = if !errors.is_empty() {
+                "✘".red()
+            } else if !warnings.is_empty() {
+                "!".yellow()
+            } else {
+                "✔".green()
+            };
+            println!("   {} {} ({})", status, file.path.display(), file.scope);
+
+            for error in errors {
+                println!("      {} {}", "Erro:".red(), error);
+            }
+            for warning in warnings {
+                println!("      {} {}", "Aviso:".yellow(), warning);
+            }
+        }
+
+        if !checks.is_empty() {
+            println!("\n   Problemas nas configurações efetivas:");
+            for check in checks {
+                println!("      {} {}", "Aviso:".yellow(), check);
+            }
+        }
+
+        let problems = loaded_config.errors.len() + loaded_config.warnings.len() + checks.len();
+        if problems == 0 {
+            println!("\n   Nenhum problema encontrado.");
+        } else {
+            println!("\n   {} problema(s) encontrado(s).", problems);
+        }
+    }
+
     /// Mostra um cardápio a partir de uma instancia de `Menu`.
+    ///
+    /// Se houver restrições alimentares nas configurações, a opção compatível é destacada,
+    /// os pratos principais incompatíveis são esmaecidos e as palavras-chave encontradas
+    /// nos acompanhamentos são avisadas.
     pub fn show_menu(menu: Menu, restaurant_id: RestaurantID, config: &Config) {
         if menu.content == "Fechado" {
             println!("   ✘ Fechado");
         } else {
-            for line in menu.content.split("\n") {
-                if line.is_empty() {
-                    continue;
+            let diet = DietChecker::new(config)
+                .map(|checker| checker.check_menu(&menu.content, restaurant_id));
+
+            if diet
+                .as_ref()
+                .is_some_and(|diet| !diet.has_compatible_main())
+            {
+                let profiles: Vec<_> = config
+                    .diet
+                    .profiles()
+                    .iter()
+                    .map(|diet| diet.to_string())
+                    .collect();
+                println!(
+                    "   {}\n",
+                    format!(
+                        "⚠ Nenhum prato principal compatível com a dieta ({})",
+                        profiles.join(", ")
+                    )
+                    .yellow()
+                );
+            }
+
+            let lines = menu.content.split("\n").filter(|line| !line.is_empty());
+            for (index, line) in lines.enumerate() {
+                let diet_line = diet.as_ref().map(|diet| &diet.lines[index]);
+
+                match diet_line {
+                    Some(DietLine {
+                        dish: Dish::Option,
+                        keywords,
+                    }) if keywords.is_empty() => {
+                        println!("   ★  {}", line.bold());
+                        continue;
+                    }
+                    Some(DietLine {
+                        dish: Dish::Main | Dish::Option,
+                        keywords,
+                    }) if !keywords.is_empty() => {
+                        let keywords = format!("({})", keywords.join(", "));
+                        println!("   ⊘  {} {}", line.dimmed(), keywords.dimmed());
+                        continue;
+                    }
+                    _ => {}
                 }
 
+                let warning = match diet_line {
+                    Some(diet_line) if !diet_line.is_compatible() => {
+                        format!("  ⚠ {}", diet_line.keywords.join(", ")).yellow()
+                    }
+                    _ => "".normal(),
+                };
+
                 let liked = config
                     .liked_foods
                     .iter()
@@ -140,11 +299,11 @@ impl Display {
                     .any(|food_config| food_config.check_line(line, restaurant_id as u64));
 
                 if liked {
-                    println!("   ✔  {}", line.green());
+                    println!("   ✔  {}{}", line.green(), warning);
                 } else if disliked {
-                    println!("   ✘  {}", line.red());
+                    println!("   ✘  {}{}", line.red(), warning);
                 } else {
-                    println!("   ➤  {}", line);
+                    println!("   ➤  {}{}", line, warning);
                 }
             }
 
@@ -158,25 +317,301 @@ impl Display {
         println!();
     }
 
+    /// Mostra o restaurante recomendado para a refeição `menu_type` do dia `weekday`, com o
+    /// motivo da escolha, a partir dos candidatos já ordenados (`ranked`).
+    pub fn show_recommendation(weekday: Weekday, menu_type: &MenuType, ranked: &[Candidate]) {
+        let weekday_name = WEEKDAY_NAMES[weekday.num_days_from_monday() as usize];
+        print_header!(
+            H2,
+            format!("{menu_type} de {weekday_name}"),
+            COLOR_MENU_TYPE
+        );
+
+        match ranked {
+            [] => println!("   ✘ Nenhum restaurante aberto"),
+            [best, others @ ..] => println!(
+                "   ★  {} [{:+}]: {}",
+                best.name.bold(),
+                best.score,
+                recommend::reason(best, others)
+            ),
+        }
+        println!();
+    }
+
+    /// Mostra os resultados da busca do prato `term`, um por linha, com o dia, a refeição,
+    /// o restaurante e a linha do cardápio.
+    pub fn show_search_results(term: &str, hits: &[SearchHit]) {
+        print_header!(H2, format!("Busca: {term}"), COLOR_MENU_TYPE);
+
+        if hits.is_empty() {
+            println!("   ✘ Nenhum cardápio da semana tem \"{term}\"");
+        }
+
+        for hit in hits {
+            let weekday_name = WEEKDAY_NAMES[hit.weekday.num_days_from_monday() as usize];
+            println!(
+                "   ➤  {}, {} - {}: {}",
+                weekday_name,
+                hit.menu_type.to_string().to_lowercase(),
+                hit.restaurant_name.color(hit.color),
+                hit.line.green()
+            );
+        }
+        println!();
+    }
+
+    /// Mostra os cardápios do histórico, agrupados pela data e pelo tipo da refeição.
+    ///
+    /// Os restaurantes das configurações são mostrados com as suas cores.
+    pub fn show_history(entries: &[HistoryEntry], config: &Config) {
+        if entries.is_empty() {
+            println!("   ✘ Nenhum cardápio encontrado no histórico\n");
+        }
+
+        let mut last_date = None;
+        let mut last_menu_type = None;
+        for entry in entries {
+            if last_date != Some(entry.date) {
+                let weekday_name =
+                    WEEKDAY_NAMES[entry.date.weekday().num_days_from_monday() as usize];
+                let title = format!("{weekday_name}, {}", entry.date.format("%d/%m/%Y"));
+                print_header!(H1, title, COLOR_WEEK_DAY);
+                last_date = Some(entry.date);
+                last_menu_type = None;
+            }
+            if last_menu_type.as_ref() != Some(&entry.menu_type) {
+                print_header!(H2, entry.menu_type.to_string(), COLOR_MENU_TYPE);
+                last_menu_type = Some(entry.menu_type.clone());
+            }
+
+            let color = config
+                .restaurants
+                .iter()
+                .find(|restaurant| restaurant.id == entry.restaurant_id)
+                .map_or(DEFAULT_COLOR, |restaurant| restaurant.color);
+            print_header!(H3, entry.restaurant_name, color);
+            Display::show_menu(entry.to_menu(), entry.restaurant_id, config);
+        }
+    }
+
+    /// Mostra a comparação linha a linha de um cardápio, com as linhas removidas em vermelho e
+    /// as adicionadas em verde.
+    fn show_diff(lines: &[DiffLine]) {
+        for line in lines {
+            match line {
+                DiffLine::Same(line) => println!("     {}", line.dimmed()),
+                DiffLine::Removed(line) => println!("   {}", format!("- {line}").red()),
+                DiffLine::Added(line) => println!("   {}", format!("+ {line}").green()),
+            }
+        }
+        println!();
+    }
+
+    /// Mostra as mudanças nos cardápios de cada restaurante, com o nome e a cor do restaurante.
+    pub fn show_changes(restaurants: &[(String, Color, Vec<MenuChange>)]) {
+        if restaurants.iter().all(|(_, _, changes)| changes.is_empty()) {
+            println!("   ✔ Nenhum cardápio mudou desde a publicação\n");
+            return;
+        }
+
+        for (name, color, changes) in restaurants {
+            if changes.is_empty() {
+                continue;
+            }
+
+            print_header!(H3, name, *color);
+            for change in changes {
+                let weekday_name = WEEKDAY_NAMES[change.weekday.num_days_from_monday() as usize];
+                println!(
+                    "   ↻ {}, {} - {}:",
+                    weekday_name,
+                    change.date.format("%d/%m/%Y"),
+                    change.menu_type.to_string().to_lowercase()
+                );
+                Display::show_diff(&change.lines);
+            }
+        }
+    }
+
+    /// Mostra uma tabela com o cabeçalho `header` e as linhas `rows`, com as colunas alinhadas.
+    fn print_table(header: &[&str], rows: &[Vec<String>]) {
+        let mut widths: Vec<usize> = header.iter().map(|title| title.chars().count()).collect();
+        for row in rows {
+            for (width, cell) in widths.iter_mut().zip(row) {
+                *width = (*width).max(cell.chars().count());
+            }
+        }
+
+        let format_row = |cells: Vec<String>| {
+            let cells: Vec<String> = cells
+                .iter()
+                .zip(&widths)
+                .map(|(cell, width)| format!("{cell:<width$}"))
+                .collect();
+            format!("   │ {} │", cells.join(" │ "))
+        };
+
+        let header = format_row(header.iter().map(|title| title.to_string()).collect());
+        println!("{}", header.bold());
+        let separator: Vec<String> = widths.iter().map(|width| "─".repeat(*width)).collect();
+        println!("   ├─{}─┤", separator.join("─┼─"));
+        for row in rows {
+            println!("{}", format_row(row.clone()));
+        }
+        println!();
+    }
+
+    /// Mostra as estatísticas do histórico dos cardápios em tabelas.
+    ///
+    /// Os restaurantes das configurações são mostrados com as suas cores.
+    pub fn show_stats(stats: &Stats, config: &Config) {
+        let (Some(first_date), Some(last_date)) = (stats.first_date, stats.last_date) else {
+            println!("   ✘ Nenhum cardápio encontrado no histórico\n");
+            return;
+        };
+
+        let title = format!(
+            "{} a {}",
+            first_date.format("%d/%m/%Y"),
+            last_date.format("%d/%m/%Y")
+        );
+        print_header!(H1, title, COLOR_WEEK_DAY);
+        println!("   {} refeições no histórico\n", stats.menus);
+
+        for restaurant in &stats.restaurants {
+            let color = config
+                .restaurants
+                .iter()
+                .find(|configured| configured.id == restaurant.restaurant_id)
+                .map_or(DEFAULT_COLOR, |configured| configured.color);
+            print_header!(H3, restaurant.name, color);
+            let closed_days: Vec<String> = restaurant
+                .closed_days
+                .iter()
+                .map(|date| date.format("%d/%m").to_string())
+                .collect();
+            println!(
+                "   {} refeições, {} dias fechado{}",
+                restaurant.menus,
+                closed_days.len(),
+                if closed_days.is_empty() {
+                    String::new()
+                } else {
+                    format!(" ({})", closed_days.join(", "))
+                }
+            );
+            let rows: Vec<Vec<String>> = restaurant
+                .top_dishes
+                .iter()
+                .map(|dish| vec![dish.dish.clone(), dish.count.to_string()])
+                .collect();
+            Display::print_table(&["Prato", "Vezes"], &rows);
+        }
+
+        print_header!(H2, "Valor energético", COLOR_MENU_TYPE);
+        let rows: Vec<Vec<String>> = stats
+            .calories
+            .iter()
+            .map(|calories| {
+                vec![
+                    calories.menu_type.to_string(),
+                    calories.menus.to_string(),
+                    calories
+                        .average
+                        .map_or("-".to_string(), |average| format!("{average:.0} kcal")),
+                    calories
+                        .max
+                        .map_or("-".to_string(), |max| format!("{max} kcal")),
+                ]
+            })
+            .collect();
+        Display::print_table(&["Refeição", "Cardápios", "Média", "Máximo"], &rows);
+
+        if !stats.foods.is_empty() {
+            print_header!(H2, "Comidas das configurações", COLOR_MENU_TYPE);
+            let rows: Vec<Vec<String>> = stats
+                .foods
+                .iter()
+                .map(|food| {
+                    vec![
+                        food.name.clone(),
+                        match food.list {
+                            "liked" => "gostada",
+                            _ => "não gostada",
+                        }
+                        .to_string(),
+                        food.count.to_string(),
+                        food.last_date
+                            .map_or("-".to_string(), |date| date.format("%d/%m/%Y").to_string()),
+                        food.days_since
+                            .map_or("-".to_string(), |days| days.to_string()),
+                    ]
+                })
+                .collect();
+            Display::print_table(
+                &["Comida", "Lista", "Vezes", "Última vez", "Dias desde"],
+                &rows,
+            );
+        }
+    }
+
     /// Mostra uma refeição de um tipo específico `menu_type` (almoço ou jantar) de um dia específico `weekday`.
     ///
     /// O parâmetro de configuração `config` define quais restaurantes devem ser exibidos (e com quais cores).
+    /// Se houver comidas configuradas, a nota de cada cardápio é mostrada ao lado do nome do
+    /// restaurante e, com `sort` igual a `MenuSort::Score`, os restaurantes são ordenados por ela.
     async fn show_menus_by_type(
         &mut self,
         menu_type: &MenuType,
         weekday: Weekday,
+        sort: MenuSort,
         config: &Config,
     ) {
         print_header!(H2, menu_type.to_string(), COLOR_MENU_TYPE);
 
+        let mut menus = Vec::new();
         for restaurant in config.restaurants.iter() {
-            if let Some((restaurant_name, menu)) = self
+            let menu = self
                 .menus_cache
                 .get_name_and_menu(restaurant.id, menu_type, weekday)
-                .await
-            {
-                print_header!(H3, restaurant_name, restaurant.color);
+                .await;
+            let score = menu
+                .as_ref()
+                .and_then(|(_, menu)| config.menu_score(&menu.content, restaurant.id));
+            menus.push((restaurant, menu, score));
+        }
+
+        // Os restaurantes fechados e os que não foram carregados ficam por último
+        if sort == MenuSort::Score {
+            menus.sort_by_key(|(_, _, score)| std::cmp::Reverse(score.unwrap_or(i32::MIN)));
+        }
+
+        for (restaurant, menu, score) in menus {
+            if let Some((restaurant_name, menu)) = menu {
+                match score.filter(|_| config.has_foods()) {
+                    Some(score) => {
+                        print_header!(
+                            H3,
+                            format!("{restaurant_name} [{score:+}]"),
+                            restaurant.color
+                        )
+                    }
+                    None => print_header!(H3, restaurant_name, restaurant.color),
+                }
+                let date = menu.date;
                 Display::show_menu(menu, restaurant.id, config);
+
+                if self.show_changes {
+                    let changes = self.menus_cache.get_changes(restaurant.id).await;
+                    let change = changes
+                        .iter()
+                        .find(|change| Some(change.date) == date && &change.menu_type == menu_type);
+                    if let Some(change) = change {
+                        println!("   {}", "↻ Mudou desde a publicação:".yellow());
+                        Display::show_diff(&change.lines);
+                    }
+                }
             } else {
                 Display::error_message(format!(
                     "Não foi possível carregar dados desse restaurante (Rest {})",
@@ -196,6 +631,7 @@ impl Display {
         &mut self,
         menu_type: &Option<MenuType>,
         weekday: Weekday,
+        sort: MenuSort,
         config: &Config,
     ) {
         let weekday_name = WEEKDAY_NAMES[weekday.num_days_from_monday() as usize];
@@ -203,11 +639,12 @@ impl Display {
         print_header!(H1, weekday_name, COLOR_WEEK_DAY);
 
         if let Some(menu_type) = menu_type {
-            self.show_menus_by_type(menu_type, weekday, config).await;
+            self.show_menus_by_type(menu_type, weekday, sort, config)
+                .await;
         } else {
-            self.show_menus_by_type(&MenuType::Lunch, weekday, config)
+            self.show_menus_by_type(&MenuType::Lunch, weekday, sort, config)
                 .await;
-            self.show_menus_by_type(&MenuType::Dinner, weekday, config)
+            self.show_menus_by_type(&MenuType::Dinner, weekday, sort, config)
                 .await;
         }
     }
@@ -220,19 +657,23 @@ impl Display {
     /// * `weekday`: Dia da semana a ser exibido.
     ///     * `Some(weekday)`: Mostra apenas as refeições do dia especificado.
     ///     * `None`: Mostra todas as refeições da semana.
+    /// * `sort`: Ordem em que os restaurantes são exibidos.
     ///
     /// O parâmetro de configuração `config` define quais restaurantes devem ser exibidos (e com quais corer).
     pub async fn show_menus(
         &mut self,
         weekday: Option<Weekday>,
         menu_type: Option<MenuType>,
+        sort: MenuSort,
         config: &Config,
     ) {
         if let Some(weekday) = weekday {
-            self.show_menus_by_day(&menu_type, weekday, config).await;
+            self.show_menus_by_day(&menu_type, weekday, sort, config)
+                .await;
         } else {
             for weekday in WEEKDAYS {
-                self.show_menus_by_day(&menu_type, weekday, config).await;
+                self.show_menus_by_day(&menu_type, weekday, sort, config)
+                    .await;
             }
         }
     }
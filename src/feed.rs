@@ -0,0 +1,258 @@
+/*!
+Feed Atom dos cardápios
+
+Este módulo gera um feed Atom (RFC 4287) com os cardápios da semana, para que os leitores de
+feeds mostrem o cardápio do dia:
+- Cada entrada é o cardápio de um restaurante em uma refeição de um dia
+- O ID de cada entrada é estável, formado pelo ID do restaurante, pela data e pela refeição
+  (`urn:bandex:menu:6:2025-03-06:lunch`), então a mesma refeição não aparece duas vezes
+- A data de cada entrada é o início da refeição no restaurante, no fuso de Brasília
+- Os cardápios sem data são ignorados, já que não é possível criar um ID estável para eles
+*/
+
+use chrono::{DateTime, FixedOffset, NaiveDate, SecondsFormat};
+use html_escape::encode_text;
+
+use crate::{
+    config::{Config, RestaurantConfig},
+    types::{Menu, MenuType, MenusCache, RestaurantID},
+};
+
+/// Tipo do conteúdo do feed.
+pub const CONTENT_TYPE_ATOM: &str = "application/atom+xml; charset=utf-8";
+
+/// ID do feed.
+const FEED_ID: &str = "urn:bandex:feed";
+
+/// Título do feed.
+const FEED_TITLE: &str = "Cardápios dos restaurantes da USP";
+
+/// Página do projeto, usada como link do feed.
+const FEED_LINK: &str = "https://github.com/mocno/bandex";
+
+/// Diferença do fuso de Brasília, em que ficam os restaurantes, para o UTC, em segundos.
+const USP_UTC_OFFSET: i32 = -3 * 3600;
+
+/// Cardápio de um restaurante em uma refeição de um dia, que vira uma entrada do feed.
+#[derive(Debug, Clone)]
+pub struct FeedEntry {
+    /// ID do restaurante.
+    pub restaurant_id: RestaurantID,
+
+    /// Nome do restaurante.
+    pub restaurant_name: String,
+
+    /// Data do cardápio.
+    pub date: NaiveDate,
+
+    /// Cardápio da refeição.
+    pub menu: Menu,
+
+    /// Início da refeição no restaurante, usado como data da entrada.
+    pub updated: DateTime<FixedOffset>,
+}
+
+impl FeedEntry {
+    /// Cria a entrada do cardápio `menu` do restaurante, ou `None` se o cardápio não tiver data.
+    pub fn new(restaurant: &RestaurantConfig, restaurant_name: &str, menu: &Menu) -> Option<Self> {
+        let date = menu.date?;
+        let offset = FixedOffset::east_opt(USP_UTC_OFFSET).expect("fuso válido");
+        let updated = date
+            .and_time(restaurant.hours.window(&menu.menu_type).start)
+            .and_local_timezone(offset)
+            .single()?;
+
+        Some(FeedEntry {
+            restaurant_id: restaurant.id,
+            restaurant_name: restaurant_name.to_string(),
+            date,
+            menu: menu.clone(),
+            updated,
+        })
+    }
+
+    /// ID estável da entrada, formado pelo restaurante, pela data e pela refeição.
+    pub fn id(&self) -> String {
+        format!(
+            "urn:bandex:menu:{}:{}:{}",
+            self.restaurant_id,
+            self.date.format("%Y-%m-%d"),
+            self.menu.menu_type.as_str()
+        )
+    }
+
+    fn title(&self) -> String {
+        format!(
+            "{} de {} - {}",
+            self.menu.menu_type,
+            self.date.format("%d/%m/%Y"),
+            self.restaurant_name
+        )
+    }
+
+    /// Conteúdo da entrada em HTML: os pratos, o valor energético e as observações.
+    fn content(&self) -> String {
+        let lines: Vec<&str> = self
+            .menu
+            .content
+            .split('\n')
+            .map(str::trim)
+            .filter(|line| !line.is_empty())
+            .collect();
+
+        let mut html = match lines[..] {
+            [] | ["Fechado"] => "<p>Fechado</p>".to_string(),
+            _ => {
+                let items: Vec<String> = lines
+                    .iter()
+                    .map(|line| format!("<li>{}</li>", encode_text(line)))
+                    .collect();
+                format!("<ul>{}</ul>", items.join(""))
+            }
+        };
+        if let Some(calorific_value) = self.menu.calorific_value {
+            html.push_str(&format!("<p>Valor energético: {calorific_value} kcal</p>"));
+        }
+        let observation = self.menu.observation.trim();
+        if !observation.is_empty() {
+            html.push_str(&format!("<p>{}</p>", encode_text(observation)));
+        }
+        html
+    }
+}
+
+/// Carrega os cardápios da semana dos restaurantes das configurações como entradas do feed,
+/// ordenadas pela data, pela refeição (almoço antes do jantar) e pela ordem dos restaurantes.
+pub async fn feed_entries(menus_cache: &mut MenusCache, config: &Config) -> Vec<FeedEntry> {
+    let mut entries = Vec::new();
+    for restaurant in &config.restaurants {
+        if let Some((name, menus)) = menus_cache.get_name_and_menus(restaurant.id).await {
+            entries.extend(
+                menus
+                    .iter()
+                    .filter_map(|menu| FeedEntry::new(restaurant, &name, menu)),
+            );
+        }
+    }
+
+    entries.sort_by_key(|entry| (entry.date, entry.menu.menu_type == MenuType::Dinner));
+    entries
+}
+
+/// Data no formato do Atom (RFC 3339), sem as frações de segundo.
+fn rfc3339(date: DateTime<FixedOffset>) -> String {
+    date.to_rfc3339_opts(SecondsFormat::Secs, false)
+}
+
+/// Gera o feed Atom com as entradas `entries`, carregadas em `now`.
+///
+/// A data do feed é `now`, e não a da última refeição, para que os leitores de feeds percebam
+/// os cardápios que mudaram durante o dia.
+pub fn atom_feed(entries: &[FeedEntry], now: DateTime<FixedOffset>) -> String {
+    let mut feed = String::new();
+    feed.push_str("<?xml version=\"1.0\" encoding=\"utf-8\"?>\n");
+    feed.push_str("<feed xmlns=\"http://www.w3.org/2005/Atom\">\n");
+    feed.push_str(&format!("  <id>{FEED_ID}</id>\n"));
+    feed.push_str(&format!("  <title>{FEED_TITLE}</title>\n"));
+    feed.push_str(&format!("  <link href=\"{FEED_LINK}\"/>\n"));
+    feed.push_str(&format!("  <updated>{}</updated>\n", rfc3339(now)));
+    feed.push_str("  <author><name>Bandex</name></author>\n");
+
+    for entry in entries {
+        feed.push_str("  <entry>\n");
+        feed.push_str(&format!("    <id>{}</id>\n", entry.id()));
+        feed.push_str(&format!(
+            "    <title>{}</title>\n",
+            encode_text(&entry.title())
+        ));
+        feed.push_str(&format!(
+            "    <updated>{}</updated>\n",
+            rfc3339(entry.updated)
+        ));
+        feed.push_str(&format!(
+            "    <content type=\"html\">{}</content>\n",
+            encode_text(&entry.content())
+        ));
+        feed.push_str("  </entry>\n");
+    }
+
+    feed.push_str("</feed>\n");
+    feed
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{Datelike, TimeZone};
+    use colored::Color;
+
+    fn menu(day: u32, menu_type: MenuType, content: &str) -> Menu {
+        let date = NaiveDate::from_ymd_opt(2025, 3, day).unwrap();
+        Menu {
+            content: content.to_string(),
+            menu_type,
+            weekday: date.weekday(),
+            date: Some(date),
+            calorific_value: Some(1030),
+            observation: "Cardápio sujeito a modificação".to_string(),
+        }
+    }
+
+    #[test]
+    fn test_feed_entry() {
+        let restaurant = RestaurantConfig::new(6, Color::Blue);
+        let entry = FeedEntry::new(
+            &restaurant,
+            "Central",
+            &menu(6, MenuType::Dinner, "Arroz\nBife & fritas\n"),
+        )
+        .unwrap();
+
+        assert_eq!(entry.id(), "urn:bandex:menu:6:2025-03-06:dinner");
+        assert_eq!(entry.title(), "Jantar de 06/03/2025 - Central");
+        assert_eq!(entry.updated.to_rfc3339(), "2025-03-06T17:30:00-03:00");
+        assert_eq!(
+            entry.content(),
+            "<ul><li>Arroz</li><li>Bife &amp; fritas</li></ul>\
+             <p>Valor energético: 1030 kcal</p><p>Cardápio sujeito a modificação</p>"
+        );
+
+        let closed = FeedEntry::new(&restaurant, "Central", &menu(6, MenuType::Lunch, "Fechado"));
+        assert!(closed.unwrap().content().starts_with("<p>Fechado</p>"));
+
+        let without_date = Menu {
+            date: None,
+            ..menu(6, MenuType::Lunch, "Arroz")
+        };
+        assert!(FeedEntry::new(&restaurant, "Central", &without_date).is_none());
+    }
+
+    #[test]
+    fn test_atom_feed() {
+        let restaurant = RestaurantConfig::new(8, Color::Blue);
+        let entries: Vec<FeedEntry> = [
+            menu(3, MenuType::Lunch, "Arroz"),
+            menu(4, MenuType::Lunch, "Peixe <assado>"),
+        ]
+        .iter()
+        .filter_map(|menu| FeedEntry::new(&restaurant, "Física", menu))
+        .collect();
+        let now = FixedOffset::east_opt(USP_UTC_OFFSET)
+            .unwrap()
+            .with_ymd_and_hms(2025, 3, 10, 8, 0, 0)
+            .unwrap();
+
+        let feed = atom_feed(&entries, now);
+        assert!(feed.starts_with("<?xml version=\"1.0\" encoding=\"utf-8\"?>\n<feed"));
+        assert!(feed.contains("  <updated>2025-03-10T08:00:00-03:00</updated>\n"));
+        assert!(feed.contains("    <updated>2025-03-04T11:00:00-03:00</updated>\n"));
+        assert_eq!(feed.matches("<entry>").count(), 2);
+        assert!(feed.contains("<id>urn:bandex:menu:8:2025-03-03:lunch</id>"));
+        assert!(feed.contains("<title>Almoço de 04/03/2025 - Física</title>"));
+        assert!(feed.contains("&lt;li&gt;Peixe &amp;lt;assado&amp;gt;&lt;/li&gt;"));
+
+        let feed = atom_feed(&[], now);
+        assert!(feed.contains("<updated>2025-03-10T08:00:00-03:00</updated>"));
+        assert!(!feed.contains("<entry>"));
+    }
+}
@@ -0,0 +1,562 @@
+/*!
+Histórico dos cardápios
+
+Os cardápios somem do sistema da USP a cada semana, então todo cardápio carregado pelo
+Bandex é guardado em um banco de dados SQLite local:
+- O banco fica em `$XDG_DATA_HOME/bandex/history.sqlite3` (por padrão, em `~/.local/share`),
+  ou no arquivo da variável de ambiente `BANDEX_HISTORY_FILE`
+- Cada refeição é guardada uma única vez, pelo restaurante, pela data e pelo tipo da refeição,
+  de modo que um cardápio carregado de novo apenas atualiza o anterior
+- A primeira versão de cada refeição também é guardada, em uma tabela separada que nunca é
+  atualizada, para que a versão carregada seja comparada com a publicada e os pratos trocados
+  depois da publicação sejam encontrados (`bandex diff`), mesmo depois de outras consultas
+- Os cardápios sem data não são guardados
+- O histórico pode ser consultado por período e por restaurante (`bandex history`)
+*/
+
+use std::{
+    env, error, fmt, fs,
+    path::{Path, PathBuf},
+};
+
+use chrono::{Datelike, Local, NaiveDate};
+use rusqlite::{
+    params, params_from_iter,
+    types::{FromSql, FromSqlError, FromSqlResult, ToSqlOutput, Value, ValueRef},
+    Connection, OptionalExtension, Row, ToSql,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    config,
+    types::{Menu, MenuType, RestaurantID},
+};
+
+/// Variável de ambiente para o arquivo do histórico.
+const ENV_VAR_HISTORY_FILE: &str = "BANDEX_HISTORY_FILE";
+
+/// Variável de ambiente com o diretório de dados do usuário (especificação XDG).
+const ENV_VAR_XDG_DATA_HOME: &str = "XDG_DATA_HOME";
+
+/// Arquivo do histórico dentro do diretório de dados do usuário.
+const XDG_HISTORY_FILE: &str = "bandex/history.sqlite3";
+
+/// Formato das datas guardadas no banco (ISO 8601), que mantém a ordem ao comparar os textos.
+const DATE_FORMAT: &str = "%Y-%m-%d";
+
+/// Cria a tabela dos cardápios, se ela ainda não existir.
+const CREATE_TABLE: &str = "
+CREATE TABLE IF NOT EXISTS menus (
+    restaurant_id INTEGER NOT NULL,
+    restaurant_name TEXT NOT NULL,
+    date TEXT NOT NULL,
+    menu_type TEXT NOT NULL,
+    calorific_value INTEGER,
+    content TEXT NOT NULL,
+    observation TEXT NOT NULL,
+    updated_at TEXT NOT NULL,
+    PRIMARY KEY (restaurant_id, date, menu_type)
+)";
+
+/// Tabela com a primeira versão guardada de cada cardápio, com as mesmas colunas de `menus`.
+const CREATE_PUBLISHED_TABLE: &str = "
+CREATE TABLE published_menus (
+    restaurant_id INTEGER NOT NULL,
+    restaurant_name TEXT NOT NULL,
+    date TEXT NOT NULL,
+    menu_type TEXT NOT NULL,
+    calorific_value INTEGER,
+    content TEXT NOT NULL,
+    observation TEXT NOT NULL,
+    updated_at TEXT NOT NULL,
+    PRIMARY KEY (restaurant_id, date, menu_type)
+)";
+
+/// Arquivo do histórico, definido pela variável de ambiente `BANDEX_HISTORY_FILE` ou no
+/// diretório de dados do usuário.
+pub fn history_file() -> Option<PathBuf> {
+    if let Some(path) = env::var_os(ENV_VAR_HISTORY_FILE).filter(|path| !path.is_empty()) {
+        return Some(PathBuf::from(path));
+    }
+
+    config::read_env_dir(ENV_VAR_XDG_DATA_HOME)
+        .or_else(|| env::var_os("HOME").map(|home| PathBuf::from(home).join(".local/share")))
+        .map(|dir| dir.join(XDG_HISTORY_FILE))
+}
+
+/// O tipo da refeição é guardado no banco com o seu nome no serde ([`MenuType::as_str`]).
+impl ToSql for MenuType {
+    fn to_sql(&self) -> rusqlite::Result<ToSqlOutput<'_>> {
+        Ok(ToSqlOutput::from(self.as_str()))
+    }
+}
+
+/// Os tipos de refeição desconhecidos são um erro de conversão
+/// (`rusqlite::Error::FromSqlConversionFailure` ao ler a linha).
+impl FromSql for MenuType {
+    fn column_result(value: ValueRef<'_>) -> FromSqlResult<Self> {
+        value
+            .as_str()?
+            .parse()
+            .map_err(|error: String| FromSqlError::Other(error.into()))
+    }
+}
+
+/// Erro do histórico ao carregar os cardápios, informado pelo cache dos cardápios
+/// ([`MenusCache::with_history_error_handler`](crate::types::MenusCache::with_history_error_handler)).
+#[derive(Debug)]
+pub enum HistoryError {
+    /// Os cardápios carregados não puderam ser guardados, e o histórico deixa de ser usado.
+    Save(rusqlite::Error),
+    /// As versões guardadas dos cardápios não puderam ser consultadas para encontrar as
+    /// mudanças.
+    Find(rusqlite::Error),
+}
+
+impl fmt::Display for HistoryError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            HistoryError::Save(error) => write!(
+                f,
+                "Não foi possível guardar os cardápios no histórico: {error}"
+            ),
+            HistoryError::Find(error) => {
+                write!(f, "Não foi possível consultar o histórico: {error}")
+            }
+        }
+    }
+}
+
+impl error::Error for HistoryError {
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        match self {
+            HistoryError::Save(error) | HistoryError::Find(error) => Some(error),
+        }
+    }
+}
+
+/// Cardápio guardado no histórico, junto do restaurante.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct HistoryEntry {
+    /// ID do restaurante.
+    pub restaurant_id: RestaurantID,
+
+    /// Nome do restaurante quando o cardápio foi guardado.
+    pub restaurant_name: String,
+
+    /// Data do cardápio.
+    pub date: NaiveDate,
+
+    /// Refeição do cardápio.
+    pub menu_type: MenuType,
+
+    /// Valor energético da refeição, em kcal.
+    pub calorific_value: Option<usize>,
+
+    /// Pratos do cardápio, um por linha.
+    pub content: String,
+
+    /// Observação do restaurante sobre o cardápio.
+    pub observation: String,
+}
+
+impl HistoryEntry {
+    /// Lê uma entrada a partir de uma linha da tabela `menus`.
+    fn from_row(row: &Row) -> rusqlite::Result<HistoryEntry> {
+        let date: String = row.get("date")?;
+        let date = NaiveDate::parse_from_str(&date, DATE_FORMAT).map_err(|error| {
+            rusqlite::Error::FromSqlConversionFailure(0, rusqlite::types::Type::Text, error.into())
+        })?;
+
+        Ok(HistoryEntry {
+            restaurant_id: row.get("restaurant_id")?,
+            restaurant_name: row.get("restaurant_name")?,
+            date,
+            menu_type: row.get("menu_type")?,
+            calorific_value: row.get("calorific_value")?,
+            content: row.get("content")?,
+            observation: row.get("observation")?,
+        })
+    }
+
+    /// Converte a entrada em um `Menu`, para que ela seja exibida como os cardápios da semana.
+    pub fn to_menu(&self) -> Menu {
+        Menu {
+            content: self.content.clone(),
+            menu_type: self.menu_type.clone(),
+            weekday: self.date.weekday(),
+            date: Some(self.date),
+            calorific_value: self.calorific_value,
+            observation: self.observation.clone(),
+        }
+    }
+}
+
+/// Filtros da consulta ao histórico.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct HistoryQuery {
+    /// Data inicial, inclusive.
+    pub from: Option<NaiveDate>,
+
+    /// Data final, inclusive.
+    pub to: Option<NaiveDate>,
+
+    /// Restaurantes consultados, ou todos se vazio.
+    pub restaurants: Vec<RestaurantID>,
+}
+
+/// Banco de dados com o histórico dos cardápios.
+pub struct History {
+    connection: Connection,
+}
+
+impl History {
+    /// Abre (ou cria) o histórico no arquivo `path`, criando os diretórios necessários.
+    pub fn open(path: &Path) -> rusqlite::Result<History> {
+        if let Some(parent) = path.parent() {
+            // Se o diretório não puder ser criado, o erro é informado ao abrir o banco
+            let _ = fs::create_dir_all(parent);
+        }
+        History::from_connection(Connection::open(path)?)
+    }
+
+    /// Cria um histórico em memória, que é perdido ao final do programa.
+    #[cfg(test)]
+    pub fn open_in_memory() -> rusqlite::Result<History> {
+        History::from_connection(Connection::open_in_memory()?)
+    }
+
+    /// Apaga as tabelas dos cardápios, para que as operações seguintes falhem.
+    #[cfg(test)]
+    pub fn drop_table(&self) -> rusqlite::Result<()> {
+        self.connection
+            .execute_batch("DROP TABLE menus; DROP TABLE published_menus;")
+    }
+
+    fn from_connection(connection: Connection) -> rusqlite::Result<History> {
+        connection.execute(CREATE_TABLE, [])?;
+
+        // Nos históricos criados antes da tabela das primeiras versões, as versões já guardadas
+        // são as mais antigas que existem
+        let has_published: bool = connection.query_row(
+            "SELECT EXISTS (SELECT 1 FROM sqlite_master WHERE type = 'table' AND name = 'published_menus')",
+            [],
+            |row| row.get(0),
+        )?;
+        if !has_published {
+            connection.execute(CREATE_PUBLISHED_TABLE, [])?;
+            connection.execute("INSERT INTO published_menus SELECT * FROM menus", [])?;
+        }
+
+        Ok(History { connection })
+    }
+
+    /// Guarda os cardápios `menus` do restaurante, retornando quantos foram guardados.
+    ///
+    /// Os cardápios já guardados são atualizados, mantendo a primeira versão de cada um (veja
+    /// [`History::find_published`]), e os cardápios sem data são ignorados.
+    pub fn save_menus(
+        &mut self,
+        restaurant_id: RestaurantID,
+        restaurant_name: &str,
+        menus: &[Menu],
+    ) -> rusqlite::Result<usize> {
+        let updated_at = Local::now().naive_local().to_string();
+        let transaction = self.connection.transaction()?;
+        let mut saved = 0;
+
+        {
+            let mut published = transaction.prepare(
+                "INSERT OR IGNORE INTO published_menus (restaurant_id, restaurant_name, date,
+                    menu_type, calorific_value, content, observation, updated_at)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+            )?;
+            let mut statement = transaction.prepare(
+                "INSERT INTO menus (restaurant_id, restaurant_name, date, menu_type,
+                    calorific_value, content, observation, updated_at)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)
+                 ON CONFLICT (restaurant_id, date, menu_type) DO UPDATE SET
+                    restaurant_name = excluded.restaurant_name,
+                    calorific_value = excluded.calorific_value,
+                    content = excluded.content,
+                    observation = excluded.observation,
+                    updated_at = excluded.updated_at",
+            )?;
+
+            for menu in menus {
+                let Some(date) = menu.date else {
+                    continue;
+                };
+
+                let values = params![
+                    restaurant_id,
+                    restaurant_name,
+                    date.format(DATE_FORMAT).to_string(),
+                    menu.menu_type,
+                    menu.calorific_value,
+                    menu.content,
+                    menu.observation,
+                    updated_at,
+                ];
+                published.execute(values)?;
+                saved += statement.execute(values)?;
+            }
+        }
+
+        transaction.commit()?;
+        Ok(saved)
+    }
+
+    /// Procura a primeira versão guardada do cardápio do restaurante na data e na refeição
+    /// escolhidas, que não muda quando o cardápio é carregado de novo.
+    pub fn find_published(
+        &self,
+        restaurant_id: RestaurantID,
+        date: NaiveDate,
+        menu_type: &MenuType,
+    ) -> rusqlite::Result<Option<HistoryEntry>> {
+        self.connection
+            .query_row(
+                "SELECT * FROM published_menus
+                 WHERE restaurant_id = ?1 AND date = ?2 AND menu_type = ?3",
+                params![
+                    restaurant_id,
+                    date.format(DATE_FORMAT).to_string(),
+                    menu_type,
+                ],
+                HistoryEntry::from_row,
+            )
+            .optional()
+    }
+
+    /// Consulta os cardápios guardados, ordenados pela data, pelo tipo da refeição (almoço
+    /// antes do jantar) e pelo restaurante.
+    pub fn query(&self, query: &HistoryQuery) -> rusqlite::Result<Vec<HistoryEntry>> {
+        let mut conditions = vec!["1 = 1".to_string()];
+        let mut values = Vec::new();
+
+        if let Some(from) = query.from {
+            conditions.push("date >= ?".to_string());
+            values.push(Value::Text(from.format(DATE_FORMAT).to_string()));
+        }
+        if let Some(to) = query.to {
+            conditions.push("date <= ?".to_string());
+            values.push(Value::Text(to.format(DATE_FORMAT).to_string()));
+        }
+        if !query.restaurants.is_empty() {
+            let placeholders = vec!["?"; query.restaurants.len()].join(", ");
+            conditions.push(format!("restaurant_id IN ({placeholders})"));
+            values.extend(
+                query
+                    .restaurants
+                    .iter()
+                    .map(|id| Value::Integer(*id as i64)),
+            );
+        }
+
+        let mut statement = self.connection.prepare(&format!(
+            "SELECT * FROM menus WHERE {}
+             ORDER BY date, menu_type = 'dinner', restaurant_id",
+            conditions.join(" AND ")
+        ))?;
+
+        let entries = statement
+            .query_map(params_from_iter(values), HistoryEntry::from_row)?
+            .collect();
+        entries
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Weekday;
+
+    fn menu(date: Option<NaiveDate>, menu_type: MenuType, content: &str) -> Menu {
+        Menu {
+            content: content.to_string(),
+            menu_type,
+            weekday: date.map_or(Weekday::Mon, |date| date.weekday()),
+            date,
+            calorific_value: Some(1030),
+            observation: String::new(),
+        }
+    }
+
+    fn date(day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(2025, 3, day).unwrap()
+    }
+
+    #[test]
+    fn test_history_save_and_query() {
+        let mut history = History::open_in_memory().unwrap();
+
+        let saved = history
+            .save_menus(
+                6,
+                "Restaurante Central",
+                &[
+                    menu(Some(date(4)), MenuType::Dinner, "Arroz\nFrango"),
+                    menu(Some(date(4)), MenuType::Lunch, "Arroz\nCarne"),
+                    menu(Some(date(5)), MenuType::Lunch, "Fechado"),
+                    menu(None, MenuType::Lunch, "Sem data"),
+                ],
+            )
+            .unwrap();
+        assert_eq!(saved, 3);
+        history
+            .save_menus(
+                8,
+                "Física",
+                &[menu(Some(date(4)), MenuType::Lunch, "Arroz")],
+            )
+            .unwrap();
+
+        let entries = history.query(&HistoryQuery::default()).unwrap();
+        let found: Vec<_> = entries
+            .iter()
+            .map(|entry| {
+                (
+                    entry.date.day(),
+                    entry.menu_type.clone(),
+                    entry.restaurant_id,
+                )
+            })
+            .collect();
+        assert_eq!(
+            found,
+            vec![
+                (4, MenuType::Lunch, 6),
+                (4, MenuType::Lunch, 8),
+                (4, MenuType::Dinner, 6),
+                (5, MenuType::Lunch, 6),
+            ]
+        );
+        assert_eq!(entries[0].to_menu().weekday, Weekday::Tue);
+        assert_eq!(entries[0].calorific_value, Some(1030));
+
+        let query = HistoryQuery {
+            from: Some(date(5)),
+            to: Some(date(5)),
+            restaurants: vec![6],
+        };
+        let entries = history.query(&query).unwrap();
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].content, "Fechado");
+
+        let query = HistoryQuery {
+            restaurants: vec![8, 9],
+            ..HistoryQuery::default()
+        };
+        assert_eq!(history.query(&query).unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_history_deduplicates() {
+        let mut history = History::open_in_memory().unwrap();
+
+        for content in ["Arroz\nCarne", "Arroz\nPeixe"] {
+            history
+                .save_menus(
+                    6,
+                    "Restaurante Central",
+                    &[menu(Some(date(4)), MenuType::Lunch, content)],
+                )
+                .unwrap();
+        }
+
+        let entries = history.query(&HistoryQuery::default()).unwrap();
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].content, "Arroz\nPeixe");
+    }
+
+    #[test]
+    fn test_history_find_published() {
+        let mut history = History::open_in_memory().unwrap();
+        for content in ["Arroz\nCarne", "Arroz\nPeixe", "Arroz\nFrango"] {
+            history
+                .save_menus(
+                    6,
+                    "Restaurante Central",
+                    &[menu(Some(date(4)), MenuType::Lunch, content)],
+                )
+                .unwrap();
+        }
+
+        // A primeira versão não muda quando o cardápio é carregado de novo
+        let entry = history
+            .find_published(6, date(4), &MenuType::Lunch)
+            .unwrap();
+        assert_eq!(entry.unwrap().content, "Arroz\nCarne");
+        let entries = history.query(&HistoryQuery::default()).unwrap();
+        assert_eq!(entries[0].content, "Arroz\nFrango");
+
+        assert_eq!(
+            history
+                .find_published(6, date(4), &MenuType::Dinner)
+                .unwrap(),
+            None
+        );
+        assert_eq!(
+            history
+                .find_published(8, date(4), &MenuType::Lunch)
+                .unwrap(),
+            None
+        );
+    }
+
+    #[test]
+    fn test_history_published_from_old_history() {
+        // Histórico criado antes da tabela das primeiras versões
+        let connection = Connection::open_in_memory().unwrap();
+        connection.execute(CREATE_TABLE, []).unwrap();
+        connection
+            .execute(
+                "INSERT INTO menus VALUES (6, 'Central', '2025-03-04', 'lunch', NULL,
+                    'Arroz\nCarne', '', '2025-03-03 10:00:00')",
+                [],
+            )
+            .unwrap();
+
+        let mut history = History::from_connection(connection).unwrap();
+        history
+            .save_menus(
+                6,
+                "Central",
+                &[menu(Some(date(4)), MenuType::Lunch, "Arroz\nPeixe")],
+            )
+            .unwrap();
+        let entry = history
+            .find_published(6, date(4), &MenuType::Lunch)
+            .unwrap();
+        assert_eq!(entry.unwrap().content, "Arroz\nCarne");
+    }
+
+    #[test]
+    fn test_history_menu_type() {
+        let mut history = History::open_in_memory().unwrap();
+        history
+            .save_menus(
+                6,
+                "Central",
+                &[menu(Some(date(4)), MenuType::Dinner, "Sopa")],
+            )
+            .unwrap();
+
+        let stored: String = history
+            .connection
+            .query_row("SELECT menu_type FROM menus", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(stored, "dinner");
+
+        history
+            .connection
+            .execute("UPDATE menus SET menu_type = 'breakfast'", [])
+            .unwrap();
+        let error = history.query(&HistoryQuery::default()).unwrap_err();
+        assert!(matches!(
+            error,
+            rusqlite::Error::FromSqlConversionFailure(..)
+        ));
+    }
+}
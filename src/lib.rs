@@ -0,0 +1,70 @@
+/*!
+Bandex: cardápios dos restaurantes da USP
+
+Esta biblioteca contém o que o CLI `bandex` usa para consultar os cardápios, para que outras
+ferramentas possam usar as mesmas partes:
+- [`parse_dwr`]: Cliente do sistema da USP, que carrega os nomes dos restaurantes e os
+  cardápios da semana ([`fetch_restaurant_name`] e [`fetch_menus`])
+- [`source`] e [`menus_file`]: Fontes dos cardápios ([`MenuSource`]), que permitem misturar
+  restaurantes do sistema da USP com outras fontes, como arquivos locais de cardápios
+- [`types`]: Os cardápios ([`Menu`]), os tipos de refeição ([`MenuType`]) e o cache dos
+  cardápios carregados ([`MenusCache`])
+- [`config`]: Leitura, validação e conversão dos arquivos de configuração ([`Config`])
+- [`feed`] e [`webhook`]: Os cardápios em um feed Atom e em mensagens de chat
+- [`history`], [`changes`] e [`stats`]: Histórico local dos cardápios, mudanças entre versões e
+  estatísticas
+- [`recommend`], [`search`], [`diet`] e [`text`]: Recomendação de onde comer, busca de pratos,
+  restrições alimentares e normalização dos textos
+- [`watch`] e [`bot_api`]: Notificações na área de trabalho e clientes das APIs de bots do
+  Telegram e do Matrix
+
+Os itens públicos dos módulos acima seguem o versionamento semântico do pacote. Os módulos
+`display`, `bot` e `server` (a saída no terminal, o bot e a API REST) são usados apenas pelo
+CLI, ficam fora da documentação e podem mudar a qualquer momento.
+
+# Exemplo
+
+```no_run
+use bandex::{MenuType, MenusCache};
+use chrono::Weekday;
+
+# async fn example() {
+let mut menus_cache = MenusCache::new();
+if let Some((name, menu)) = menus_cache
+    .get_name_and_menu(6, &MenuType::Lunch, Weekday::Mon)
+    .await
+{
+    println!("{name}: {}", menu.content);
+}
+# }
+```
+*/
+
+#[doc(hidden)]
+pub mod bot;
+pub mod bot_api;
+pub mod changes;
+pub mod config;
+pub mod diet;
+#[doc(hidden)]
+pub mod display;
+pub mod feed;
+pub mod history;
+pub mod menus_file;
+pub mod parse_dwr;
+pub mod recommend;
+mod request;
+pub mod search;
+#[doc(hidden)]
+pub mod server;
+pub mod source;
+pub mod stats;
+pub mod text;
+pub mod types;
+pub mod watch;
+pub mod webhook;
+
+pub use config::Config;
+pub use parse_dwr::{fetch_menus, fetch_restaurant_name};
+pub use source::{MenuSource, Sources};
+pub use types::{Menu, MenuType, MenusCache, RestaurantID};
@@ -1,40 +1,117 @@
 use std::io::Error;
 
-use cli::parse_cli;
-use config::Config;
-use display::Display;
+use bandex::{
+    config::{config_files, FoodMatch},
+    display::Display,
+    source::Sources,
+};
+use cli::{parse_cli, Command, ConfigCommand, ExportCommand};
 
 mod cli;
-mod config;
-mod display;
-mod parse_dwr;
-mod request;
-mod types;
+mod commands;
+mod wizard;
 
 /// Função que orquestra a execução do programa
 ///
 /// - Recebe dados enviados no CLI, como quais cardápios devem ser exibidos
-/// - Lê, se existir, o arquivo de configurações, que define que restaurantes devem ser exibidos
-/// - Mostra os dados dos cardápios considerando as configurações
+/// - Lê, se existirem, os arquivos de configurações, que definem que restaurantes devem ser exibidos
+///     - Os arquivos do sistema, do usuário, do projeto e do CLI são aplicados em camadas
+///     - Avisa sobre as entradas ignoradas dos arquivos e, no modo estrito, falha com elas
+/// - Executa o subcomando escolhido, se houver, como a recomendação de onde comer, ou mostra os
+///   dados dos cardápios considerando as configurações
 #[tokio::main]
 async fn main() -> Result<(), Error> {
-    let mut display = Display::new();
+    let mut options = parse_cli()?;
+    let config_files = config_files(options.config_filepath.clone(), options.config_format);
 
-    Display::show_logo(true);
+    if let Some(Command::Config { command }) = options
+        .command
+        .take_if(|command| matches!(command, Command::Config { .. }))
+    {
+        // As saídas do `config show`, do `config schema` e do `config convert` (sem `-o`)
+        // podem ser salvas em um arquivo, então a logo não é exibida
+        if !matches!(
+            command,
+            ConfigCommand::Show
+                | ConfigCommand::Schema
+                | ConfigCommand::Convert { output: None, .. }
+        ) {
+            Display::show_logo(true);
+        }
+        return commands::run_config_command(command, &options, &config_files).await;
+    }
 
-    let (menu_type, weekday, config_filepath) = parse_cli()?;
+    // O JSON do `stats --json` e o feed do `export feed` (sem `-o`) podem ser salvos em um
+    // arquivo, então a logo não é exibida
+    if !matches!(
+        options.command,
+        Some(Command::Stats { json: true, .. })
+            | Some(Command::Export {
+                command: ExportCommand::Feed { output: None }
+            })
+    ) {
+        Display::show_logo(true);
+    }
 
-    let config = match config_filepath {
-        None => Config::default(),
-        Some(filepath) => Config::from_file(filepath).unwrap_or_else(|message| {
-            Display::error_message(format!(
-                "Erro ao ler o arquivo de configurações: {}",
-                message
-            ));
-            Config::default()
-        }),
-    };
+    let config = commands::load_config(&config_files, options.strict_config).config;
+    let sources = Sources::from_config(&config);
 
-    display.show_menus(weekday, menu_type, &config).await;
+    match options.command.take() {
+        Some(Command::Best) => {
+            commands::best(&options, &config, &sources).await;
+            return Ok(());
+        }
+        Some(Command::Search {
+            term,
+            word,
+            regex,
+            all_restaurants,
+        }) => {
+            let match_kind = match (word, regex) {
+                (true, _) => FoodMatch::Word,
+                (_, true) => FoodMatch::Regex,
+                _ => FoodMatch::Contains,
+            };
+            return commands::search(&config, &sources, term, match_kind, all_restaurants).await;
+        }
+        Some(Command::History {
+            from,
+            to,
+            restaurants,
+        }) => return commands::history(&config, from, to, restaurants),
+        Some(Command::Watch { before, refresh }) => {
+            return commands::watch(&config, &sources, before, refresh).await
+        }
+        Some(Command::Export {
+            command: ExportCommand::Feed { output },
+        }) => return commands::export_feed(&config, &sources, output).await,
+        Some(Command::Serve { port, host, ttl }) => {
+            return commands::serve(&config, &sources, host, port, ttl).await
+        }
+        Some(Command::Post { webhook, format }) => {
+            return commands::post(&options, &config, &sources, webhook, format).await
+        }
+        Some(Command::Bot {
+            platform,
+            api_url,
+            token,
+        }) => return commands::bot(&config, &sources, platform, api_url, token).await,
+        Some(Command::Diff { restaurants }) => {
+            return commands::diff(&config, &sources, restaurants).await
+        }
+        Some(Command::Stats {
+            from,
+            to,
+            restaurants,
+            top,
+            json,
+        }) => return commands::stats(&config, from, to, restaurants, top, json),
+        _ => {}
+    }
+
+    let mut display = Display::new(commands::menus_cache(&sources)).with_changes(options.changes);
+    display
+        .show_menus(options.weekday, options.menu_type, options.sort, &config)
+        .await;
     Ok(())
 }
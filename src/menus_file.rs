@@ -0,0 +1,167 @@
+/*!
+Arquivos locais de cardápios
+
+Restaurantes fora do sistema da USP (como a lanchonete do departamento) podem ter os cardápios
+escritos à mão em um arquivo YAML, TOML ou JSON, no mesmo formato dos cardápios do Bandex
+([`Menu`]). Nas configurações, esses restaurantes usam `source: file` e o caminho do arquivo
+(`path`), relativo ao arquivo de configurações:
+
+```yaml
+# cafe.yaml
+name: Café do Departamento
+menus:
+  - weekday: 1           # segunda-feira
+    menu_type: lunch     # lunch ou dinner
+    content: |
+      Pão de queijo
+      Café
+    calorific_value: 450 # opcional
+    date: 2025-03-03     # opcional
+    observation: Apenas no intervalo  # opcional
+```
+
+Os erros do arquivo são informados como os erros das configurações, com o arquivo, a linha, a
+coluna e a chave com problema.
+*/
+
+use std::{
+    fs, io,
+    path::{Path, PathBuf},
+};
+
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    config::{ConfigDiagnostic, ConfigFormat},
+    source::MenuSource,
+    types::{Menu, RestaurantID},
+};
+
+/// Conteúdo de um arquivo de cardápios: o nome do restaurante e os cardápios da semana.
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct MenusFile {
+    /// Nome do restaurante
+    pub name: String,
+
+    /// Cardápios da semana
+    #[serde(default)]
+    pub menus: Vec<Menu>,
+}
+
+impl MenusFile {
+    /// Lê o arquivo de cardápios `contents`, no formato `format`.
+    pub fn parse(contents: &str, format: ConfigFormat) -> Result<MenusFile, ConfigDiagnostic> {
+        let menus_file: MenusFile = format.parse(contents)?;
+
+        if menus_file.name.trim().is_empty() {
+            return Err(ConfigDiagnostic {
+                key: Some("name".to_string()),
+                ..ConfigDiagnostic::new("o nome do restaurante está vazio")
+            });
+        }
+
+        Ok(menus_file)
+    }
+
+    /// Lê o arquivo de cardápios `path`, com o formato detectado pela extensão (por padrão, YAML).
+    pub fn from_file(path: &Path) -> Result<MenusFile, ConfigDiagnostic> {
+        let format = ConfigFormat::from_path(path).unwrap_or_default();
+
+        fs::read_to_string(path)
+            .map_err(|error| ConfigDiagnostic::new(error.to_string()))
+            .and_then(|contents| MenusFile::parse(&contents, format))
+            .map_err(|diagnostic| ConfigDiagnostic {
+                file: Some(path.to_path_buf()),
+                ..diagnostic
+            })
+    }
+}
+
+/// Fonte dos cardápios de um restaurante por um arquivo local, lido a cada carregamento.
+pub struct FileSource {
+    path: PathBuf,
+}
+
+impl FileSource {
+    /// Cria a fonte do arquivo de cardápios `path`.
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        FileSource { path: path.into() }
+    }
+}
+
+#[async_trait]
+impl MenuSource for FileSource {
+    /// O arquivo não define o ID do restaurante, que vem das configurações.
+    fn restaurants(&self) -> Vec<RestaurantID> {
+        Vec::new()
+    }
+
+    async fn restaurant_name(&self, _: RestaurantID) -> io::Result<String> {
+        Ok(MenusFile::from_file(&self.path)?.name)
+    }
+
+    async fn menus(&self, _: RestaurantID) -> io::Result<Vec<Menu>> {
+        Ok(MenusFile::from_file(&self.path)?.menus)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::MenuType;
+    use chrono::{NaiveDate, Weekday};
+
+    #[test]
+    fn test_menus_file_parse() {
+        let menus_file = MenusFile::parse(
+            "name: Café do Departamento\nmenus:\n  - weekday: 5\n    menu_type: dinner\n    content: Pizza\n    date: 2025-03-07",
+            ConfigFormat::Yaml,
+        )
+        .unwrap();
+        assert_eq!(menus_file.name, "Café do Departamento");
+        let menu = &menus_file.menus[0];
+        assert_eq!(menu.weekday, Weekday::Fri);
+        assert_eq!(menu.menu_type, MenuType::Dinner);
+        assert_eq!(menu.date, NaiveDate::from_ymd_opt(2025, 3, 7));
+        assert!(menu.calorific_value.is_none());
+        assert_eq!(menu.observation, "");
+
+        let error = MenusFile::parse(
+            "name: Café\nmenus:\n  - weekday: 8\n    menu_type: lunch\n    content: Pão",
+            ConfigFormat::Yaml,
+        )
+        .unwrap_err();
+        assert_eq!(error.key.as_deref(), Some("menus[0].weekday"));
+        assert_eq!(error.location, Some((3, 5)));
+
+        let error = MenusFile::parse(
+            r#"{"name": "Café", "menus": [{"weekday": 1, "menu_type": "breakfast", "content": ""}]}"#,
+            ConfigFormat::Json,
+        )
+        .unwrap_err();
+        assert_eq!(error.key.as_deref(), Some("menus[0].menu_type"));
+
+        let error = MenusFile::parse("name: ' '", ConfigFormat::Yaml).unwrap_err();
+        assert_eq!(error.key.as_deref(), Some("name"));
+    }
+
+    #[tokio::test]
+    async fn test_file_source() {
+        let source = FileSource::new("configs-examples/cafe-menus.yaml");
+        assert_eq!(
+            source.restaurant_name(100).await.unwrap(),
+            "Café do Departamento"
+        );
+        let menus = source.menus(100).await.unwrap();
+        assert_eq!(menus.len(), 5);
+        assert!(menus.iter().all(|menu| menu.menu_type == MenuType::Lunch));
+
+        let error = FileSource::new("configs-examples/nao-existe.yaml")
+            .menus(100)
+            .await
+            .unwrap_err();
+        assert!(error.to_string().contains("nao-existe.yaml"));
+    }
+}
@@ -31,10 +31,15 @@ Todas as colunas de dados então presentes nas duas funções (R e C), mas algum
 - `vlrclorfi`: Calor calórico da refeição
 */
 
-use crate::types::{Menu, MenuType, RestaurantID};
-use chrono::Weekday;
+use crate::{
+    source::MenuSource,
+    types::{Menu, MenuType, RestaurantID, CAMPUSES},
+};
+use async_trait::async_trait;
+use chrono::{NaiveDate, Weekday};
 use html_escape::decode_html_entities;
 use regex::Regex;
+use std::io;
 use unescape::unescape;
 
 trait FromDWR<T = Self> {
@@ -62,6 +67,14 @@ impl FromDWR for Weekday {
     }
 }
 
+impl FromDWR for NaiveDate {
+    fn from_dwr(value: &str) -> Option<NaiveDate> {
+        // No DWR, a data é escrita como "06\/03\/2025" (dia, mês e ano)
+        let value = value.trim_matches('"').replace("\\/", "/");
+        NaiveDate::parse_from_str(&value, "%d/%m/%Y").ok()
+    }
+}
+
 impl FromDWR for Menu {
     fn from_dwr(object: &str) -> Option<Menu> {
         let content = get_value_in_dwr_object(object, KEY_MENU)?;
@@ -71,6 +84,8 @@ impl FromDWR for Menu {
         let menu_type = MenuType::from_dwr(menu_type.as_str())?;
         let weekday = get_value_in_dwr_object(object, KEY_WEEKDAY_MENU)?;
         let weekday = Weekday::from_dwr(weekday.as_str())?;
+        let date = get_value_in_dwr_object(object, KEY_DATE_MENU)
+            .and_then(|date| NaiveDate::from_dwr(date.as_str()));
 
         let calorific_value = get_value_in_dwr_object(object, KEY_CALORIFIC_VALUE)?;
         let calorific_value = calorific_value.parse::<usize>().ok()?;
@@ -83,6 +98,7 @@ impl FromDWR for Menu {
             content,
             menu_type,
             weekday,
+            date,
             calorific_value,
             observation,
         })
@@ -98,6 +114,9 @@ const KEY_MENU: &str = "cdpdia";
 /// Chave do objeto DWR: Dia da semana da refeição (Domingo: 1, Segunda: 2, ..., Sabado: 7)
 const KEY_WEEKDAY_MENU: &str = "diasemana";
 
+/// Chave do objeto DWR: Data da refeição (dia, mês e ano)
+const KEY_DATE_MENU: &str = "dtarfi";
+
 /// Chave do objeto DWR: Observações do refeição
 const KEY_OBS_MENU: &str = "obscdpsmn";
 
@@ -108,7 +127,7 @@ const KEY_MENU_TYPE: &str = "tiprfi";
 const KEY_CALORIFIC_VALUE: &str = "vlrclorfi";
 
 /// Corta apenas o objeto dwr, começando com `[{` e terminando com `}]`
-fn slice_dwr_objects(body: &String) -> Option<&str> {
+fn slice_dwr_objects(body: &str) -> Option<&str> {
     let start_object = body.find("[{");
     let end_object = body.rfind("}]");
 
@@ -137,26 +156,16 @@ fn get_value_in_dwr_object(object: &str, key: &str) -> Option<String> {
     Some(String::from(&capture["value"]))
 }
 
-/// Extrai o nome do restaurante usando o código do restaurante
-pub async fn get_restaurant_name(restaurant_id: RestaurantID) -> Option<String> {
-    let Ok(response) = crate::request::request_rest_name(restaurant_id).await else {
-        return None;
-    };
-
-    let object = slice_dwr_objects(&response)?;
-    let name = get_value_in_dwr_object(&object, KEY_NAME_RESTAURANT)?;
-    let name = format_text_dwr_value(name)?;
-
-    Some(name)
+/// Extrai o nome do restaurante da resposta do `obterRestauranteUsp`
+fn parse_restaurant_name(response: &str) -> Option<String> {
+    let object = slice_dwr_objects(response)?;
+    let name = get_value_in_dwr_object(object, KEY_NAME_RESTAURANT)?;
+    format_text_dwr_value(name)
 }
 
-/// Extrai os cardápios da semana usando o código de um restaurante
-pub async fn get_menus(restaurant_id: RestaurantID) -> Option<Vec<Menu>> {
-    let Ok(response) = crate::request::request_menu(restaurant_id).await else {
-        return None;
-    };
-
-    let objects = slice_dwr_objects(&response)?;
+/// Extrai os cardápios da semana da resposta do `obterCardapioRestUSP`
+fn parse_menus(response: &str) -> Option<Vec<Menu>> {
+    let objects = slice_dwr_objects(response)?;
 
     let mut menus: Vec<Menu> = Vec::new();
 
@@ -170,6 +179,63 @@ pub async fn get_menus(restaurant_id: RestaurantID) -> Option<Vec<Menu>> {
     Some(menus)
 }
 
+/// Erro de uma resposta do DWR que não pôde ser lida
+fn invalid_response(restaurant_id: RestaurantID) -> io::Error {
+    io::Error::new(
+        io::ErrorKind::InvalidData,
+        format!("Resposta inesperada do sistema da USP para o restaurante {restaurant_id}"),
+    )
+}
+
+/// Carrega o nome do restaurante usando o código do restaurante
+pub async fn fetch_restaurant_name(restaurant_id: RestaurantID) -> io::Result<String> {
+    let response = crate::request::request_rest_name(restaurant_id)
+        .await
+        .map_err(io::Error::other)?;
+
+    parse_restaurant_name(&response).ok_or_else(|| invalid_response(restaurant_id))
+}
+
+/// Carrega os cardápios da semana usando o código de um restaurante
+pub async fn fetch_menus(restaurant_id: RestaurantID) -> io::Result<Vec<Menu>> {
+    let response = crate::request::request_menu(restaurant_id)
+        .await
+        .map_err(io::Error::other)?;
+
+    parse_menus(&response).ok_or_else(|| invalid_response(restaurant_id))
+}
+
+/// Extrai o nome do restaurante usando o código do restaurante
+pub async fn get_restaurant_name(restaurant_id: RestaurantID) -> Option<String> {
+    fetch_restaurant_name(restaurant_id).await.ok()
+}
+
+/// Extrai os cardápios da semana usando o código de um restaurante
+pub async fn get_menus(restaurant_id: RestaurantID) -> Option<Vec<Menu>> {
+    fetch_menus(restaurant_id).await.ok()
+}
+
+/// Fonte dos cardápios pelo sistema da USP, com os restaurantes dos campi conhecidos.
+pub struct DwrSource;
+
+#[async_trait]
+impl MenuSource for DwrSource {
+    fn restaurants(&self) -> Vec<RestaurantID> {
+        CAMPUSES
+            .iter()
+            .flat_map(|(_, ids)| ids.iter().copied())
+            .collect()
+    }
+
+    async fn restaurant_name(&self, restaurant_id: RestaurantID) -> io::Result<String> {
+        fetch_restaurant_name(restaurant_id).await
+    }
+
+    async fn menus(&self, restaurant_id: RestaurantID) -> io::Result<Vec<Menu>> {
+        fetch_menus(restaurant_id).await
+    }
+}
+
 #[cfg(test)]
 mod tests {
     /// ID do restaurante Central
@@ -283,6 +349,15 @@ mod tests {
         assert!(weekday.is_none());
     }
 
+    #[test]
+    fn test_date_from_dwr() {
+        let date = NaiveDate::from_dwr(r#""09\/03\/2025""#);
+        assert_eq!(date, NaiveDate::from_ymd_opt(2025, 3, 9));
+
+        let date = NaiveDate::from_dwr("null");
+        assert!(date.is_none());
+    }
+
     #[test]
     fn test_menu_from_dwr() {
         let menu = Menu::from_dwr(
@@ -293,6 +368,7 @@ mod tests {
         assert!(menu.content.starts_with("Arroz, feijão"));
         assert_eq!(menu.menu_type, MenuType::Lunch);
         assert_eq!(menu.weekday, Weekday::Thu);
+        assert_eq!(menu.date, NaiveDate::from_ymd_opt(2025, 3, 6));
         assert_eq!(menu.calorific_value.unwrap(), 1030);
         assert!(menu
             .observation
@@ -0,0 +1,269 @@
+/*!
+Recomendação de onde comer
+
+Este módulo escolhe, dentre os restaurantes configurados, onde comer em uma refeição:
+- Os restaurantes fechados não são recomendados
+- Os restaurantes sem prato principal compatível com as restrições alimentares ficam por último
+- Os restaurantes são ordenados pela nota do cardápio, calculada pelos pesos das comidas
+  gostadas e não gostadas
+- Em caso de empate, vale a ordem dos restaurantes no arquivo de configuração
+- A recomendação vem com o motivo da escolha, como "tem pudim, sem pvt"
+*/
+
+use chrono::Weekday;
+
+use crate::{
+    config::{Config, FoodList},
+    diet::DietChecker,
+    types::{Menu, MenuType, MenusCache, RestaurantID},
+};
+
+/// Restaurante aberto que pode ser recomendado, com as comidas encontradas no seu cardápio.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Candidate {
+    /// ID do restaurante.
+    pub restaurant_id: RestaurantID,
+
+    /// Nome do restaurante.
+    pub name: String,
+
+    /// Nota do cardápio (veja [`Config::menu_score`]).
+    pub score: i32,
+
+    /// Comidas gostadas encontradas no cardápio.
+    pub liked: Vec<String>,
+
+    /// Comidas não gostadas encontradas no cardápio.
+    pub disliked: Vec<String>,
+
+    /// Se o cardápio tem algum prato principal compatível com as restrições alimentares.
+    pub compatible: bool,
+}
+
+impl Candidate {
+    /// Cria um candidato a partir do cardápio do restaurante, ou `None` se ele estiver fechado.
+    pub fn from_menu(
+        config: &Config,
+        restaurant_id: RestaurantID,
+        name: String,
+        menu: &Menu,
+    ) -> Option<Candidate> {
+        let score = config.menu_score(&menu.content, restaurant_id)?;
+        let found_foods = |list| {
+            config
+                .found_foods(list, &menu.content, restaurant_id)
+                .into_iter()
+                .map(|food| food.name.clone())
+                .collect()
+        };
+
+        let compatible = DietChecker::new(config).is_none_or(|checker| {
+            checker
+                .check_menu(&menu.content, restaurant_id)
+                .has_compatible_main()
+        });
+
+        Some(Candidate {
+            restaurant_id,
+            name,
+            score,
+            liked: found_foods(FoodList::Liked),
+            disliked: found_foods(FoodList::Disliked),
+            compatible,
+        })
+    }
+}
+
+/// Ordena os candidatos da maior para a menor nota, mantendo a ordem original nos empates.
+///
+/// Os candidatos sem prato principal compatível com as restrições alimentares ficam por último.
+pub fn rank(mut candidates: Vec<Candidate>) -> Vec<Candidate> {
+    candidates.sort_by_key(|candidate| std::cmp::Reverse((candidate.compatible, candidate.score)));
+    candidates
+}
+
+/// Procura os candidatos a recomendação da refeição entre os restaurantes das configurações,
+/// já ordenados (veja [`rank`]).
+///
+/// Retorna também os restaurantes cujo cardápio da refeição não pôde ser carregado.
+pub async fn candidates(
+    menus_cache: &mut MenusCache,
+    config: &Config,
+    weekday: Weekday,
+    menu_type: &MenuType,
+) -> (Vec<Candidate>, Vec<RestaurantID>) {
+    let mut candidates = Vec::new();
+    let mut unavailable = Vec::new();
+    for restaurant in &config.restaurants {
+        match menus_cache
+            .get_name_and_menu(restaurant.id, menu_type, weekday)
+            .await
+        {
+            Some((name, menu)) => {
+                candidates.extend(Candidate::from_menu(config, restaurant.id, name, &menu))
+            }
+            None => unavailable.push(restaurant.id),
+        }
+    }
+    (rank(candidates), unavailable)
+}
+
+/// Junta os itens de uma lista em português ("a", "a e b", "a, b e c").
+fn join_list(items: &[String]) -> String {
+    match items {
+        [] => String::new(),
+        [item] => item.clone(),
+        [init @ .., last] => format!("{} e {}", init.join(", "), last),
+    }
+}
+
+/// Explica por que o candidato `best` foi escolhido em vez dos demais (`others`).
+///
+/// São citadas as comidas gostadas do cardápio, as comidas não gostadas que aparecem nos
+/// outros restaurantes, mas não nele, as comidas não gostadas do próprio cardápio e as
+/// restrições alimentares, quando elas fazem diferença.
+pub fn reason(best: &Candidate, others: &[Candidate]) -> String {
+    let mut avoided: Vec<String> = Vec::new();
+    for food in others.iter().flat_map(|other| &other.disliked) {
+        if !best.disliked.contains(food) && !avoided.contains(food) {
+            avoided.push(food.clone());
+        }
+    }
+
+    let mut parts = Vec::new();
+    if best.compatible && others.iter().any(|other| !other.compatible) {
+        parts.push("tem prato compatível com a dieta".to_string());
+    }
+    if !best.liked.is_empty() {
+        parts.push(format!("tem {}", join_list(&best.liked)));
+    }
+    if !avoided.is_empty() {
+        parts.push(format!("sem {}", join_list(&avoided)));
+    }
+
+    let reason = if parts.is_empty() {
+        "nenhuma comida configurada faz diferença".to_string()
+    } else {
+        parts.join(", ")
+    };
+
+    let reason = if best.disliked.is_empty() {
+        reason
+    } else {
+        format!("{reason}, mas tem {}", join_list(&best.disliked))
+    };
+
+    if best.compatible {
+        reason
+    } else {
+        format!("{reason} (nenhum prato principal compatível com a dieta)")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        config::{FoodConfig, RestaurantConfig},
+        menus_file::FileSource,
+        source::Sources,
+    };
+    use colored::Color;
+    use std::sync::Arc;
+
+    fn candidate(name: &str, score: i32, liked: &[&str], disliked: &[&str]) -> Candidate {
+        Candidate {
+            restaurant_id: 0,
+            name: name.to_string(),
+            score,
+            liked: liked.iter().map(|food| food.to_string()).collect(),
+            disliked: disliked.iter().map(|food| food.to_string()).collect(),
+            compatible: true,
+        }
+    }
+
+    #[test]
+    fn test_rank() {
+        let ranked = rank(vec![
+            candidate("Física", 0, &[], &[]),
+            candidate("Central", 2, &["pudim"], &[]),
+            candidate("Química", 0, &[], &[]),
+            candidate("Prefeitura", -1, &[], &["pvt"]),
+        ]);
+        let names: Vec<_> = ranked.iter().map(|candidate| &candidate.name[..]).collect();
+        assert_eq!(names, vec!["Central", "Física", "Química", "Prefeitura"]);
+
+        let incompatible = Candidate {
+            compatible: false,
+            ..candidate("Central", 5, &["pudim"], &[])
+        };
+        let ranked = rank(vec![incompatible, candidate("Física", -1, &[], &["pvt"])]);
+        assert_eq!(ranked[0].name, "Física");
+        assert_eq!(
+            reason(&ranked[0], &ranked[1..]),
+            "tem prato compatível com a dieta, mas tem pvt"
+        );
+        assert_eq!(
+            reason(&ranked[1], &[]),
+            "tem pudim (nenhum prato principal compatível com a dieta)"
+        );
+    }
+
+    #[test]
+    fn test_reason() {
+        let central = candidate("Central", 2, &["pudim"], &[]);
+        let fisica = candidate("Física", -1, &[], &["pvt"]);
+        let quimica = candidate("Química", -2, &[], &["pvt", "acelga"]);
+
+        assert_eq!(
+            reason(&central, &[fisica.clone(), quimica.clone()]),
+            "tem pudim, sem pvt e acelga"
+        );
+        assert_eq!(reason(&fisica, &[quimica]), "sem acelga, mas tem pvt");
+        assert_eq!(
+            reason(&candidate("Física", 0, &[], &[]), &[]),
+            "nenhuma comida configurada faz diferença"
+        );
+    }
+
+    #[test]
+    fn test_join_list() {
+        let items = ["pudim", "brigadeiro", "sorvete"].map(String::from);
+        assert_eq!(join_list(&items[..1]), "pudim");
+        assert_eq!(join_list(&items[..2]), "pudim e brigadeiro");
+        assert_eq!(join_list(&items), "pudim, brigadeiro e sorvete");
+    }
+
+    #[tokio::test]
+    async fn test_candidates() {
+        let menu = |content: &str| Menu {
+            content: content.to_string(),
+            menu_type: MenuType::Lunch,
+            weekday: Weekday::Mon,
+            date: None,
+            calorific_value: None,
+            observation: String::new(),
+        };
+        // O restaurante 9 não tem arquivo de cardápios, então não pode ser carregado
+        let sources = Sources::default().with_restaurant(9, Arc::new(FileSource::new("")));
+        let mut menus_cache = MenusCache::new().with_sources(sources);
+        menus_cache.insert(6, "Central", vec![menu("Arroz\nStrogonoff")]);
+        menus_cache.insert(8, "Física", vec![menu("Fechado")]);
+        menus_cache.insert(7, "Química", vec![menu("Arroz\nPudim")]);
+
+        let config = Config {
+            restaurants: [6, 8, 9, 7]
+                .map(|id| RestaurantConfig::new(id, Color::Blue))
+                .to_vec(),
+            liked_foods: vec![FoodConfig::new("pudim".to_string(), None)],
+            ..Config::default()
+        };
+
+        let (ranked, unavailable) =
+            candidates(&mut menus_cache, &config, Weekday::Mon, &MenuType::Lunch).await;
+        let names: Vec<_> = ranked.iter().map(|candidate| &candidate.name[..]).collect();
+        assert_eq!(names, vec!["Química", "Central"]);
+        assert_eq!(ranked[0].liked, vec!["pudim"]);
+        assert_eq!(unavailable, vec![9]);
+    }
+}
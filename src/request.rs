@@ -12,7 +12,6 @@ Todas as funções retornam o conteúdo da requisição ou um erro de requisiç
 */
 
 use crate::types::RestaurantID;
-use reqwest;
 
 /// URL para obter o nome do restaurante
 const GET_RESTAURANT_NAME_URL: &str = "https://uspdigital.usp.br/rucard/dwr/call/plaincall/CardapioControleDWR.obterRestauranteUsp.dwr";
@@ -47,7 +46,7 @@ pub async fn request_rest_name(restaurant_id: RestaurantID) -> Result<String, re
         .text()
         .await?;
 
-    return Ok(response);
+    Ok(response)
 }
 
 /// Função faz a requisição para obterCardapioRestUSP
@@ -77,7 +76,7 @@ pub async fn request_menu(restaurant_id: RestaurantID) -> Result<String, reqwest
         .text()
         .await?;
 
-    return Ok(response);
+    Ok(response)
 }
 
 #[cfg(test)]
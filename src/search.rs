@@ -0,0 +1,257 @@
+/*!
+Busca de pratos nos cardápios da semana
+
+Este módulo procura um prato em todos os cardápios da semana dos restaurantes, usando a
+mesma comparação das comidas das configurações (sem diferenciar maiúsculas, acentos e
+plural, ou por palavra inteira e expressão regular).
+
+Os resultados são ordenados pelo dia da semana, pela refeição (almoço antes do jantar) e
+pela ordem em que os restaurantes foram procurados.
+*/
+
+use chrono::Weekday;
+use colored::Color;
+
+use crate::{
+    config::{Config, FoodConfig, DEFAULT_COLOR},
+    types::{Menu, MenuType, MenusCache, RestaurantID},
+};
+
+/// Cardápios da semana de um restaurante, onde a busca é feita.
+#[derive(Debug, Clone)]
+pub struct RestaurantMenus {
+    /// ID do restaurante.
+    pub restaurant_id: RestaurantID,
+
+    /// Nome do restaurante.
+    pub name: String,
+
+    /// Cor do restaurante nas configurações.
+    pub color: Color,
+
+    /// Cardápios da semana.
+    pub menus: Vec<Menu>,
+}
+
+/// Linha de um cardápio em que o prato foi encontrado.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SearchHit {
+    /// Dia da semana do cardápio.
+    pub weekday: Weekday,
+
+    /// Refeição do cardápio.
+    pub menu_type: MenuType,
+
+    /// Nome do restaurante.
+    pub restaurant_name: String,
+
+    /// Cor do restaurante nas configurações.
+    pub color: Color,
+
+    /// Linha do cardápio em que o prato foi encontrado.
+    pub line: String,
+}
+
+/// Procura o prato `food` nos cardápios dos restaurantes `restaurants`.
+pub fn search_menus(food: &FoodConfig, restaurants: &[RestaurantMenus]) -> Vec<SearchHit> {
+    let mut hits: Vec<(usize, SearchHit)> = Vec::new();
+
+    for (index, restaurant) in restaurants.iter().enumerate() {
+        for menu in &restaurant.menus {
+            let lines = menu
+                .content
+                .split('\n')
+                .filter(|line| !line.is_empty())
+                .filter(|line| food.check_line(line, restaurant.restaurant_id as u64));
+
+            for line in lines {
+                hits.push((
+                    index,
+                    SearchHit {
+                        weekday: menu.weekday,
+                        menu_type: menu.menu_type.clone(),
+                        restaurant_name: restaurant.name.clone(),
+                        color: restaurant.color,
+                        line: line.trim().to_string(),
+                    },
+                ));
+            }
+        }
+    }
+
+    hits.sort_by_key(|(index, hit)| {
+        (
+            hit.weekday.num_days_from_monday(),
+            hit.menu_type == MenuType::Dinner,
+            *index,
+        )
+    });
+    hits.into_iter().map(|(_, hit)| hit).collect()
+}
+
+/// Procura o prato `food` nos cardápios da semana dos restaurantes das configurações e, com
+/// `all_restaurants`, também nos restaurantes conhecidos pelas fontes fora das configurações.
+///
+/// Retorna também os restaurantes das configurações cujos cardápios não puderam ser
+/// carregados; os restaurantes fora das configurações que não forem encontrados são ignorados.
+pub async fn search_restaurants(
+    menus_cache: &mut MenusCache,
+    config: &Config,
+    food: &FoodConfig,
+    all_restaurants: bool,
+) -> (Vec<SearchHit>, Vec<RestaurantID>) {
+    let mut restaurants: Vec<_> = config
+        .restaurants
+        .iter()
+        .map(|restaurant| (restaurant.id, restaurant.color, true))
+        .collect();
+    if all_restaurants {
+        for id in menus_cache.sources().known_restaurants() {
+            if !restaurants.iter().any(|(other, _, _)| *other == id) {
+                restaurants.push((id, DEFAULT_COLOR, false));
+            }
+        }
+    }
+
+    let mut restaurant_menus = Vec::new();
+    let mut unavailable = Vec::new();
+    for (restaurant_id, color, configured) in restaurants {
+        match menus_cache.get_name_and_menus(restaurant_id).await {
+            Some((name, menus)) => restaurant_menus.push(RestaurantMenus {
+                restaurant_id,
+                name,
+                color,
+                menus,
+            }),
+            None if !configured => {}
+            None => unavailable.push(restaurant_id),
+        }
+    }
+
+    (search_menus(food, &restaurant_menus), unavailable)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        config::{FoodMatch, RestaurantConfig},
+        menus_file::FileSource,
+        source::Sources,
+    };
+    use std::sync::Arc;
+
+    fn menu(weekday: Weekday, menu_type: MenuType, content: &str) -> Menu {
+        Menu {
+            content: content.to_string(),
+            menu_type,
+            weekday,
+            date: None,
+            calorific_value: None,
+            observation: String::new(),
+        }
+    }
+
+    fn restaurants() -> Vec<RestaurantMenus> {
+        vec![
+            RestaurantMenus {
+                restaurant_id: 8,
+                name: "Física".to_string(),
+                color: Color::Yellow,
+                menus: vec![
+                    menu(Weekday::Wed, MenuType::Lunch, "Arroz\nStrogonoff de frango"),
+                    menu(
+                        Weekday::Mon,
+                        MenuType::Dinner,
+                        "Arroz\nStrogonoffs de carne",
+                    ),
+                ],
+            },
+            RestaurantMenus {
+                restaurant_id: 6,
+                name: "Central".to_string(),
+                color: Color::White,
+                menus: vec![
+                    menu(Weekday::Mon, MenuType::Dinner, "Arroz\nStrogonoff de soja "),
+                    menu(Weekday::Mon, MenuType::Lunch, "Arroz\nFrango assado"),
+                    menu(Weekday::Tue, MenuType::Lunch, "Fechado"),
+                ],
+            },
+        ]
+    }
+
+    #[test]
+    fn test_search_menus() {
+        let food = FoodConfig::new("strogonoff".to_string(), None);
+        let hits = search_menus(&food, &restaurants());
+
+        let found: Vec<_> = hits
+            .iter()
+            .map(|hit| (hit.weekday, &hit.restaurant_name[..], &hit.line[..]))
+            .collect();
+        assert_eq!(
+            found,
+            vec![
+                (Weekday::Mon, "Física", "Strogonoffs de carne"),
+                (Weekday::Mon, "Central", "Strogonoff de soja"),
+                (Weekday::Wed, "Física", "Strogonoff de frango"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_search_menus_modifiers() {
+        let food = FoodConfig::new("frango".to_string(), Some(vec![6]));
+        let hits = search_menus(&food, &restaurants());
+        assert_eq!(hits.len(), 1);
+        assert_eq!(hits[0].menu_type, MenuType::Lunch);
+
+        let food = FoodConfig::new("^strogonoffs? de (soja|carne)".to_string(), None)
+            .with_modifiers(FoodMatch::Regex, vec![])
+            .unwrap();
+        assert_eq!(search_menus(&food, &restaurants()).len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_search_restaurants() {
+        // Nenhum restaurante tem arquivo de cardápios, então só os que estão no cache são carregados
+        let source = Arc::new(FileSource::new(""));
+        let sources = Sources::new(source.clone())
+            .with_restaurant(100, source.clone())
+            .with_restaurant(101, source);
+        let mut menus_cache = MenusCache::new().with_sources(sources);
+        for restaurant in restaurants() {
+            menus_cache.insert(restaurant.restaurant_id, &restaurant.name, restaurant.menus);
+        }
+        menus_cache.insert(
+            100,
+            "Café",
+            vec![menu(Weekday::Mon, MenuType::Lunch, "Strogonoff de palmito")],
+        );
+
+        let config = Config {
+            restaurants: vec![
+                RestaurantConfig::new(6, Color::White),
+                RestaurantConfig::new(9, Color::Blue),
+            ],
+            ..Config::default()
+        };
+        let food = FoodConfig::new("strogonoff".to_string(), None);
+
+        let (hits, unavailable) = search_restaurants(&mut menus_cache, &config, &food, false).await;
+        assert_eq!(hits.len(), 1);
+        assert_eq!(hits[0].restaurant_name, "Central");
+        assert_eq!(unavailable, vec![9]);
+
+        let (hits, unavailable) = search_restaurants(&mut menus_cache, &config, &food, true).await;
+        let found: Vec<_> = hits
+            .iter()
+            .map(|hit| (&hit.restaurant_name[..], hit.color))
+            .collect();
+        assert_eq!(
+            found,
+            vec![("Café", DEFAULT_COLOR), ("Central", Color::White)]
+        );
+        assert_eq!(unavailable, vec![9]);
+    }
+}
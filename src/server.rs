@@ -0,0 +1,473 @@
+/*!
+API REST dos cardápios (`bandex serve`)
+
+Este módulo responde às requisições da API HTTP local, para que outras ferramentas usem os
+cardápios sem fazer requisições ao sistema da USP:
+- `GET /restaurants`: Restaurantes das configurações, com os seus nomes
+- `GET /menus?date=&meal=&restaurant=`: Cardápios de um dia (por padrão, hoje), filtrados pela
+  refeição (`lunch` ou `dinner`) e pelo restaurante (por padrão, os das configurações)
+- `GET /menus/week?restaurant=`: Todos os cardápios da semana
+- `GET /feed.xml`: Feed Atom com os cardápios da semana (veja o módulo `feed`)
+
+As respostas (exceto o feed) são em JSON e os erros são respondidos como `{"error": "..."}`. Os cardápios vêm
+do `MenusCache`, então só são carregados de novo quando o tempo de validade acaba.
+*/
+
+use chrono::{DateTime, Datelike, FixedOffset, NaiveDate};
+use serde::Serialize;
+
+use crate::{
+    config::Config,
+    feed::{self, CONTENT_TYPE_ATOM},
+    types::{Menu, MenuType, MenusCache, RestaurantID},
+};
+
+/// Tipo do conteúdo das respostas em JSON.
+pub const CONTENT_TYPE_JSON: &str = "application/json; charset=utf-8";
+
+/// Resposta de uma requisição à API.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ApiResponse {
+    pub status: u16,
+    pub content_type: &'static str,
+    pub body: String,
+}
+
+impl ApiResponse {
+    /// Resposta com o valor `value` em JSON.
+    fn json<T: Serialize>(status: u16, value: &T) -> Self {
+        match serde_json::to_string(value) {
+            Ok(body) => ApiResponse {
+                status,
+                content_type: CONTENT_TYPE_JSON,
+                body,
+            },
+            Err(error) => ApiResponse::error(500, format!("Erro ao gerar o JSON: {error}")),
+        }
+    }
+
+    /// Resposta de erro, com a mensagem `message`.
+    fn error(status: u16, message: impl Into<String>) -> Self {
+        #[derive(Serialize)]
+        struct ErrorJson {
+            error: String,
+        }
+
+        ApiResponse::json(
+            status,
+            &ErrorJson {
+                error: message.into(),
+            },
+        )
+    }
+}
+
+/// Restaurante na resposta de `/restaurants`.
+#[derive(Debug, Serialize)]
+struct RestaurantJson {
+    id: RestaurantID,
+    name: String,
+}
+
+/// Cardápio nas respostas de `/menus` e `/menus/week`.
+#[derive(Debug, Serialize)]
+struct MenuJson {
+    restaurant_id: RestaurantID,
+    restaurant: String,
+    date: Option<NaiveDate>,
+
+    /// Dia da semana, de 1 (segunda) a 7 (domingo), como na ISO 8601.
+    weekday: u32,
+
+    /// Refeição ("lunch" ou "dinner").
+    meal: &'static str,
+    closed: bool,
+    dishes: Vec<String>,
+    calorific_value: Option<usize>,
+    observation: String,
+}
+
+impl MenuJson {
+    fn new(restaurant_id: RestaurantID, restaurant: &str, menu: &Menu) -> Self {
+        let closed = menu.content.trim() == "Fechado";
+        MenuJson {
+            restaurant_id,
+            restaurant: restaurant.to_string(),
+            date: menu.date,
+            weekday: menu.weekday.number_from_monday(),
+            meal: menu.menu_type.as_str(),
+            closed,
+            dishes: if closed {
+                Vec::new()
+            } else {
+                menu.content
+                    .split('\n')
+                    .map(str::trim)
+                    .filter(|line| !line.is_empty())
+                    .map(String::from)
+                    .collect()
+            },
+            calorific_value: menu.calorific_value,
+            observation: menu.observation.trim().to_string(),
+        }
+    }
+}
+
+/// Parâmetros da URL de uma requisição, como `date=2025-03-06&meal=lunch`, já decodificados
+/// (`%20` e `+` viram espaços, por exemplo).
+fn query_params(query: &str) -> Vec<(String, String)> {
+    form_urlencoded::parse(query.as_bytes())
+        .into_owned()
+        .collect()
+}
+
+/// Filtros de uma requisição de cardápios.
+#[derive(Debug, Default, PartialEq)]
+struct MenusFilter {
+    date: Option<NaiveDate>,
+    meal: Option<MenuType>,
+    restaurants: Vec<RestaurantID>,
+}
+
+impl MenusFilter {
+    fn parse(query: &str) -> Result<Self, String> {
+        let mut filter = MenusFilter::default();
+        for (key, value) in query_params(query) {
+            let value = value.as_str();
+            match key.as_str() {
+                "date" => {
+                    let date = NaiveDate::parse_from_str(value, "%Y-%m-%d").map_err(|_| {
+                        format!("Data \"{value}\" inválida, use o formato \"2025-03-06\"")
+                    })?;
+                    filter.date = Some(date);
+                }
+                "meal" => filter.meal = Some(value.parse()?),
+                "restaurant" => {
+                    let id = value
+                        .parse()
+                        .map_err(|_| format!("Restaurante \"{value}\" inválido"))?;
+                    filter.restaurants.push(id);
+                }
+                _ => return Err(format!("Parâmetro \"{key}\" desconhecido")),
+            }
+        }
+        Ok(filter)
+    }
+}
+
+/// API dos cardápios, que responde às requisições usando o cache dos cardápios.
+pub struct Api<'a> {
+    menus_cache: MenusCache,
+    config: &'a Config,
+}
+
+impl<'a> Api<'a> {
+    pub fn new(menus_cache: MenusCache, config: &'a Config) -> Self {
+        Api {
+            menus_cache,
+            config,
+        }
+    }
+
+    /// Responde à requisição `method` para `url` (com os parâmetros), feita em `now`.
+    pub async fn handle(
+        &mut self,
+        method: &str,
+        url: &str,
+        now: DateTime<FixedOffset>,
+    ) -> ApiResponse {
+        let today = now.date_naive();
+        let (path, query) = url.split_once('?').unwrap_or((url, ""));
+        let path = path.trim_end_matches('/');
+
+        if !matches!(
+            path,
+            "/restaurants" | "/menus" | "/menus/week" | "/feed.xml"
+        ) {
+            return ApiResponse::error(404, format!("Caminho \"{path}\" não encontrado"));
+        }
+        if method != "GET" {
+            return ApiResponse::error(405, format!("Método \"{method}\" não permitido"));
+        }
+
+        let filter = match MenusFilter::parse(query) {
+            Ok(filter) => filter,
+            Err(message) => return ApiResponse::error(400, message),
+        };
+
+        match path {
+            "/restaurants" => self.restaurants().await,
+            "/feed.xml" => self.feed(now).await,
+            "/menus/week" => self.menus(&filter, |_| true).await,
+            _ => {
+                let date = filter.date.unwrap_or(today);
+                self.menus(&filter, |menu| match menu.date {
+                    Some(menu_date) => menu_date == date,
+                    None => menu.weekday == date.weekday(),
+                })
+                .await
+            }
+        }
+    }
+
+    /// Feed Atom com os cardápios da semana dos restaurantes das configurações.
+    async fn feed(&mut self, now: DateTime<FixedOffset>) -> ApiResponse {
+        let entries = feed::feed_entries(&mut self.menus_cache, self.config).await;
+        ApiResponse {
+            status: 200,
+            content_type: CONTENT_TYPE_ATOM,
+            body: feed::atom_feed(&entries, now),
+        }
+    }
+
+    /// Restaurantes das configurações, com os nomes dos que foram carregados.
+    async fn restaurants(&mut self) -> ApiResponse {
+        let mut restaurants = Vec::new();
+        for restaurant in &self.config.restaurants {
+            if let Some((name, _)) = self.menus_cache.get_name_and_menus(restaurant.id).await {
+                restaurants.push(RestaurantJson {
+                    id: restaurant.id,
+                    name,
+                });
+            }
+        }
+        ApiResponse::json(200, &restaurants)
+    }
+
+    /// Cardápios dos restaurantes do filtro (ou das configurações) que passam por `keep`.
+    ///
+    /// Se um restaurante pedido no filtro não puder ser carregado, a resposta é um erro.
+    async fn menus(&mut self, filter: &MenusFilter, keep: impl Fn(&Menu) -> bool) -> ApiResponse {
+        let restaurants = if filter.restaurants.is_empty() {
+            self.config
+                .restaurants
+                .iter()
+                .map(|restaurant| restaurant.id)
+                .collect()
+        } else {
+            filter.restaurants.clone()
+        };
+
+        let mut menus = Vec::new();
+        for restaurant_id in restaurants {
+            let Some((name, restaurant_menus)) =
+                self.menus_cache.get_name_and_menus(restaurant_id).await
+            else {
+                if filter.restaurants.is_empty() {
+                    continue;
+                }
+                return ApiResponse::error(
+                    502,
+                    format!(
+                        "Não foi possível carregar os cardápios do restaurante {restaurant_id}"
+                    ),
+                );
+            };
+
+            menus.extend(
+                restaurant_menus
+                    .iter()
+                    .filter(|menu| keep(menu))
+                    .filter(|menu| {
+                        filter
+                            .meal
+                            .as_ref()
+                            .is_none_or(|meal| &menu.menu_type == meal)
+                    })
+                    .map(|menu| {
+                        (
+                            menu.weekday.num_days_from_monday(),
+                            menu.menu_type == MenuType::Dinner,
+                            MenuJson::new(restaurant_id, &name, menu),
+                        )
+                    }),
+            );
+        }
+
+        // Os cardápios são ordenados pelo dia e pela refeição, mantendo a ordem dos restaurantes
+        menus.sort_by_key(|(weekday, dinner, _)| (*weekday, *dinner));
+        let menus: Vec<MenuJson> = menus.into_iter().map(|(_, _, menu)| menu).collect();
+        ApiResponse::json(200, &menus)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::RestaurantConfig;
+    use chrono::{TimeZone, Weekday};
+    use colored::Color;
+    use serde_json::{json, Value};
+
+    /// Momento das requisições: 04/03/2025, uma terça-feira, às 10h30 em Brasília
+    fn now() -> DateTime<FixedOffset> {
+        FixedOffset::west_opt(3 * 3600)
+            .unwrap()
+            .with_ymd_and_hms(2025, 3, 4, 10, 30, 0)
+            .unwrap()
+    }
+
+    fn menu(day: u32, menu_type: MenuType, content: &str) -> Menu {
+        let date = NaiveDate::from_ymd_opt(2025, 3, day).unwrap();
+        Menu {
+            content: content.to_string(),
+            menu_type,
+            weekday: date.weekday(),
+            date: Some(date),
+            calorific_value: Some(1030),
+            observation: " Cardápio sujeito a modificação ".to_string(),
+        }
+    }
+
+    fn config() -> Config {
+        Config {
+            restaurants: vec![
+                RestaurantConfig::new(8, Color::Blue),
+                RestaurantConfig::new(6, Color::Blue),
+            ],
+            ..Config::default()
+        }
+    }
+
+    fn menus_cache() -> MenusCache {
+        let mut menus_cache = MenusCache::new();
+        menus_cache.insert(
+            8,
+            "Física",
+            vec![
+                menu(4, MenuType::Dinner, "Arroz\nPeixe"),
+                menu(4, MenuType::Lunch, "Fechado"),
+            ],
+        );
+        menus_cache.insert(
+            6,
+            "Central",
+            vec![
+                menu(3, MenuType::Lunch, "Arroz\nCarne"),
+                menu(4, MenuType::Lunch, "Arroz\n\nFrango "),
+            ],
+        );
+        menus_cache
+    }
+
+    async fn get(url: &str) -> (u16, Value) {
+        let config = config();
+        let mut api = Api::new(menus_cache(), &config);
+        let response = api.handle("GET", url, now()).await;
+        assert_eq!(response.content_type, CONTENT_TYPE_JSON);
+        (
+            response.status,
+            serde_json::from_str(&response.body).unwrap(),
+        )
+    }
+
+    #[tokio::test]
+    async fn test_api_restaurants() {
+        let (status, body) = get("/restaurants").await;
+        assert_eq!(status, 200);
+        assert_eq!(
+            body,
+            json!([{"id": 8, "name": "Física"}, {"id": 6, "name": "Central"}])
+        );
+    }
+
+    #[tokio::test]
+    async fn test_api_menus() {
+        let (status, body) = get("/menus").await;
+        assert_eq!(status, 200);
+        assert_eq!(
+            body,
+            json!([
+                {
+                    "restaurant_id": 8,
+                    "restaurant": "Física",
+                    "date": "2025-03-04",
+                    "weekday": 2,
+                    "meal": "lunch",
+                    "closed": true,
+                    "dishes": [],
+                    "calorific_value": 1030,
+                    "observation": "Cardápio sujeito a modificação"
+                },
+                {
+                    "restaurant_id": 6,
+                    "restaurant": "Central",
+                    "date": "2025-03-04",
+                    "weekday": 2,
+                    "meal": "lunch",
+                    "closed": false,
+                    "dishes": ["Arroz", "Frango"],
+                    "calorific_value": 1030,
+                    "observation": "Cardápio sujeito a modificação"
+                },
+                {
+                    "restaurant_id": 8,
+                    "restaurant": "Física",
+                    "date": "2025-03-04",
+                    "weekday": 2,
+                    "meal": "dinner",
+                    "closed": false,
+                    "dishes": ["Arroz", "Peixe"],
+                    "calorific_value": 1030,
+                    "observation": "Cardápio sujeito a modificação"
+                }
+            ])
+        );
+
+        let (_, body) = get("/menus?date=2025-03-03&meal=lunch&restaurant=6").await;
+        assert_eq!(body.as_array().unwrap().len(), 1);
+        assert_eq!(body[0]["dishes"], json!(["Arroz", "Carne"]));
+
+        let (_, body) = get("/menus?meal=dinner&restaurant=6").await;
+        assert_eq!(body, json!([]));
+
+        // Os parâmetros são decodificados antes de serem lidos
+        let (status, body) = get("/menus?%6Deal=%64inner&restaurant=%208").await;
+        assert_eq!(status, 400);
+        assert_eq!(body, json!({"error": "Restaurante \" 8\" inválido"}));
+        let (_, body) = get("/menus?date=2025%2D03%2D04&%6Deal=%64inner").await;
+        assert_eq!(body.as_array().unwrap().len(), 1);
+
+        let (_, body) = get("/menus/week/?restaurant=6").await;
+        let weekdays: Vec<_> = body
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|menu| menu["weekday"].as_u64().unwrap())
+            .collect();
+        assert_eq!(weekdays, vec![Weekday::Mon.number_from_monday() as u64, 2]);
+    }
+
+    #[tokio::test]
+    async fn test_api_errors() {
+        assert_eq!(get("/cardapios").await.0, 404);
+        assert_eq!(get("/menus?date=06/03/2025").await.0, 400);
+        assert_eq!(get("/menus?meal=breakfast").await.0, 400);
+        assert_eq!(get("/menus?restaurant=central").await.0, 400);
+
+        let (status, body) = get("/menus?foo=bar").await;
+        assert_eq!(status, 400);
+        assert_eq!(body, json!({"error": "Parâmetro \"foo\" desconhecido"}));
+
+        let config = config();
+        let mut api = Api::new(menus_cache(), &config);
+        assert_eq!(api.handle("POST", "/menus", now()).await.status, 405);
+    }
+
+    #[tokio::test]
+    async fn test_api_feed() {
+        let config = config();
+        let mut api = Api::new(menus_cache(), &config);
+        let response = api.handle("GET", "/feed.xml", now()).await;
+
+        assert_eq!(response.status, 200);
+        assert_eq!(response.content_type, CONTENT_TYPE_ATOM);
+        assert_eq!(response.body.matches("<entry>").count(), 4);
+        assert!(response
+            .body
+            .contains("<id>urn:bandex:menu:6:2025-03-03:lunch</id>"));
+        assert!(response
+            .body
+            .contains("  <updated>2025-03-04T10:30:00-03:00</updated>\n"));
+    }
+}
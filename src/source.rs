@@ -0,0 +1,239 @@
+/*!
+Fontes dos cardápios
+
+Este módulo define de onde os cardápios de cada restaurante são carregados:
+- [`MenuSource`] é a interface de uma fonte: os restaurantes que ela conhece, o nome de cada
+  restaurante e os cardápios da semana
+- O sistema da USP é a fonte padrão: os cardápios são carregados pelo DWR ([`DwrSource`])
+- Os restaurantes fora do sistema da USP podem ter os cardápios em um arquivo local
+  ([`FileSource`])
+- [`Sources`] escolhe a fonte de cada restaurante, de modo que restaurantes de fontes
+  diferentes podem ser misturados nas mesmas configurações (com a chave `source` de cada
+  restaurante)
+- Os IDs dos restaurantes precisam ser únicos entre todas as fontes, já que o cache, o
+  histórico e as configurações identificam os restaurantes apenas pelo ID: as configurações
+  com restaurantes repetidos ou com arquivos locais que usam os IDs dos restaurantes
+  do sistema da USP ([`SourceKind::reserved_by`]) são rejeitadas
+*/
+
+use std::{collections::HashMap, fmt, io, sync::Arc};
+
+use async_trait::async_trait;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    config::{Config, RestaurantConfig},
+    menus_file::FileSource,
+    parse_dwr::DwrSource,
+    types::{Menu, RestaurantID},
+};
+
+/// Fonte dos cardápios dos restaurantes.
+///
+/// Os erros seguem o tipo de [`io::ErrorKind`]: `NotFound` para os restaurantes desconhecidos,
+/// `InvalidData` para as respostas que não puderam ser lidas e os demais para as falhas de
+/// acesso à fonte.
+#[async_trait]
+pub trait MenuSource: Send + Sync {
+    /// IDs dos restaurantes conhecidos pela fonte, na ordem em que devem ser mostrados.
+    fn restaurants(&self) -> Vec<RestaurantID>;
+
+    /// Carrega o nome do restaurante.
+    async fn restaurant_name(&self, restaurant_id: RestaurantID) -> io::Result<String>;
+
+    /// Carrega os cardápios da semana do restaurante.
+    async fn menus(&self, restaurant_id: RestaurantID) -> io::Result<Vec<Menu>>;
+}
+
+/// Tipo da fonte de um restaurante nas configurações (`source`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Deserialize, Serialize, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum SourceKind {
+    /// Sistema dos restaurantes da USP.
+    #[default]
+    Usp,
+    /// Arquivo local de cardápios, no caminho `path`.
+    File,
+}
+
+impl SourceKind {
+    /// Verifica se é a fonte padrão, o sistema da USP.
+    pub fn is_default(&self) -> bool {
+        *self == SourceKind::default()
+    }
+
+    /// Fonte que já tem um restaurante com o ID `restaurant_id`: os restaurantes conhecidos do
+    /// sistema da USP.
+    pub fn reserved_by(restaurant_id: RestaurantID) -> Option<SourceKind> {
+        DwrSource
+            .restaurants()
+            .contains(&restaurant_id)
+            .then_some(SourceKind::Usp)
+    }
+
+    /// Cria a fonte do restaurante.
+    fn source(restaurant: &RestaurantConfig) -> Arc<dyn MenuSource> {
+        match restaurant.source {
+            SourceKind::Usp => Arc::new(DwrSource),
+            // Sem o arquivo, a fonte falha ao carregar os cardápios, e o erro já foi informado
+            // na leitura das configurações
+            SourceKind::File => {
+                Arc::new(FileSource::new(restaurant.path.clone().unwrap_or_default()))
+            }
+        }
+    }
+}
+
+impl fmt::Display for SourceKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            SourceKind::Usp => "usp",
+            SourceKind::File => "file",
+        })
+    }
+}
+
+/// Fonte de cada restaurante, com uma fonte padrão para os restaurantes sem fonte definida.
+#[derive(Clone)]
+pub struct Sources {
+    default: Arc<dyn MenuSource>,
+    restaurants: HashMap<RestaurantID, Arc<dyn MenuSource>>,
+}
+
+impl Default for Sources {
+    /// Fontes com o sistema da USP para todos os restaurantes.
+    fn default() -> Self {
+        Sources::new(Arc::new(DwrSource))
+    }
+}
+
+impl Sources {
+    /// Cria as fontes com `default` para todos os restaurantes.
+    pub fn new(default: Arc<dyn MenuSource>) -> Self {
+        Sources {
+            default,
+            restaurants: HashMap::new(),
+        }
+    }
+
+    /// Cria as fontes dos restaurantes das configurações, com o sistema da USP como padrão.
+    pub fn from_config(config: &Config) -> Self {
+        let mut sources = Sources::default();
+        for restaurant in &config.restaurants {
+            if !restaurant.source.is_default() {
+                let source = SourceKind::source(restaurant);
+                sources = sources.with_restaurant(restaurant.id, source);
+            }
+        }
+        sources
+    }
+
+    /// Define a fonte `source` para o restaurante `restaurant_id`.
+    pub fn with_restaurant(
+        mut self,
+        restaurant_id: RestaurantID,
+        source: Arc<dyn MenuSource>,
+    ) -> Self {
+        self.restaurants.insert(restaurant_id, source);
+        self
+    }
+
+    /// Fonte do restaurante `restaurant_id`.
+    pub fn get(&self, restaurant_id: RestaurantID) -> Arc<dyn MenuSource> {
+        self.restaurants
+            .get(&restaurant_id)
+            .unwrap_or(&self.default)
+            .clone()
+    }
+
+    /// IDs de todos os restaurantes conhecidos pelas fontes, sem repetições: primeiro os da
+    /// fonte padrão e depois os das demais fontes, em ordem crescente.
+    pub fn known_restaurants(&self) -> Vec<RestaurantID> {
+        let mut restaurants = self.default.restaurants();
+        let mut others: Vec<_> = self.restaurants.keys().copied().collect();
+        others.sort();
+        for id in others {
+            if !restaurants.contains(&id) {
+                restaurants.push(id);
+            }
+        }
+        restaurants
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::{MenuType, MenusCache};
+    use chrono::Weekday;
+    use colored::Color;
+
+    /// Fonte com um único restaurante, com cardápios fixos.
+    struct FixtureSource;
+
+    #[async_trait]
+    impl MenuSource for FixtureSource {
+        fn restaurants(&self) -> Vec<RestaurantID> {
+            vec![100]
+        }
+
+        async fn restaurant_name(&self, restaurant_id: RestaurantID) -> io::Result<String> {
+            match restaurant_id {
+                100 => Ok("Café do Departamento".to_string()),
+                _ => Err(io::Error::new(
+                    io::ErrorKind::NotFound,
+                    "restaurante desconhecido",
+                )),
+            }
+        }
+
+        async fn menus(&self, restaurant_id: RestaurantID) -> io::Result<Vec<Menu>> {
+            self.restaurant_name(restaurant_id).await?;
+            Ok(vec![Menu {
+                content: "Pão de queijo\nCafé".to_string(),
+                menu_type: MenuType::Lunch,
+                weekday: Weekday::Mon,
+                date: None,
+                calorific_value: None,
+                observation: String::new(),
+            }])
+        }
+    }
+
+    #[test]
+    fn test_sources() {
+        let sources = Sources::default().with_restaurant(100, Arc::new(FixtureSource));
+        assert_eq!(sources.get(100).restaurants(), vec![100]);
+        assert!(sources.get(6).restaurants().contains(&6));
+
+        let known = sources.known_restaurants();
+        assert!(known.contains(&6) && known.contains(&13));
+        assert_eq!(known.last(), Some(&100));
+
+        let mut restaurant = RestaurantConfig::new(8, Color::Blue);
+        restaurant.source = SourceKind::Usp;
+        let config = Config {
+            restaurants: vec![restaurant],
+            ..Config::default()
+        };
+        assert_eq!(
+            Sources::from_config(&config).known_restaurants(),
+            DwrSource.restaurants()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_menus_cache_with_sources() {
+        let sources = Sources::new(Arc::new(FixtureSource));
+        let mut menus_cache = MenusCache::new().with_sources(sources);
+
+        let (name, menu) = menus_cache
+            .get_name_and_menu(100, &MenuType::Lunch, Weekday::Mon)
+            .await
+            .unwrap();
+        assert_eq!(name, "Café do Departamento");
+        assert_eq!(menu.content, "Pão de queijo\nCafé");
+        assert!(menus_cache.get_name_and_menus(101).await.is_none());
+    }
+}
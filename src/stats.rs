@@ -0,0 +1,356 @@
+/*!
+Estatísticas do histórico dos cardápios
+
+Este módulo calcula, a partir dos cardápios guardados no histórico:
+- Os pratos mais frequentes de cada restaurante
+- Os dias em que cada restaurante esteve fechado
+- A média e o máximo do valor energético de cada tipo de refeição
+- Quantas vezes as comidas gostadas e não gostadas apareceram e há quantos dias apareceram
+  pela última vez ("dias desde o último pudim")
+
+As estatísticas podem ser exibidas em tabelas ou escritas em JSON.
+*/
+
+use std::collections::HashMap;
+
+use chrono::NaiveDate;
+use serde::Serialize;
+
+use crate::{
+    config::{Config, FoodList},
+    history::HistoryEntry,
+    text,
+    types::{MenuType, RestaurantID},
+};
+
+/// Quantidade de vezes que um prato apareceu nos cardápios.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct DishCount {
+    /// Prato, como foi escrito na primeira vez em que apareceu.
+    pub dish: String,
+
+    /// Quantidade de refeições em que o prato apareceu.
+    pub count: usize,
+}
+
+/// Estatísticas de um restaurante.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct RestaurantStats {
+    /// ID do restaurante.
+    pub restaurant_id: RestaurantID,
+
+    /// Nome do restaurante.
+    pub name: String,
+
+    /// Quantidade de refeições no histórico, incluindo as fechadas.
+    pub menus: usize,
+
+    /// Dias em que alguma refeição do restaurante estava fechada.
+    pub closed_days: Vec<NaiveDate>,
+
+    /// Pratos mais frequentes, do mais para o menos frequente.
+    pub top_dishes: Vec<DishCount>,
+}
+
+/// Valor energético de um tipo de refeição.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct CalorieStats {
+    /// Tipo da refeição, serializado como "lunch" ou "dinner".
+    pub menu_type: MenuType,
+
+    /// Quantidade de refeições com o valor energético informado.
+    pub menus: usize,
+
+    /// Média do valor energético, em kcal.
+    pub average: Option<f64>,
+
+    /// Maior valor energético, em kcal.
+    pub max: Option<usize>,
+}
+
+/// Frequência de uma comida das configurações.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct FoodStats {
+    /// Nome da comida nas configurações.
+    pub name: String,
+
+    /// Lista da comida ("liked" ou "disliked").
+    pub list: &'static str,
+
+    /// Quantidade de refeições em que a comida apareceu.
+    pub count: usize,
+
+    /// Última data em que a comida apareceu.
+    pub last_date: Option<NaiveDate>,
+
+    /// Dias desde a última vez em que a comida apareceu.
+    pub days_since: Option<i64>,
+}
+
+/// Estatísticas do histórico dos cardápios.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Stats {
+    /// Quantidade de refeições consideradas.
+    pub menus: usize,
+
+    /// Primeira data das refeições consideradas.
+    pub first_date: Option<NaiveDate>,
+
+    /// Última data das refeições consideradas.
+    pub last_date: Option<NaiveDate>,
+
+    /// Estatísticas de cada restaurante.
+    pub restaurants: Vec<RestaurantStats>,
+
+    /// Valor energético de cada tipo de refeição.
+    pub calories: Vec<CalorieStats>,
+
+    /// Frequência das comidas das configurações.
+    pub foods: Vec<FoodStats>,
+}
+
+/// Verifica se o cardápio é de um restaurante fechado.
+fn is_closed(entry: &HistoryEntry) -> bool {
+    entry.content.trim() == "Fechado"
+}
+
+/// Linhas de pratos do cardápio, sem as linhas vazias e os avisos ("**Os Restaurantes...**").
+fn dish_lines(content: &str) -> impl Iterator<Item = &str> {
+    content
+        .split('\n')
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('*'))
+}
+
+/// Calcula as estatísticas de um restaurante, com os `top` pratos mais frequentes.
+fn restaurant_stats(entries: &[&HistoryEntry], top: usize) -> RestaurantStats {
+    let mut closed_days: Vec<NaiveDate> = Vec::new();
+    let mut dishes: Vec<DishCount> = Vec::new();
+    let mut dish_index: HashMap<String, usize> = HashMap::new();
+
+    for entry in entries {
+        if is_closed(entry) {
+            if !closed_days.contains(&entry.date) {
+                closed_days.push(entry.date);
+            }
+            continue;
+        }
+
+        for line in dish_lines(&entry.content) {
+            let index = *dish_index.entry(text::normalize(line)).or_insert_with(|| {
+                dishes.push(DishCount {
+                    dish: line.to_string(),
+                    count: 0,
+                });
+                dishes.len() - 1
+            });
+            dishes[index].count += 1;
+        }
+    }
+
+    // A ordenação é estável, então os empates ficam na ordem em que apareceram
+    dishes.sort_by_key(|dish| std::cmp::Reverse(dish.count));
+    dishes.truncate(top);
+
+    let last = entries
+        .last()
+        .expect("o restaurante tem ao menos um cardápio");
+    RestaurantStats {
+        restaurant_id: last.restaurant_id,
+        name: last.restaurant_name.clone(),
+        menus: entries.len(),
+        closed_days,
+        top_dishes: dishes,
+    }
+}
+
+/// Calcula o valor energético médio e máximo de um tipo de refeição.
+fn calorie_stats(entries: &[HistoryEntry], menu_type: MenuType) -> CalorieStats {
+    let values: Vec<usize> = entries
+        .iter()
+        .filter(|entry| entry.menu_type == menu_type && !is_closed(entry))
+        .filter_map(|entry| entry.calorific_value)
+        .collect();
+
+    let average =
+        (!values.is_empty()).then(|| values.iter().sum::<usize>() as f64 / values.len() as f64);
+
+    CalorieStats {
+        menu_type,
+        menus: values.len(),
+        average,
+        max: values.iter().copied().max(),
+    }
+}
+
+/// Calcula as estatísticas dos cardápios do histórico (`entries`, ordenados pela data).
+///
+/// As comidas gostadas e não gostadas vêm das configurações (`config`) e os dias desde a
+/// última vez em que elas apareceram são contados até `today`. Para cada restaurante, são
+/// listados os `top` pratos mais frequentes.
+pub fn compute(entries: &[HistoryEntry], config: &Config, today: NaiveDate, top: usize) -> Stats {
+    let mut restaurant_ids: Vec<RestaurantID> = Vec::new();
+    for entry in entries {
+        if !restaurant_ids.contains(&entry.restaurant_id) {
+            restaurant_ids.push(entry.restaurant_id);
+        }
+    }
+
+    let restaurants = restaurant_ids
+        .into_iter()
+        .map(|restaurant_id| {
+            let entries: Vec<&HistoryEntry> = entries
+                .iter()
+                .filter(|entry| entry.restaurant_id == restaurant_id)
+                .collect();
+            restaurant_stats(&entries, top)
+        })
+        .collect();
+
+    let foods = [
+        (FoodList::Liked, &config.liked_foods),
+        (FoodList::Disliked, &config.disliked_foods),
+    ]
+    .into_iter()
+    .flat_map(|(list, foods)| foods.iter().map(move |food| (list, food)))
+    .map(|(list, food)| {
+        let dates: Vec<NaiveDate> = entries
+            .iter()
+            .filter(|entry| {
+                dish_lines(&entry.content)
+                    .any(|line| food.check_line(line, entry.restaurant_id as u64))
+            })
+            .map(|entry| entry.date)
+            .collect();
+        let last_date = dates.iter().max().copied();
+
+        FoodStats {
+            name: food.name.clone(),
+            list: list.key(),
+            count: dates.len(),
+            last_date,
+            days_since: last_date.map(|date| (today - date).num_days()),
+        }
+    })
+    .collect();
+
+    Stats {
+        menus: entries.len(),
+        first_date: entries.iter().map(|entry| entry.date).min(),
+        last_date: entries.iter().map(|entry| entry.date).max(),
+        restaurants,
+        calories: vec![
+            calorie_stats(entries, MenuType::Lunch),
+            calorie_stats(entries, MenuType::Dinner),
+        ],
+        foods,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::FoodConfig;
+
+    fn entry(
+        restaurant_id: RestaurantID,
+        day: u32,
+        menu_type: MenuType,
+        calorific_value: Option<usize>,
+        content: &str,
+    ) -> HistoryEntry {
+        HistoryEntry {
+            restaurant_id,
+            restaurant_name: format!("Rest {restaurant_id}"),
+            date: NaiveDate::from_ymd_opt(2025, 3, day).unwrap(),
+            menu_type,
+            calorific_value,
+            content: content.to_string(),
+            observation: String::new(),
+        }
+    }
+
+    fn entries() -> Vec<HistoryEntry> {
+        vec![
+            entry(
+                6,
+                3,
+                MenuType::Lunch,
+                Some(1000),
+                "Arroz\nPudim\n\n**Aviso**",
+            ),
+            entry(6, 3, MenuType::Dinner, Some(800), "Arroz\nSopa"),
+            entry(8, 3, MenuType::Lunch, Some(1200), "Arroz\nPVT"),
+            entry(6, 4, MenuType::Lunch, None, "Fechado"),
+            entry(6, 5, MenuType::Lunch, Some(900), "arroz\nPudins de leite"),
+        ]
+    }
+
+    #[test]
+    fn test_compute_restaurants_and_calories() {
+        let today = NaiveDate::from_ymd_opt(2025, 3, 10).unwrap();
+        let stats = compute(&entries(), &Config::default(), today, 2);
+
+        assert_eq!(stats.menus, 5);
+        assert_eq!(stats.first_date, NaiveDate::from_ymd_opt(2025, 3, 3));
+        assert_eq!(stats.last_date, NaiveDate::from_ymd_opt(2025, 3, 5));
+
+        let central = &stats.restaurants[0];
+        assert_eq!(central.restaurant_id, 6);
+        assert_eq!(central.menus, 4);
+        assert_eq!(
+            central.closed_days,
+            vec![NaiveDate::from_ymd_opt(2025, 3, 4).unwrap()]
+        );
+        assert_eq!(
+            central.top_dishes,
+            vec![
+                DishCount {
+                    dish: "Arroz".to_string(),
+                    count: 3
+                },
+                DishCount {
+                    dish: "Pudim".to_string(),
+                    count: 1
+                },
+            ]
+        );
+        assert_eq!(stats.restaurants[1].restaurant_id, 8);
+
+        let lunch = &stats.calories[0];
+        assert_eq!(lunch.menu_type, MenuType::Lunch);
+        assert_eq!(serde_json::to_value(lunch).unwrap()["menu_type"], "lunch");
+        assert_eq!(lunch.menus, 3);
+        assert_eq!(lunch.average, Some((1000.0 + 1200.0 + 900.0) / 3.0));
+        assert_eq!(lunch.max, Some(1200));
+        assert_eq!(stats.calories[1].average, Some(800.0));
+    }
+
+    #[test]
+    fn test_compute_foods() {
+        let config = Config {
+            liked_foods: vec![FoodConfig::new("pudim".to_string(), None)],
+            disliked_foods: vec![
+                FoodConfig::new("pvt".to_string(), None),
+                FoodConfig::new("acelga".to_string(), None),
+            ],
+            ..Config::default()
+        };
+        let today = NaiveDate::from_ymd_opt(2025, 3, 10).unwrap();
+        let stats = compute(&entries(), &config, today, 10);
+
+        let foods: Vec<_> = stats
+            .foods
+            .iter()
+            .map(|food| (&food.name[..], food.list, food.count, food.days_since))
+            .collect();
+        assert_eq!(
+            foods,
+            vec![
+                ("pudim", "liked", 2, Some(5)),
+                ("pvt", "disliked", 1, Some(7)),
+                ("acelga", "disliked", 0, None),
+            ]
+        );
+    }
+}
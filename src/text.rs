@@ -0,0 +1,111 @@
+/*!
+Normalização dos textos dos cardápios
+
+Este módulo contém as funções usadas para comparar os nomes das comidas com as linhas
+dos cardápios, sem depender da forma como eles foram escritos:
+- Letras maiúsculas e minúsculas são consideradas iguais
+- Os acentos e o "ç" são removidos ("Feijão" → "feijao")
+- As palavras no plural são levadas ao singular ("ovos" → "ovo", "pudins" → "pudim")
+- A pontuação é ignorada e as palavras são separadas por um único espaço
+*/
+
+/// Remove o acento de uma letra minúscula, retornando a própria letra se ela não tiver acento.
+fn fold_diacritic(c: char) -> char {
+    match c {
+        'á' | 'à' | 'â' | 'ã' | 'ä' => 'a',
+        'é' | 'è' | 'ê' | 'ë' => 'e',
+        'í' | 'ì' | 'î' | 'ï' => 'i',
+        'ó' | 'ò' | 'ô' | 'õ' | 'ö' => 'o',
+        'ú' | 'ù' | 'û' | 'ü' => 'u',
+        'ç' => 'c',
+        'ñ' => 'n',
+        _ => c,
+    }
+}
+
+/// Verifica se o caractere é um acento combinado com a letra anterior (Unicode NFD).
+fn is_combining_mark(c: char) -> bool {
+    ('\u{0300}'..='\u{036F}').contains(&c)
+}
+
+/// Terminações do plural e as terminações do singular correspondentes, já sem os acentos.
+///
+/// As regras são testadas em ordem, então as terminações mais específicas vêm primeiro.
+const PLURAL_SUFFIXES: [(&str, &str); 9] = [
+    ("oes", "ao"), // feijões → feijão
+    ("aes", "ao"), // pães → pão
+    ("ais", "al"), // integrais → integral
+    ("eis", "el"), // pastéis → pastel
+    ("ois", "ol"), // faróis → farol
+    ("res", "r"),  // flores → flor
+    ("zes", "z"),  // arrozes → arroz
+    ("ns", "m"),   // pudins → pudim
+    ("s", ""),     // ovos → ovo
+];
+
+/// Tamanho mínimo de uma palavra para que ela seja levada ao singular, de modo que palavras
+/// curtas, como "gás", não sejam alteradas.
+const MIN_PLURAL_LENGTH: usize = 4;
+
+/// Leva uma palavra normalizada (minúscula e sem acentos) ao singular.
+///
+/// As regras são simples e podem errar em algumas palavras, mas, como são aplicadas tanto
+/// aos nomes das comidas quanto aos cardápios, os dois lados continuam iguais.
+fn singular(word: &str) -> String {
+    if word.len() < MIN_PLURAL_LENGTH || word.chars().any(|c| c.is_ascii_digit()) {
+        return word.to_string();
+    }
+
+    PLURAL_SUFFIXES
+        .iter()
+        .find_map(|(plural, singular)| {
+            word.strip_suffix(plural)
+                .map(|stem| format!("{stem}{singular}"))
+        })
+        .unwrap_or_else(|| word.to_string())
+}
+
+/// Normaliza um texto para comparação: minúsculas, sem acentos, sem pontuação e com as
+/// palavras no singular, separadas por um espaço.
+pub fn normalize(text: &str) -> String {
+    let folded: String = text
+        .to_lowercase()
+        .chars()
+        .filter(|c| !is_combining_mark(*c))
+        .map(fold_diacritic)
+        .collect();
+
+    folded
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .map(singular)
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_normalize() {
+        assert_eq!(normalize("Feijão"), "feijao");
+        assert_eq!(normalize("  ARROZ/Feijão  preto "), "arroz feijao preto");
+        assert_eq!(normalize("Maçã"), "maca");
+        // Acentos combinados (NFD)
+        assert_eq!(normalize("feija\u{0303}o"), "feijao");
+    }
+
+    #[test]
+    fn test_normalize_plural() {
+        assert_eq!(normalize("Ovos"), "ovo");
+        assert_eq!(normalize("pudins"), "pudim");
+        assert_eq!(normalize("feijões"), normalize("feijão"));
+        assert_eq!(normalize("pães"), normalize("pão"));
+        assert_eq!(normalize("pastéis"), "pastel");
+        assert_eq!(normalize("legumes"), "legume");
+        assert_eq!(normalize("Arroz integral"), normalize("arrozes integrais"));
+        // Palavras curtas não são alteradas
+        assert_eq!(normalize("gás"), "gas");
+    }
+}
@@ -1,15 +1,36 @@
 /*!
 Algumas objetos para funcionamento do aplicativo
+
+Os cardápios e os tipos de refeição podem ser serializados (com serde), com as datas no formato
+ISO 8601 ("2025-03-06") e os dias da semana como números ISO (segunda: 1, ..., domingo: 7).
 */
-use crate::parse_dwr;
-use chrono::Weekday;
-use std::collections::HashMap;
+use crate::{
+    changes::MenuChange,
+    history::{History, HistoryError},
+    source::Sources,
+};
+use chrono::{NaiveDate, Weekday};
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::HashMap,
+    fmt,
+    str::FromStr,
+    time::{Duration, Instant},
+};
 
 /// ID do restaurate
 pub type RestaurantID = usize;
 
-/// Tipos de refeição
-#[derive(Debug, PartialEq, Clone)]
+/// Campi da USP e os IDs dos seus restaurantes.
+pub const CAMPUSES: [(&str, &[RestaurantID]); 3] = [
+    ("São Paulo - Cidade Universitária", &[6, 7, 8, 9]),
+    ("São Paulo - EACH", &[13]),
+    ("São Carlos", &[2, 3]),
+];
+
+/// Tipos de refeição, serializados como `"lunch"` e `"dinner"`
+#[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
 pub enum MenuType {
     /// Almoço
     Lunch,
@@ -17,18 +38,76 @@ pub enum MenuType {
     Dinner,
 }
 
-impl ToString for MenuType {
-    fn to_string(&self) -> String {
+impl MenuType {
+    /// Nome do tipo da refeição no serde (`"lunch"` ou `"dinner"`), usado também no histórico,
+    /// nos IDs do feed e na API.
+    pub fn as_str(&self) -> &'static str {
         match self {
+            MenuType::Lunch => "lunch",
+            MenuType::Dinner => "dinner",
+        }
+    }
+}
+
+impl FromStr for MenuType {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "lunch" => Ok(MenuType::Lunch),
+            "dinner" => Ok(MenuType::Dinner),
+            _ => Err(format!(
+                "Tipo de refeição \"{s}\" desconhecido, use \"lunch\" ou \"dinner\""
+            )),
+        }
+    }
+}
+
+impl fmt::Display for MenuType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
             MenuType::Dinner => "Jantar",
             MenuType::Lunch => "Almoço",
+        })
+    }
+}
+
+/// Ordem em que os restaurantes são exibidos
+#[derive(Debug, PartialEq, Clone, Copy, Default)]
+pub enum MenuSort {
+    /// Na ordem do arquivo de configuração
+    #[default]
+    Config,
+    /// Pela nota do cardápio, do maior para o menor
+    Score,
+}
+
+/// Serialização dos dias da semana como números ISO 8601, de 1 (segunda) a 7 (domingo), para
+/// usar com `#[serde(with = "iso_weekday")]`.
+pub mod iso_weekday {
+    use chrono::Weekday;
+    use serde::{de, Deserialize, Deserializer, Serializer};
+
+    /// Serializa o dia da semana como o seu número ISO 8601.
+    pub fn serialize<S: Serializer>(weekday: &Weekday, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_u32(weekday.number_from_monday())
+    }
+
+    /// Lê o dia da semana pelo seu número ISO 8601, de 1 a 7.
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Weekday, D::Error> {
+        let number = u8::deserialize(deserializer)?;
+        match number {
+            1..=7 => Ok(Weekday::try_from(number - 1).expect("dia da semana entre 0 e 6")),
+            _ => Err(de::Error::invalid_value(
+                de::Unexpected::Unsigned(number.into()),
+                &"um dia da semana ISO, de 1 (segunda) a 7 (domingo)",
+            )),
         }
-        .to_string()
     }
 }
 
 /// Cardápio do dia
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct Menu {
     /// Conteúdo da refeição
     pub content: String,
@@ -36,49 +115,211 @@ pub struct Menu {
     /// Tipo da refeição, por exemplo: janta ou almoço
     pub menu_type: MenuType,
 
-    /// Dia da semana da refeição (Domingo: 1, Segunda: 2, ..., Sabado: 7)
+    /// Dia da semana da refeição, serializado como número ISO (Segunda: 1, ..., Domingo: 7)
+    #[serde(with = "iso_weekday")]
     pub weekday: Weekday,
 
+    /// Data da refeição, se informada pelo sistema da USP
+    pub date: Option<NaiveDate>,
+
     /// Valor Calórico da refeição
     pub calorific_value: Option<usize>,
 
     /// Observações do refeição
+    #[serde(default)]
     pub observation: String,
 }
 
 /// Cache para as refeições e os nomes dos restaurantes
-#[derive(Debug)]
+///
+/// Se houver um histórico, todos os cardápios carregados são guardados nele, e as mudanças
+/// desde a primeira versão guardada de cada cardápio ficam disponíveis em `get_changes`. Os
+/// erros do histórico não interrompem o carregamento e são passados para a função definida em
+/// `with_history_error_handler`, se houver.
+///
+/// Com um tempo de validade (`with_ttl`), os cardápios carregados há mais tempo que ele são
+/// carregados de novo. Se não for possível, os cardápios antigos continuam sendo usados.
 pub struct MenusCache {
     menus: HashMap<RestaurantID, Vec<Menu>>,
     names: HashMap<RestaurantID, String>,
+    changes: HashMap<RestaurantID, Vec<MenuChange>>,
+    searched_at: HashMap<RestaurantID, Instant>,
+    ttl: Option<Duration>,
+    history: Option<History>,
+    history_error_handler: Option<Box<dyn Fn(HistoryError) + Send + Sync>>,
+    sources: Sources,
+}
+
+impl Default for MenusCache {
+    fn default() -> Self {
+        MenusCache::new()
+    }
 }
 
 impl MenusCache {
+    /// Cria um cache vazio, sem tempo de validade e sem histórico, com o sistema da USP como
+    /// fonte de todos os restaurantes.
     pub fn new() -> Self {
         MenusCache {
             names: HashMap::new(),
             menus: HashMap::new(),
+            changes: HashMap::new(),
+            searched_at: HashMap::new(),
+            ttl: None,
+            history: None,
+            history_error_handler: None,
+            sources: Sources::default(),
         }
     }
 
+    /// Define o tempo de validade dos cardápios carregados.
+    pub fn with_ttl(mut self, ttl: Option<Duration>) -> Self {
+        self.ttl = ttl;
+        self
+    }
+
+    /// Define o histórico em que os cardápios carregados são guardados.
+    pub fn with_history(mut self, history: Option<History>) -> Self {
+        self.history = history;
+        self
+    }
+
+    /// Define a função que recebe os erros do histórico, como uma falha ao guardar os cardápios.
+    pub fn with_history_error_handler(
+        mut self,
+        handler: impl Fn(HistoryError) + Send + Sync + 'static,
+    ) -> Self {
+        self.history_error_handler = Some(Box::new(handler));
+        self
+    }
+
+    /// Define as fontes de onde os cardápios de cada restaurante são carregados.
+    pub fn with_sources(mut self, sources: Sources) -> Self {
+        self.sources = sources;
+        self
+    }
+
+    /// Fontes de onde os cardápios de cada restaurante são carregados.
+    pub fn sources(&self) -> &Sources {
+        &self.sources
+    }
+
+    /// Carrega os cardápios e o nome do restaurante da sua fonte, guardando-os no histórico.
+    ///
+    /// Se a fonte falhar, os cardápios já carregados do restaurante são mantidos.
     pub async fn search(&mut self, restaurant_id: RestaurantID) {
-        if let Some(menus) = parse_dwr::get_menus(restaurant_id).await {
-            if let Some(name) = parse_dwr::get_restaurant_name(restaurant_id).await {
+        self.searched_at.insert(restaurant_id, Instant::now());
+        let source = self.sources.get(restaurant_id);
+        if let Ok(menus) = source.menus(restaurant_id).await {
+            if let Ok(name) = source.restaurant_name(restaurant_id).await {
+                let changes = self
+                    .find_changes(restaurant_id, &menus)
+                    .unwrap_or_else(|error| {
+                        self.report_history_error(HistoryError::Find(error));
+                        Vec::new()
+                    });
+                self.changes.insert(restaurant_id, changes);
+                self.save_history(restaurant_id, &name, &menus);
                 self.menus.insert(restaurant_id, menus);
                 self.names.insert(restaurant_id, name);
             };
         };
     }
 
+    /// Carrega os cardápios do restaurante se eles ainda não foram carregados ou se o tempo
+    /// de validade acabou.
+    async fn load(&mut self, restaurant_id: RestaurantID) {
+        let expired = match (self.ttl, self.searched_at.get(&restaurant_id)) {
+            (Some(ttl), Some(searched_at)) => searched_at.elapsed() >= ttl,
+            _ => false,
+        };
+
+        if expired || !self.menus.contains_key(&restaurant_id) {
+            self.search(restaurant_id).await;
+        }
+    }
+
+    /// Guarda os cardápios do restaurante no cache, como se tivessem sido carregados.
+    #[cfg(test)]
+    pub fn insert(&mut self, restaurant_id: RestaurantID, name: &str, menus: Vec<Menu>) {
+        self.searched_at.insert(restaurant_id, Instant::now());
+        self.menus.insert(restaurant_id, menus);
+        self.names.insert(restaurant_id, name.to_string());
+    }
+
+    /// Compara os cardápios carregados com as primeiras versões guardadas no histórico.
+    fn find_changes(
+        &self,
+        restaurant_id: RestaurantID,
+        menus: &[Menu],
+    ) -> rusqlite::Result<Vec<MenuChange>> {
+        let Some(history) = &self.history else {
+            return Ok(Vec::new());
+        };
+
+        let mut changes = Vec::new();
+        for menu in menus {
+            let Some(date) = menu.date else {
+                continue;
+            };
+            if let Some(stored) = history.find_published(restaurant_id, date, &menu.menu_type)? {
+                changes.extend(MenuChange::new(&stored, menu));
+            }
+        }
+        Ok(changes)
+    }
+
+    /// Guarda os cardápios no histórico. Se não for possível, o histórico deixa de ser usado.
+    fn save_history(&mut self, restaurant_id: RestaurantID, name: &str, menus: &[Menu]) {
+        let Some(history) = &mut self.history else {
+            return;
+        };
+
+        if let Err(error) = history.save_menus(restaurant_id, name, menus) {
+            self.history = None;
+            self.report_history_error(HistoryError::Save(error));
+        }
+    }
+
+    /// Passa o erro do histórico para a função definida em `with_history_error_handler`.
+    fn report_history_error(&self, error: HistoryError) {
+        if let Some(handler) = &self.history_error_handler {
+            handler(error);
+        }
+    }
+
+    /// Retorna o nome do restaurante e todos os seus cardápios da semana.
+    pub async fn get_name_and_menus(
+        &mut self,
+        restaurant_id: RestaurantID,
+    ) -> Option<(String, Vec<Menu>)> {
+        self.load(restaurant_id).await;
+
+        let menus = self.menus.get(&restaurant_id)?;
+        let name = self.names.get(&restaurant_id)?;
+        Some((name.clone(), menus.clone()))
+    }
+
+    /// Retorna as mudanças nos cardápios da semana do restaurante desde as primeiras versões
+    /// guardadas no histórico.
+    pub async fn get_changes(&mut self, restaurant_id: RestaurantID) -> Vec<MenuChange> {
+        self.load(restaurant_id).await;
+
+        self.changes
+            .get(&restaurant_id)
+            .cloned()
+            .unwrap_or_default()
+    }
+
+    /// Retorna o nome do restaurante e o seu cardápio da refeição `menu_type` do dia
+    /// `weekday`, ou `None` se ele não puder ser carregado.
     pub async fn get_name_and_menu(
         &mut self,
         restaurant_id: RestaurantID,
         menu_type: &MenuType,
         weekday: Weekday,
     ) -> Option<(String, Menu)> {
-        if !self.menus.contains_key(&restaurant_id) {
-            self.search(restaurant_id).await;
-        }
+        self.load(restaurant_id).await;
 
         let menus = self.menus.get(&restaurant_id)?;
 
@@ -99,6 +340,44 @@ mod tests {
     const RESTAURANT_CENTRAL: RestaurantID = 6;
 
     use super::*;
+    use crate::{changes::DiffLine, menus_file::FileSource};
+    use std::sync::{Arc, Mutex};
+
+    #[test]
+    fn test_menu_serde() {
+        let menu = Menu {
+            content: "Arroz\nFeijão".to_string(),
+            menu_type: MenuType::Dinner,
+            weekday: Weekday::Sun,
+            date: NaiveDate::from_ymd_opt(2025, 3, 9),
+            calorific_value: Some(1030),
+            observation: String::new(),
+        };
+
+        let json = serde_json::to_value(&menu).unwrap();
+        assert_eq!(
+            json,
+            serde_json::json!({
+                "content": "Arroz\nFeijão",
+                "menu_type": "dinner",
+                "weekday": 7,
+                "date": "2025-03-09",
+                "calorific_value": 1030,
+                "observation": "",
+            })
+        );
+        assert_eq!(serde_json::from_value::<Menu>(json).unwrap(), menu);
+
+        let invalid = serde_json::json!({
+            "content": "",
+            "menu_type": "lunch",
+            "weekday": 0,
+            "date": null,
+            "calorific_value": null,
+            "observation": "",
+        });
+        assert!(serde_json::from_value::<Menu>(invalid).is_err());
+    }
 
     #[test]
     fn test_menu_type_to_string() {
@@ -106,6 +385,18 @@ mod tests {
         assert_eq!(MenuType::Dinner.to_string(), "Jantar");
     }
 
+    #[test]
+    fn test_menu_type_as_str() {
+        for menu_type in [MenuType::Lunch, MenuType::Dinner] {
+            assert_eq!(
+                serde_json::to_value(&menu_type).unwrap(),
+                menu_type.as_str()
+            );
+            assert_eq!(menu_type.as_str().parse(), Ok(menu_type));
+        }
+        assert!("Almoço".parse::<MenuType>().is_err());
+    }
+
     #[tokio::test]
     async fn test_get_name_and_menu_from_menu_cache() {
         let mut cache = MenusCache::new();
@@ -119,4 +410,69 @@ mod tests {
         assert!(menu.weekday == Weekday::Mon);
         assert!(menu.menu_type == MenuType::Lunch);
     }
+
+    #[tokio::test]
+    async fn test_history_errors_are_passed_to_the_handler() {
+        let history = History::open_in_memory().unwrap();
+        history.drop_table().unwrap();
+
+        let menus_file = tempfile::Builder::new().suffix(".yaml").tempfile().unwrap();
+        std::fs::write(
+            menus_file.path(),
+            "name: Café\nmenus:\n  - {weekday: 4, menu_type: lunch, content: Pão, date: 2025-03-06}",
+        )
+        .unwrap();
+
+        let errors = Arc::new(Mutex::new(Vec::new()));
+        let handler_errors = errors.clone();
+        let sources =
+            Sources::default().with_restaurant(100, Arc::new(FileSource::new(menus_file.path())));
+        let mut cache = MenusCache::new()
+            .with_sources(sources)
+            .with_history(Some(history))
+            .with_history_error_handler(move |error| {
+                handler_errors.lock().unwrap().push(error.to_string())
+            });
+
+        // O erro não impede que os cardápios carregados sejam usados
+        assert!(cache.get_name_and_menus(100).await.is_some());
+        assert!(cache.get_changes(100).await.is_empty());
+        let errors = errors.lock().unwrap();
+        assert_eq!(errors.len(), 2);
+        assert!(errors[0].starts_with("Não foi possível consultar o histórico"));
+        assert!(errors[1].starts_with("Não foi possível guardar os cardápios no histórico"));
+        assert!(cache.history.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_changes_since_the_first_version() {
+        let menus_file = tempfile::Builder::new().suffix(".yaml").tempfile().unwrap();
+        let write_menu = |content: &str| {
+            std::fs::write(
+                menus_file.path(),
+                format!("name: Café\nmenus:\n  - {{weekday: 4, menu_type: lunch, content: \"{content}\", date: 2025-03-06}}"),
+            )
+            .unwrap()
+        };
+        let sources =
+            Sources::default().with_restaurant(100, Arc::new(FileSource::new(menus_file.path())));
+        // Sem tempo de validade, os cardápios são carregados de novo a cada consulta
+        let mut cache = MenusCache::new()
+            .with_sources(sources)
+            .with_history(Some(History::open_in_memory().unwrap()))
+            .with_ttl(Some(Duration::ZERO));
+
+        write_menu("Arroz\\nStrogonoff");
+        assert!(cache.get_changes(100).await.is_empty());
+
+        // A mudança continua sendo mostrada depois de o cardápio novo ser guardado
+        write_menu("Arroz\\nPeixe");
+        for _ in 0..2 {
+            let changes = cache.get_changes(100).await;
+            assert_eq!(changes.len(), 1);
+            assert!(changes[0]
+                .lines
+                .contains(&DiffLine::Removed("Strogonoff".to_string())));
+        }
+    }
 }
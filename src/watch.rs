@@ -0,0 +1,521 @@
+/*!
+Modo de observação (`bandex watch`)
+
+Este módulo mantém o Bandex aberto e avisa sobre as refeições com notificações na área de
+trabalho:
+- Cada restaurante pode definir os horários do almoço e do jantar (`hours`), que por padrão
+  são os horários dos bandejões da USP
+- A notificação é enviada alguns minutos antes de o primeiro restaurante configurado abrir
+- A notificação traz o restaurante com a melhor nota e as comidas gostadas dos cardápios
+- As notificações usam a interface `org.freedesktop.Notifications` do D-Bus da sessão, então
+  podem ser testadas em um barramento local (por exemplo, com `dbus-run-session`)
+*/
+
+use std::{borrow::Cow, collections::HashMap, fmt, str::FromStr};
+
+use chrono::{Datelike, Days, Local, NaiveDate, NaiveDateTime, NaiveTime, TimeDelta};
+use schemars::{json_schema, JsonSchema, Schema, SchemaGenerator};
+use serde::{Deserialize, Serialize};
+use zbus::{zvariant::Value, Connection};
+
+use crate::{
+    config::Config,
+    recommend::{self, Candidate},
+    types::{MenuType, MenusCache, RestaurantID},
+};
+
+/// Horário padrão do almoço nos bandejões da USP.
+const DEFAULT_LUNCH: (u32, u32, u32, u32) = (11, 0, 14, 15);
+
+/// Horário padrão do jantar nos bandejões da USP.
+const DEFAULT_DINNER: (u32, u32, u32, u32) = (17, 30, 19, 45);
+
+/// Nome, caminho e interface do serviço de notificações do D-Bus.
+const NOTIFICATIONS_DESTINATION: &str = "org.freedesktop.Notifications";
+const NOTIFICATIONS_PATH: &str = "/org/freedesktop/Notifications";
+const NOTIFICATIONS_INTERFACE: &str = "org.freedesktop.Notifications";
+
+/// Nome do aplicativo nas notificações.
+const APP_NAME: &str = "Bandex";
+
+/// Horário de uma refeição, como "11:00-14:15".
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MealWindow {
+    /// Horário em que o restaurante abre.
+    pub start: NaiveTime,
+
+    /// Horário em que o restaurante fecha.
+    pub end: NaiveTime,
+}
+
+impl MealWindow {
+    fn from_hours((start_hour, start_min, end_hour, end_min): (u32, u32, u32, u32)) -> Self {
+        MealWindow {
+            start: NaiveTime::from_hms_opt(start_hour, start_min, 0).expect("horário válido"),
+            end: NaiveTime::from_hms_opt(end_hour, end_min, 0).expect("horário válido"),
+        }
+    }
+
+    /// Horário padrão da refeição nos bandejões da USP.
+    pub fn default_for(menu_type: &MenuType) -> Self {
+        match menu_type {
+            MenuType::Lunch => MealWindow::from_hours(DEFAULT_LUNCH),
+            MenuType::Dinner => MealWindow::from_hours(DEFAULT_DINNER),
+        }
+    }
+}
+
+impl FromStr for MealWindow {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let error = || format!("Horário \"{s}\" inválido, use o formato \"11:00-14:15\"");
+        let (start, end) = s.split_once('-').ok_or_else(error)?;
+        let parse = |time: &str| NaiveTime::parse_from_str(time.trim(), "%H:%M");
+        let (Ok(start), Ok(end)) = (parse(start), parse(end)) else {
+            return Err(error());
+        };
+
+        if start >= end {
+            return Err(format!(
+                "Horário \"{s}\" inválido, o início deve ser antes do fim"
+            ));
+        }
+        Ok(MealWindow { start, end })
+    }
+}
+
+impl fmt::Display for MealWindow {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}-{}",
+            self.start.format("%H:%M"),
+            self.end.format("%H:%M")
+        )
+    }
+}
+
+impl<'de> Deserialize<'de> for MealWindow {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        String::deserialize(deserializer)?
+            .parse()
+            .map_err(serde::de::Error::custom)
+    }
+}
+
+impl Serialize for MealWindow {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+impl JsonSchema for MealWindow {
+    fn schema_name() -> Cow<'static, str> {
+        "MealWindow".into()
+    }
+
+    fn json_schema(_: &mut SchemaGenerator) -> Schema {
+        json_schema!({
+            "description": "Início e fim da refeição, no formato \"HH:MM-HH:MM\"",
+            "type": "string",
+            "pattern": "^\\d{1,2}:\\d{2}-\\d{1,2}:\\d{2}$",
+            "examples": ["11:00-14:15"]
+        })
+    }
+}
+
+/// Horários das refeições de um restaurante (`bandex.restaurants[].hours`).
+#[derive(Debug, Clone, Default, PartialEq, Deserialize, Serialize, JsonSchema)]
+#[schemars(deny_unknown_fields)]
+pub struct MealHours {
+    /// Horário do almoço no restaurante, se for diferente do padrão.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[schemars(title = "Horário do almoço (por padrão, 11:00-14:15)")]
+    pub lunch: Option<MealWindow>,
+
+    /// Horário do jantar no restaurante, se for diferente do padrão.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[schemars(title = "Horário do jantar (por padrão, 17:30-19:45)")]
+    pub dinner: Option<MealWindow>,
+}
+
+impl MealHours {
+    /// Verifica se nenhum horário foi definido.
+    pub fn is_empty(&self) -> bool {
+        self.lunch.is_none() && self.dinner.is_none()
+    }
+
+    /// Horário da refeição, ou o horário padrão se ele não foi definido.
+    pub fn window(&self, menu_type: &MenuType) -> MealWindow {
+        let window = match menu_type {
+            MenuType::Lunch => self.lunch,
+            MenuType::Dinner => self.dinner,
+        };
+        window.unwrap_or_else(|| MealWindow::default_for(menu_type))
+    }
+}
+
+/// Próxima refeição a ser avisada.
+#[derive(Debug, Clone, PartialEq)]
+pub struct MealAlert {
+    /// Data da refeição.
+    pub date: NaiveDate,
+
+    /// Tipo da refeição.
+    pub menu_type: MenuType,
+
+    /// Horário em que o primeiro restaurante abre.
+    pub start: NaiveDateTime,
+
+    /// Horário em que a notificação deve ser enviada.
+    pub notify_at: NaiveDateTime,
+}
+
+/// Encontra a primeira refeição que começa depois de `after`, a ser avisada `before` antes de
+/// o primeiro dos restaurantes `hours` abrir.
+pub fn next_alert(hours: &[&MealHours], after: NaiveDateTime, before: TimeDelta) -> MealAlert {
+    let start_time = |menu_type: &MenuType| {
+        hours
+            .iter()
+            .map(|hours| hours.window(menu_type).start)
+            .min()
+            .unwrap_or_else(|| MealWindow::default_for(menu_type).start)
+    };
+
+    let mut date = after.date();
+    loop {
+        for menu_type in [MenuType::Lunch, MenuType::Dinner] {
+            let start = date.and_time(start_time(&menu_type));
+            if start > after {
+                return MealAlert {
+                    date,
+                    menu_type,
+                    start,
+                    notify_at: start - before,
+                };
+            }
+        }
+        date = date + Days::new(1);
+    }
+}
+
+/// Notificação na área de trabalho.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Notification {
+    /// Título da notificação, com a refeição e o restaurante recomendado.
+    pub summary: String,
+
+    /// Texto da notificação, com o motivo da recomendação e as comidas gostadas.
+    pub body: String,
+}
+
+impl Notification {
+    /// Cria a notificação da refeição `alert` a partir dos restaurantes abertos já ordenados
+    /// (`ranked`), ou `None` se todos estiverem fechados.
+    ///
+    /// A notificação traz o restaurante com a melhor nota (com a nota, se `show_score`), o
+    /// motivo da escolha e as comidas gostadas de todos os cardápios.
+    pub fn for_meal(alert: &MealAlert, ranked: &[Candidate], show_score: bool) -> Option<Self> {
+        let [best, others @ ..] = ranked else {
+            return None;
+        };
+
+        let summary = format!(
+            "{} às {}: {}",
+            alert.menu_type,
+            alert.start.format("%H:%M"),
+            best.name
+        );
+
+        let mut body = if show_score {
+            format!("★ {} [{:+}]", best.name, best.score)
+        } else {
+            format!("★ {}", best.name)
+        };
+        body.push_str(&format!(": {}", recommend::reason(best, others)));
+
+        let liked: Vec<String> = ranked
+            .iter()
+            .flat_map(|candidate| {
+                candidate
+                    .liked
+                    .iter()
+                    .map(move |food| format!("{food} ({})", candidate.name))
+            })
+            .collect();
+        if !liked.is_empty() {
+            body.push_str(&format!("\nComidas gostadas: {}", liked.join(", ")));
+        }
+
+        Some(Notification { summary, body })
+    }
+}
+
+/// Envia notificações pelo serviço `org.freedesktop.Notifications` do D-Bus.
+pub struct Notifier {
+    connection: Connection,
+}
+
+impl Notifier {
+    /// Conecta ao D-Bus da sessão (definido por `DBUS_SESSION_BUS_ADDRESS`).
+    pub async fn session() -> zbus::Result<Notifier> {
+        Ok(Notifier::new(Connection::session().await?))
+    }
+
+    /// Usa a conexão `connection` com o D-Bus, como um barramento local nos testes.
+    pub fn new(connection: Connection) -> Notifier {
+        Notifier { connection }
+    }
+
+    /// Envia a notificação, retornando o ID dado pelo serviço de notificações.
+    pub async fn notify(&self, notification: &Notification) -> zbus::Result<u32> {
+        let actions: Vec<&str> = Vec::new();
+        let hints: HashMap<&str, Value> = HashMap::new();
+        let reply = self
+            .connection
+            .call_method(
+                Some(NOTIFICATIONS_DESTINATION),
+                NOTIFICATIONS_PATH,
+                Some(NOTIFICATIONS_INTERFACE),
+                "Notify",
+                &(
+                    APP_NAME,
+                    0u32,
+                    "",
+                    &notification.summary,
+                    &notification.body,
+                    actions,
+                    hints,
+                    -1i32,
+                ),
+            )
+            .await?;
+        reply.body().deserialize()
+    }
+}
+
+/// Acontecimento do modo de observação, para ser mostrado ao usuário.
+#[derive(Debug)]
+pub enum WatchEvent<'a> {
+    /// A próxima notificação foi agendada.
+    Scheduled(&'a MealAlert),
+
+    /// O cardápio da refeição do restaurante não pôde ser carregado para a notificação.
+    Unavailable(RestaurantID),
+
+    /// A notificação foi enviada ao serviço de notificações, com o resultado do envio.
+    Notified(&'a Notification, zbus::Result<u32>),
+}
+
+/// Fica aberto e envia pelo `notifier` uma notificação `before` antes de cada refeição dos
+/// restaurantes das configurações, carregando os cardápios de novo a cada `refresh`.
+///
+/// A cada atualização, os cardápios são carregados em um novo cache criado por `menus_cache`.
+/// Os acontecimentos são passados para `on_event`. Só termina quando o programa é interrompido.
+pub async fn watch(
+    config: &Config,
+    notifier: &Notifier,
+    before: TimeDelta,
+    refresh: TimeDelta,
+    menus_cache: impl Fn() -> MenusCache,
+    mut on_event: impl FnMut(WatchEvent),
+) {
+    let hours: Vec<&MealHours> = config
+        .restaurants
+        .iter()
+        .map(|restaurant| &restaurant.hours)
+        .collect();
+
+    let now = Local::now().naive_local();
+    let mut alert = next_alert(&hours, now, before);
+    on_event(WatchEvent::Scheduled(&alert));
+
+    let mut cache = menus_cache();
+    let mut refreshed_at: Option<NaiveDateTime> = None;
+    loop {
+        let now = Local::now().naive_local();
+        let next_refresh = refreshed_at.map_or(now, |refreshed_at| refreshed_at + refresh);
+
+        if now >= next_refresh {
+            // Um novo cache carrega os cardápios de novo, guardando-os no histórico
+            cache = menus_cache();
+            for restaurant in &config.restaurants {
+                cache.get_name_and_menus(restaurant.id).await;
+            }
+            refreshed_at = Some(now);
+            continue;
+        }
+
+        if now >= alert.notify_at {
+            let (ranked, unavailable) =
+                recommend::candidates(&mut cache, config, alert.date.weekday(), &alert.menu_type)
+                    .await;
+            for restaurant_id in unavailable {
+                on_event(WatchEvent::Unavailable(restaurant_id));
+            }
+
+            if let Some(notification) = Notification::for_meal(&alert, &ranked, config.has_foods())
+            {
+                let result = notifier.notify(&notification).await;
+                on_event(WatchEvent::Notified(&notification, result));
+            }
+
+            alert = next_alert(&hours, alert.start, before);
+            on_event(WatchEvent::Scheduled(&alert));
+            continue;
+        }
+
+        let duration = (alert.notify_at.min(next_refresh) - now)
+            .to_std()
+            .unwrap_or_default();
+        tokio::time::sleep(duration).await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::{Arc, Mutex};
+    use zbus::{connection, zvariant::OwnedValue, Guid};
+
+    fn datetime(day: u32, hour: u32, min: u32) -> NaiveDateTime {
+        NaiveDate::from_ymd_opt(2025, 3, day)
+            .unwrap()
+            .and_hms_opt(hour, min, 0)
+            .unwrap()
+    }
+
+    #[test]
+    fn test_meal_window() {
+        let window: MealWindow = "11:00-14:15".parse().unwrap();
+        assert_eq!(window, MealWindow::default_for(&MenuType::Lunch));
+        assert_eq!(window.to_string(), "11:00-14:15");
+
+        assert!("11:00".parse::<MealWindow>().is_err());
+        assert!("14:00-11:00".parse::<MealWindow>().is_err());
+        assert!("11h-14h".parse::<MealWindow>().is_err());
+
+        let hours: MealHours = serde_yaml::from_str("dinner: 17:00-19:00").unwrap();
+        assert_eq!(hours.lunch, None);
+        assert_eq!(hours.window(&MenuType::Dinner).to_string(), "17:00-19:00");
+        assert_eq!(hours.window(&MenuType::Lunch).to_string(), "11:00-14:15");
+        assert!(serde_yaml::from_str::<MealHours>("dinner: 19:00").is_err());
+    }
+
+    #[test]
+    fn test_next_alert() {
+        let early = MealHours {
+            lunch: Some("10:30-13:00".parse().unwrap()),
+            dinner: None,
+        };
+        let default = MealHours::default();
+        let hours = [&default, &early];
+        let before = TimeDelta::minutes(15);
+
+        let alert = next_alert(&hours, datetime(4, 8, 0), before);
+        assert_eq!(alert.menu_type, MenuType::Lunch);
+        assert_eq!(alert.start, datetime(4, 10, 30));
+        assert_eq!(alert.notify_at, datetime(4, 10, 15));
+
+        let alert = next_alert(&hours, alert.start, before);
+        assert_eq!(alert.menu_type, MenuType::Dinner);
+        assert_eq!(alert.notify_at, datetime(4, 17, 15));
+
+        let alert = next_alert(&hours, datetime(4, 20, 0), before);
+        assert_eq!(alert.date, NaiveDate::from_ymd_opt(2025, 3, 5).unwrap());
+        assert_eq!(alert.menu_type, MenuType::Lunch);
+
+        let alert = next_alert(&[], datetime(4, 12, 0), before);
+        assert_eq!(alert.start, datetime(4, 17, 30));
+    }
+
+    #[test]
+    fn test_notification_for_meal() {
+        let alert = next_alert(&[], datetime(4, 8, 0), TimeDelta::minutes(15));
+        let candidate = |name: &str, score, liked: &[&str]| Candidate {
+            restaurant_id: 0,
+            name: name.to_string(),
+            score,
+            liked: liked.iter().map(|food| food.to_string()).collect(),
+            disliked: vec![],
+            compatible: true,
+        };
+        let ranked = [
+            candidate("Central", 2, &["pudim"]),
+            candidate("Física", 1, &["sorvete"]),
+        ];
+
+        let notification = Notification::for_meal(&alert, &ranked, true).unwrap();
+        assert_eq!(notification.summary, "Almoço às 11:00: Central");
+        assert_eq!(
+            notification.body,
+            "★ Central [+2]: tem pudim\nComidas gostadas: pudim (Central), sorvete (Física)"
+        );
+
+        let notification = Notification::for_meal(&alert, &ranked[1..], false).unwrap();
+        assert!(notification.body.starts_with("★ Física: tem sorvete"));
+        assert_eq!(Notification::for_meal(&alert, &[], true), None);
+    }
+
+    /// Serviço de notificações falso, que guarda as notificações recebidas.
+    struct FakeNotifications {
+        received: Arc<Mutex<Vec<(String, String, String)>>>,
+    }
+
+    #[zbus::interface(name = "org.freedesktop.Notifications")]
+    impl FakeNotifications {
+        #[allow(clippy::too_many_arguments)]
+        fn notify(
+            &self,
+            app_name: String,
+            _replaces_id: u32,
+            _app_icon: String,
+            summary: String,
+            body: String,
+            _actions: Vec<String>,
+            _hints: HashMap<String, OwnedValue>,
+            _expire_timeout: i32,
+        ) -> u32 {
+            let mut received = self.received.lock().unwrap();
+            received.push((app_name, summary, body));
+            received.len() as u32
+        }
+    }
+
+    #[tokio::test]
+    async fn test_notifier() {
+        let received = Arc::new(Mutex::new(Vec::new()));
+        let service = FakeNotifications {
+            received: received.clone(),
+        };
+
+        let (server_stream, client_stream) = tokio::net::UnixStream::pair().unwrap();
+        let server = connection::Builder::unix_stream(server_stream)
+            .server(Guid::generate())
+            .unwrap()
+            .p2p()
+            .serve_at(NOTIFICATIONS_PATH, service)
+            .unwrap()
+            .build();
+        let client = connection::Builder::unix_stream(client_stream)
+            .p2p()
+            .build();
+        let (_server, client) = tokio::try_join!(server, client).unwrap();
+
+        let notification = Notification {
+            summary: "Almoço às 11:00: Central".to_string(),
+            body: "★ Central: tem pudim".to_string(),
+        };
+        let id = Notifier::new(client).notify(&notification).await.unwrap();
+        assert_eq!(id, 1);
+        assert_eq!(
+            received.lock().unwrap()[0],
+            (
+                "Bandex".to_string(),
+                notification.summary.clone(),
+                notification.body.clone()
+            )
+        );
+    }
+}
@@ -0,0 +1,365 @@
+/*!
+Envio dos cardápios para canais de chat (`bandex post`)
+
+Este módulo monta as mensagens com os cardápios no formato dos webhooks do Slack, do Discord
+e do Mattermost, e as envia por HTTP:
+- Cada mensagem é uma refeição de um dia, com um anexo (ou "embed", no Discord) por restaurante
+- Os anexos usam as cores dos restaurantes das configurações
+- O Slack e o Mattermost usam o mesmo formato de anexos (`attachments`), enquanto o Discord
+  usa `embeds`, com a cor como um número
+*/
+
+use std::fmt;
+
+use chrono::Weekday;
+use colored::Color;
+use reqwest::header::CONTENT_TYPE;
+use serde_json::{json, Value};
+
+use crate::{
+    config::Config,
+    display::WEEKDAY_NAMES,
+    types::{Menu, MenuType, MenusCache, RestaurantID},
+};
+
+/// Nome do remetente das mensagens, nos serviços que permitem escolhê-lo.
+const USERNAME: &str = "Bandex";
+
+/// Formato da mensagem enviada ao webhook.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum WebhookFormat {
+    /// Anexos (`attachments`) do Slack.
+    #[default]
+    Slack,
+
+    /// `embeds` do Discord.
+    Discord,
+
+    /// Anexos do Mattermost, com o nome do remetente.
+    Mattermost,
+}
+
+impl fmt::Display for WebhookFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            WebhookFormat::Slack => "slack",
+            WebhookFormat::Discord => "discord",
+            WebhookFormat::Mattermost => "mattermost",
+        })
+    }
+}
+
+/// Cardápio de um restaurante que será enviado.
+#[derive(Debug, Clone)]
+pub struct PostedMenu {
+    /// Nome do restaurante.
+    pub name: String,
+
+    /// Cor do restaurante nas configurações.
+    pub color: Color,
+
+    /// Cardápio da refeição.
+    pub menu: Menu,
+}
+
+/// Mensagem de uma refeição de um dia, com os cardápios dos restaurantes.
+#[derive(Debug, Clone)]
+pub struct MealPost {
+    /// Título da mensagem, como "Almoço de Segunda-feira (03/03/2025)".
+    pub title: String,
+
+    /// Cardápios dos restaurantes, na ordem das configurações.
+    pub menus: Vec<PostedMenu>,
+}
+
+impl PostedMenu {
+    /// Texto do cardápio, com um prato por linha.
+    fn text(&self) -> String {
+        let lines: Vec<&str> = self
+            .menu
+            .content
+            .split('\n')
+            .map(str::trim)
+            .filter(|line| !line.is_empty())
+            .collect();
+        lines.join("\n")
+    }
+
+    /// Rodapé do cardápio, com o valor energético e as observações.
+    fn footer(&self) -> String {
+        let mut parts = Vec::new();
+        if let Some(calorific_value) = self.menu.calorific_value {
+            parts.push(format!("{calorific_value} kcal"));
+        }
+        let observation = self.menu.observation.trim();
+        if !observation.is_empty() {
+            parts.push(observation.to_string());
+        }
+        parts.join(" - ")
+    }
+}
+
+/// Cor em RGB, usando a paleta padrão do xterm para as cores pelo nome.
+pub fn color_rgb(color: Color) -> (u8, u8, u8) {
+    match color {
+        Color::Black => (0, 0, 0),
+        Color::Red => (205, 0, 0),
+        Color::Green => (0, 205, 0),
+        Color::Yellow => (205, 205, 0),
+        Color::Blue => (0, 0, 238),
+        Color::Magenta => (205, 0, 205),
+        Color::Cyan => (0, 205, 205),
+        Color::White => (229, 229, 229),
+        Color::BrightBlack => (127, 127, 127),
+        Color::BrightRed => (255, 0, 0),
+        Color::BrightGreen => (0, 255, 0),
+        Color::BrightYellow => (255, 255, 0),
+        Color::BrightBlue => (92, 92, 255),
+        Color::BrightMagenta => (255, 0, 255),
+        Color::BrightCyan => (0, 255, 255),
+        Color::BrightWhite => (255, 255, 255),
+        Color::TrueColor { r, g, b } => (r, g, b),
+    }
+}
+
+/// Monta a mensagem no formato `format`, com o título `title` e os cardápios `menus`.
+pub fn payload(format: WebhookFormat, title: &str, menus: &[PostedMenu]) -> Value {
+    match format {
+        WebhookFormat::Slack | WebhookFormat::Mattermost => {
+            let attachments: Vec<Value> = menus
+                .iter()
+                .map(|menu| {
+                    let (r, g, b) = color_rgb(menu.color);
+                    json!({
+                        "fallback": format!("{}: {}", menu.name, menu.text().replace('\n', ", ")),
+                        "color": format!("#{r:02x}{g:02x}{b:02x}"),
+                        "title": menu.name,
+                        "text": menu.text(),
+                        "footer": menu.footer(),
+                    })
+                })
+                .collect();
+
+            let mut payload = json!({ "text": title, "attachments": attachments });
+            if format == WebhookFormat::Mattermost {
+                payload["username"] = json!(USERNAME);
+            }
+            payload
+        }
+        WebhookFormat::Discord => {
+            let embeds: Vec<Value> = menus
+                .iter()
+                .map(|menu| {
+                    let (r, g, b) = color_rgb(menu.color);
+                    json!({
+                        "title": menu.name,
+                        "description": menu.text(),
+                        "color": (u32::from(r) << 16) | (u32::from(g) << 8) | u32::from(b),
+                        "footer": { "text": menu.footer() },
+                    })
+                })
+                .collect();
+
+            json!({ "username": USERNAME, "content": title, "embeds": embeds })
+        }
+    }
+}
+
+/// Envia a mensagem `payload` ao webhook `url`, falhando se a resposta não for de sucesso.
+pub async fn post(url: &str, payload: &Value) -> Result<(), String> {
+    let response = reqwest::Client::new()
+        .post(url)
+        .header(CONTENT_TYPE, "application/json")
+        .body(payload.to_string())
+        .send()
+        .await
+        .map_err(|error| format!("Erro ao enviar a mensagem: {error}"))?;
+
+    let status = response.status();
+    if status.is_success() {
+        Ok(())
+    } else {
+        let body = response.text().await.unwrap_or_default();
+        Err(format!("O webhook respondeu com {status}: {}", body.trim()))
+    }
+}
+
+/// Monta as mensagens das refeições `menu_types` dos dias `weekdays` com os cardápios dos
+/// restaurantes das configurações, uma para cada refeição de cada dia.
+///
+/// Retorna também os restaurantes em que algum desses cardápios não pôde ser carregado. As
+/// mensagens sem nenhum cardápio também são retornadas, para que possam ser avisadas.
+pub async fn meal_posts(
+    menus_cache: &mut MenusCache,
+    config: &Config,
+    weekdays: &[Weekday],
+    menu_types: &[MenuType],
+) -> (Vec<MealPost>, Vec<RestaurantID>) {
+    let mut posts = Vec::new();
+    let mut unavailable = Vec::new();
+    for weekday in weekdays {
+        for menu_type in menu_types {
+            let mut menus = Vec::new();
+            for restaurant in &config.restaurants {
+                match menus_cache
+                    .get_name_and_menu(restaurant.id, menu_type, *weekday)
+                    .await
+                {
+                    Some((name, menu)) => menus.push(PostedMenu {
+                        name,
+                        color: restaurant.color,
+                        menu,
+                    }),
+                    None if unavailable.contains(&restaurant.id) => {}
+                    None => unavailable.push(restaurant.id),
+                }
+            }
+
+            let weekday_name = WEEKDAY_NAMES[weekday.num_days_from_monday() as usize];
+            let mut title = format!("{menu_type} de {weekday_name}");
+            if let Some(date) = menus.iter().find_map(|posted| posted.menu.date) {
+                title.push_str(&format!(" ({})", date.format("%d/%m/%Y")));
+            }
+            posts.push(MealPost { title, menus });
+        }
+    }
+    (posts, unavailable)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{config::RestaurantConfig, menus_file::FileSource, source::Sources};
+    use chrono::NaiveDate;
+    use std::{sync::Arc, thread};
+
+    fn menus() -> Vec<PostedMenu> {
+        vec![PostedMenu {
+            name: "Central".to_string(),
+            color: Color::TrueColor {
+                r: 240,
+                g: 218,
+                b: 94,
+            },
+            menu: Menu {
+                content: "Arroz\n\nPudim ".to_string(),
+                menu_type: MenuType::Lunch,
+                weekday: Weekday::Mon,
+                date: None,
+                calorific_value: Some(1030),
+                observation: "Cardápio sujeito a modificação".to_string(),
+            },
+        }]
+    }
+
+    #[test]
+    fn test_payload() {
+        let slack = payload(WebhookFormat::Slack, "Almoço de Segunda", &menus());
+        assert_eq!(
+            slack,
+            json!({
+                "text": "Almoço de Segunda",
+                "attachments": [{
+                    "fallback": "Central: Arroz, Pudim",
+                    "color": "#f0da5e",
+                    "title": "Central",
+                    "text": "Arroz\nPudim",
+                    "footer": "1030 kcal - Cardápio sujeito a modificação",
+                }]
+            })
+        );
+
+        let mattermost = payload(WebhookFormat::Mattermost, "Almoço de Segunda", &menus());
+        assert_eq!(mattermost["attachments"], slack["attachments"]);
+        assert_eq!(mattermost["username"], "Bandex");
+
+        let discord = payload(WebhookFormat::Discord, "Almoço de Segunda", &menus());
+        assert_eq!(discord["content"], "Almoço de Segunda");
+        assert_eq!(discord["embeds"][0]["color"], 0xf0da5e);
+        assert_eq!(discord["embeds"][0]["description"], "Arroz\nPudim");
+    }
+
+    #[test]
+    fn test_color_rgb() {
+        assert_eq!(color_rgb(Color::BrightWhite), (255, 255, 255));
+        assert_eq!(color_rgb(Color::TrueColor { r: 1, g: 2, b: 3 }), (1, 2, 3));
+    }
+
+    /// Inicia um servidor HTTP local que responde uma requisição com `status`, retornando o
+    /// endereço do servidor e o corpo da requisição recebida.
+    fn stand_in(status: u16) -> (String, thread::JoinHandle<(String, String)>) {
+        let server = tiny_http::Server::http("127.0.0.1:0").unwrap();
+        let url = format!("http://{}/hooks/abc", server.server_addr().to_ip().unwrap());
+
+        let handle = thread::spawn(move || {
+            let mut request = server.recv().unwrap();
+            let mut body = String::new();
+            request.as_reader().read_to_string(&mut body).unwrap();
+            let content_type = request
+                .headers()
+                .iter()
+                .find(|header| header.field.equiv("Content-Type"))
+                .map(|header| header.value.to_string())
+                .unwrap_or_default();
+            let response = tiny_http::Response::from_string("ok").with_status_code(status);
+            request.respond(response).unwrap();
+            (content_type, body)
+        });
+        (url, handle)
+    }
+
+    #[tokio::test]
+    async fn test_post() {
+        let (url, handle) = stand_in(200);
+        let message = payload(WebhookFormat::Discord, "Jantar de Segunda", &menus());
+        post(&url, &message).await.unwrap();
+
+        let (content_type, body) = handle.join().unwrap();
+        assert_eq!(content_type, "application/json");
+        assert_eq!(serde_json::from_str::<Value>(&body).unwrap(), message);
+
+        let (url, handle) = stand_in(404);
+        let error = post(&url, &message).await.unwrap_err();
+        assert!(error.contains("404"), "{error}");
+        handle.join().unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_meal_posts() {
+        // O restaurante 9 não tem arquivo de cardápios, então não pode ser carregado
+        let sources = Sources::default().with_restaurant(9, Arc::new(FileSource::new("")));
+        let mut menus_cache = MenusCache::new().with_sources(sources);
+        let mut menu = menus()[0].menu.clone();
+        menu.date = NaiveDate::from_ymd_opt(2025, 3, 3);
+        menus_cache.insert(6, "Central", vec![menu]);
+
+        let config = Config {
+            restaurants: vec![
+                RestaurantConfig::new(9, Color::Blue),
+                RestaurantConfig::new(6, Color::Yellow),
+            ],
+            ..Config::default()
+        };
+        let (posts, unavailable) = meal_posts(
+            &mut menus_cache,
+            &config,
+            &[Weekday::Mon, Weekday::Tue],
+            &[MenuType::Lunch],
+        )
+        .await;
+
+        let titles: Vec<_> = posts.iter().map(|post| &post.title[..]).collect();
+        assert_eq!(
+            titles,
+            vec![
+                "Almoço de Segunda-feira (03/03/2025)",
+                "Almoço de Terça-feira"
+            ]
+        );
+        assert_eq!(posts[0].menus.len(), 1);
+        assert_eq!(posts[0].menus[0].color, Color::Yellow);
+        // O cardápio de terça-feira do restaurante 6 também não pôde ser carregado
+        assert!(posts[1].menus.is_empty());
+        assert_eq!(unavailable, vec![9, 6]);
+    }
+}
@@ -0,0 +1,310 @@
+/*!
+Assistente para criar o arquivo de configuração
+
+Este módulo implementa o assistente interativo do `bandex config init`, que pergunta
+o campus, os restaurantes (a partir dos restaurantes encontrados no sistema da USP),
+as cores dos restaurantes e as comidas gostadas e não gostadas, montando um `Config`.
+
+A entrada e a saída são genéricas, de modo que o assistente pode ser testado sem um terminal.
+*/
+
+use std::io::{BufRead, Error, ErrorKind, Write};
+
+use colored::Color;
+
+use bandex::{
+    config::{Config, FoodConfig, RestaurantConfig},
+    diet::DietConfig,
+    types::{RestaurantID, CAMPUSES},
+};
+
+/// Cor usada quando o usuário não escolhe uma cor para o restaurante.
+const DEFAULT_COLOR: Color = Color::White;
+
+/// Lê uma cor digitada pelo usuário, pelo nome (`blue`) ou por RGB (`187, 35, 51`).
+fn parse_color(value: &str) -> Option<Color> {
+    let value = value.trim().trim_start_matches('[').trim_end_matches(']');
+
+    if !value.contains(',') {
+        return value.parse().ok();
+    }
+
+    let rgb: Vec<u8> = value
+        .split(',')
+        .map(|value| value.trim().parse().ok())
+        .collect::<Option<_>>()?;
+
+    match rgb[..] {
+        [r, g, b] => Some(Color::TrueColor { r, g, b }),
+        _ => None,
+    }
+}
+
+/// Separa uma lista digitada pelo usuário, com os itens separados por vírgula.
+fn split_list(value: &str) -> Vec<String> {
+    value
+        .split(',')
+        .map(str::trim)
+        .filter(|item| !item.is_empty())
+        .map(String::from)
+        .collect()
+}
+
+/// Lê uma lista de números entre 1 e `max`, separados por vírgula.
+fn parse_choices(value: &str, max: usize) -> Option<Vec<usize>> {
+    split_list(value)
+        .iter()
+        .map(|item| {
+            item.parse()
+                .ok()
+                .filter(|choice| (1..=max).contains(choice))
+        })
+        .collect()
+}
+
+/// Assistente interativo para criar as configurações do bandex.
+pub struct Wizard<R, W> {
+    input: R,
+    output: W,
+}
+
+impl<R: BufRead, W: Write> Wizard<R, W> {
+    pub fn new(input: R, output: W) -> Self {
+        Wizard { input, output }
+    }
+
+    /// Faz uma pergunta e retorna a resposta, sem os espaços das pontas.
+    fn ask(&mut self, question: &str) -> Result<String, Error> {
+        write!(self.output, "{question} ")?;
+        self.output.flush()?;
+
+        let mut answer = String::new();
+        if self.input.read_line(&mut answer)? == 0 {
+            return Err(Error::new(
+                ErrorKind::UnexpectedEof,
+                "A configuração foi interrompida antes de terminar",
+            ));
+        }
+
+        Ok(answer.trim().to_string())
+    }
+
+    /// Repete uma pergunta até que a resposta seja válida para a função `parse`.
+    fn ask_until<T>(
+        &mut self,
+        question: &str,
+        parse: impl Fn(&str) -> Option<T>,
+    ) -> Result<T, Error> {
+        loop {
+            let answer = self.ask(question)?;
+            match parse(&answer) {
+                Some(value) => return Ok(value),
+                None => writeln!(self.output, "   Resposta inválida, tente novamente.")?,
+            }
+        }
+    }
+
+    /// Pergunta o campus e retorna os IDs dos restaurantes que devem ser procurados.
+    fn ask_campus(&mut self) -> Result<Vec<RestaurantID>, Error> {
+        writeln!(self.output, "Campus:")?;
+        for (index, (name, _)) in CAMPUSES.iter().enumerate() {
+            writeln!(self.output, "   {}) {name}", index + 1)?;
+        }
+        writeln!(self.output, "   {}) Outro", CAMPUSES.len() + 1)?;
+
+        let choice = self.ask_until("Escolha o campus:", |answer| {
+            answer
+                .parse()
+                .ok()
+                .filter(|choice| (1..=CAMPUSES.len() + 1).contains(choice))
+        })?;
+
+        if let Some((_, restaurants)) = CAMPUSES.get(choice - 1) {
+            return Ok(restaurants.to_vec());
+        }
+
+        self.ask_until("IDs dos restaurantes, separados por vírgula:", |answer| {
+            let ids: Vec<RestaurantID> = split_list(answer)
+                .iter()
+                .map(|id| id.parse().ok())
+                .collect::<Option<_>>()?;
+            (!ids.is_empty()).then_some(ids)
+        })
+    }
+
+    /// Pergunta quais restaurantes, dentre os encontrados, devem ser exibidos.
+    fn ask_restaurants(
+        &mut self,
+        restaurants: &[(RestaurantID, Option<String>)],
+    ) -> Result<Vec<(RestaurantID, String)>, Error> {
+        writeln!(self.output, "\nRestaurantes encontrados:")?;
+        for (index, (id, name)) in restaurants.iter().enumerate() {
+            let name = name.as_deref().unwrap_or("nome indisponível");
+            writeln!(self.output, "   {}) {name} (Rest {id})", index + 1)?;
+        }
+
+        let choices = self.ask_until(
+            "Escolha os restaurantes, separados por vírgula (vazio para todos):",
+            |answer| {
+                if answer.is_empty() {
+                    Some((1..=restaurants.len()).collect())
+                } else {
+                    parse_choices(answer, restaurants.len()).filter(|choices| !choices.is_empty())
+                }
+            },
+        )?;
+
+        Ok(choices
+            .into_iter()
+            .map(|choice| {
+                let (id, name) = &restaurants[choice - 1];
+                let name = name.clone().unwrap_or_else(|| format!("Rest {id}"));
+                (*id, name)
+            })
+            .collect())
+    }
+
+    /// Pergunta uma lista de comidas, separadas por vírgula.
+    fn ask_foods(&mut self, question: &str) -> Result<Vec<FoodConfig>, Error> {
+        let answer = self.ask(question)?;
+
+        Ok(split_list(&answer)
+            .into_iter()
+            .map(|name| FoodConfig::new(name, None))
+            .collect())
+    }
+
+    /// Executa o assistente, retornando as configurações escolhidas.
+    ///
+    /// A função `discover` recebe os IDs dos restaurantes do campus escolhido e retorna
+    /// os restaurantes encontrados, com os seus nomes (ou `None` se o nome não foi encontrado).
+    pub async fn run(
+        &mut self,
+        discover: impl AsyncFnOnce(Vec<RestaurantID>) -> Vec<(RestaurantID, Option<String>)>,
+    ) -> Result<Config, Error> {
+        let restaurant_ids = self.ask_campus()?;
+
+        writeln!(self.output, "\nProcurando os restaurantes...")?;
+        let restaurants = discover(restaurant_ids).await;
+
+        let mut restaurants_config = Vec::new();
+        for (id, name) in self.ask_restaurants(&restaurants)? {
+            let color = self.ask_until(
+                &format!("Cor do {name} (nome ou \"r, g, b\", vazio para branco):"),
+                |answer| {
+                    if answer.is_empty() {
+                        Some(DEFAULT_COLOR)
+                    } else {
+                        parse_color(answer)
+                    }
+                },
+            )?;
+            restaurants_config.push(RestaurantConfig::new(id, color));
+        }
+
+        writeln!(self.output)?;
+        let liked_foods = self.ask_foods("Comidas favoritas, separadas por vírgula:")?;
+        let disliked_foods = self.ask_foods("Comidas não gostadas, separadas por vírgula:")?;
+
+        Ok(Config {
+            restaurants: restaurants_config,
+            liked_foods,
+            disliked_foods,
+            diet: DietConfig::default(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_color() {
+        assert_eq!(parse_color("Blue"), Some(Color::Blue));
+        assert_eq!(parse_color("bright red"), Some(Color::BrightRed));
+        assert_eq!(
+            parse_color("187, 35, 51"),
+            Some(Color::TrueColor {
+                r: 187,
+                g: 35,
+                b: 51
+            })
+        );
+        assert_eq!(
+            parse_color("[1,2,3]"),
+            Some(Color::TrueColor { r: 1, g: 2, b: 3 })
+        );
+        assert_eq!(parse_color("azul"), None);
+        assert_eq!(parse_color("1, 2"), None);
+        assert_eq!(parse_color("1, 2, 300"), None);
+    }
+
+    #[test]
+    fn test_parse_choices() {
+        assert_eq!(parse_choices("1, 3", 3), Some(vec![1, 3]));
+        assert_eq!(parse_choices("0", 3), None);
+        assert_eq!(parse_choices("4", 3), None);
+        assert_eq!(parse_choices("um", 3), None);
+    }
+
+    #[tokio::test]
+    async fn test_wizard_run() {
+        let input = "9\n1\n2, 1\nyellow\nroxo\n240, 218, 94\npudim, brigadeiro\n\n";
+        let mut output = Vec::new();
+        let mut wizard = Wizard::new(input.as_bytes(), &mut output);
+
+        let config = wizard
+            .run(async |ids| {
+                assert_eq!(ids, vec![6, 7, 8, 9]);
+                vec![(6, Some("Restaurante Central".to_string())), (8, None)]
+            })
+            .await
+            .unwrap();
+
+        assert_eq!(
+            config.restaurants,
+            vec![
+                RestaurantConfig::new(8, Color::Yellow),
+                RestaurantConfig::new(
+                    6,
+                    Color::TrueColor {
+                        r: 240,
+                        g: 218,
+                        b: 94
+                    }
+                ),
+            ]
+        );
+        assert_eq!(
+            config.liked_foods,
+            vec![
+                FoodConfig::new("pudim".to_string(), None),
+                FoodConfig::new("brigadeiro".to_string(), None),
+            ]
+        );
+        assert!(config.disliked_foods.is_empty());
+
+        let output = String::from_utf8(output).unwrap();
+        assert!(output.contains("1) Restaurante Central (Rest 6)"));
+        assert!(output.contains("2) nome indisponível (Rest 8)"));
+        assert!(output.contains("Resposta inválida"));
+    }
+
+    #[tokio::test]
+    async fn test_wizard_other_campus_and_eof() {
+        let input = "4\n20, 21\n2\n\n";
+        let mut output = Vec::new();
+        let mut wizard = Wizard::new(input.as_bytes(), &mut output);
+
+        let result = wizard
+            .run(async |ids| {
+                assert_eq!(ids, vec![20, 21]);
+                ids.into_iter().map(|id| (id, None)).collect()
+            })
+            .await;
+
+        // A entrada termina antes das perguntas sobre as comidas
+        assert_eq!(result.unwrap_err().kind(), ErrorKind::UnexpectedEof);
+    }
+}